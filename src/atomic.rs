@@ -0,0 +1,86 @@
+use crate::model::BookmarkError;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Writes `path` by filling a sibling temp file and renaming it over the target,
+/// so a crash or failed write never leaves a truncated file behind.
+pub(crate) fn write_atomic<F>(path: &Path, write: F) -> Result<(), BookmarkError>
+where
+    F: FnOnce(&mut BufWriter<File>) -> Result<(), BookmarkError>,
+{
+    let temp_path = temp_path_for(path);
+    let result = write_temp(&temp_path, write).and_then(|()| {
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+fn write_temp<F>(temp_path: &Path, write: F) -> Result<(), BookmarkError>
+where
+    F: FnOnce(&mut BufWriter<File>) -> Result<(), BookmarkError>,
+{
+    let mut writer = BufWriter::new(File::create(temp_path)?);
+    write(&mut writer)?;
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    Ok(())
+}
+
+fn temp_path_for(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{file_name}.tmp-{}", process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+    use std::io;
+
+    #[test]
+    fn replaces_target_contents() {
+        let dir = temp_dir("bookmark-atomic");
+        let path = dir.join("report.yml");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, |writer| {
+            writer.write_all(b"new")?;
+            Ok(())
+        })
+        .expect("write");
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert!(!temp_path_for(&path).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_write_keeps_original_and_removes_temp_file() {
+        let dir = temp_dir("bookmark-atomic");
+        let path = dir.join("Bookmarks");
+        fs::write(&path, "original").unwrap();
+
+        let result = write_atomic(&path, |writer| {
+            writer.write_all(b"partial")?;
+            Err(BookmarkError::Io(io::Error::other("serialization failed")))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+        assert!(!temp_path_for(&path).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
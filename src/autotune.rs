@@ -0,0 +1,151 @@
+use std::sync::{Condvar, Mutex, PoisonError};
+
+const WINDOW: usize = 20;
+const BACK_OFF_ERROR_RATE: f64 = 0.5;
+const RAMP_UP_ERROR_RATE: f64 = 0.1;
+
+/// Additive-increase / multiplicative-decrease controller: halves the concurrency
+/// limit when the recent error rate spikes and adds one slot back once it recovers.
+#[derive(Debug)]
+pub(crate) struct AdaptiveConcurrency {
+    min: usize,
+    max: usize,
+    limit: usize,
+    recent: Vec<bool>,
+}
+
+impl AdaptiveConcurrency {
+    pub(crate) fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            min: 1,
+            max,
+            limit: max,
+            recent: Vec::with_capacity(WINDOW),
+        }
+    }
+
+    pub(crate) fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Records one completed check and returns the (possibly adjusted) limit.
+    pub(crate) fn record(&mut self, failed: bool) -> usize {
+        self.recent.push(failed);
+        if self.recent.len() < WINDOW {
+            return self.limit;
+        }
+
+        let errors = self.recent.iter().filter(|&&failed| failed).count();
+        let error_rate = errors as f64 / self.recent.len() as f64;
+        self.recent.clear();
+
+        if error_rate >= BACK_OFF_ERROR_RATE {
+            self.limit = (self.limit / 2).max(self.min);
+        } else if error_rate <= RAMP_UP_ERROR_RATE {
+            self.limit = (self.limit + 1).min(self.max);
+        }
+
+        self.limit
+    }
+}
+
+/// Blocks workers while more checks are in flight than the controller allows.
+pub(crate) struct ConcurrencyGate {
+    state: Mutex<GateState>,
+    released: Condvar,
+}
+
+struct GateState {
+    controller: AdaptiveConcurrency,
+    active: usize,
+}
+
+impl ConcurrencyGate {
+    pub(crate) fn new(max: usize) -> Self {
+        Self {
+            state: Mutex::new(GateState {
+                controller: AdaptiveConcurrency::new(max),
+                active: 0,
+            }),
+            released: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn acquire(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        while state.active >= state.controller.limit() {
+            state = self
+                .released
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        state.active += 1;
+    }
+
+    pub(crate) fn release(&self, failed: bool) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.active -= 1;
+        state.controller.record(failed);
+        self.released.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(controller: &mut AdaptiveConcurrency, errors: usize) -> usize {
+        let mut limit = controller.limit();
+        for idx in 0..WINDOW {
+            limit = controller.record(idx < errors);
+        }
+        limit
+    }
+
+    #[test]
+    fn starts_at_maximum_concurrency() {
+        assert_eq!(AdaptiveConcurrency::new(8).limit(), 8);
+        assert_eq!(AdaptiveConcurrency::new(0).limit(), 1);
+    }
+
+    #[test]
+    fn high_error_rate_halves_concurrency_down_to_one() {
+        let mut controller = AdaptiveConcurrency::new(8);
+        assert_eq!(feed(&mut controller, WINDOW), 4);
+        assert_eq!(feed(&mut controller, WINDOW), 2);
+        assert_eq!(feed(&mut controller, WINDOW), 1);
+        assert_eq!(feed(&mut controller, WINDOW), 1);
+    }
+
+    #[test]
+    fn recovery_ramps_back_up_to_maximum() {
+        let mut controller = AdaptiveConcurrency::new(4);
+        feed(&mut controller, WINDOW);
+        assert_eq!(controller.limit(), 2);
+
+        assert_eq!(feed(&mut controller, 0), 3);
+        assert_eq!(feed(&mut controller, 1), 4);
+        assert_eq!(feed(&mut controller, 0), 4);
+    }
+
+    #[test]
+    fn moderate_error_rate_holds_steady() {
+        let mut controller = AdaptiveConcurrency::new(6);
+        assert_eq!(feed(&mut controller, WINDOW / 4), 6);
+    }
+
+    #[test]
+    fn gate_tracks_active_checks() {
+        let gate = ConcurrencyGate::new(2);
+        gate.acquire();
+        gate.acquire();
+        gate.release(false);
+        gate.acquire();
+        gate.release(false);
+        gate.release(false);
+
+        let state = gate.state.lock().unwrap();
+        assert_eq!(state.active, 0);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::model::BookmarkError;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Backups are named `<Bookmarks file>-<UTC timestamp>.bak`.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H-%M-%S";
+const BACKUP_EXTENSION: &str = ".bak";
+const DEFAULT_FILE_NAME: &str = "Bookmarks";
+
+/// A backup of a Bookmarks file and when it was taken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Backup {
+    pub(crate) path: PathBuf,
+    pub(crate) created: DateTime<Utc>,
+}
+
+/// Copies `bookmarks_file` to a timestamped backup, next to it or in `backup_dir`.
+pub(crate) fn create_backup(
+    bookmarks_file: &Path,
+    backup_dir: Option<&Path>,
+) -> Result<PathBuf, BookmarkError> {
+    let timestamp = Utc::now().format(TIMESTAMP_FORMAT);
+    let backup_name = format!(
+        "{}-{timestamp}{BACKUP_EXTENSION}",
+        file_name(bookmarks_file)
+    );
+    let backup_path = match backup_dir {
+        Some(dir) => {
+            fs::create_dir_all(dir)?;
+            dir.join(backup_name)
+        }
+        None => bookmarks_file.with_file_name(backup_name),
+    };
+    fs::copy(bookmarks_file, &backup_path)?;
+    Ok(backup_path)
+}
+
+/// Backups of `bookmarks_file` found in `dir`, newest first. A missing directory
+/// simply has no backups.
+pub(crate) fn list_backups(
+    bookmarks_file: &Path,
+    dir: &Path,
+) -> Result<Vec<Backup>, BookmarkError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    let paths = entries.filter_map(Result::ok).map(|entry| entry.path());
+    Ok(collect_backups(&file_name(bookmarks_file), paths))
+}
+
+fn collect_backups(file_name: &str, paths: impl IntoIterator<Item = PathBuf>) -> Vec<Backup> {
+    let prefix = format!("{file_name}-");
+    let mut backups: Vec<Backup> = paths
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let timestamp = name.strip_prefix(&prefix)?.strip_suffix(BACKUP_EXTENSION)?;
+            let created = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+                .ok()?
+                .and_utc();
+            Some(Backup { path, created })
+        })
+        .collect();
+
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created));
+    backups
+}
+
+/// Describes how long before `now` a backup was taken, e.g. "3 days ago".
+pub(crate) fn format_age(created: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - created).num_seconds().max(0);
+    let (value, unit) = match seconds {
+        0..60 => return "just now".to_string(),
+        60..3_600 => (seconds / 60, "minute"),
+        3_600..86_400 => (seconds / 3_600, "hour"),
+        _ => (seconds / 86_400, "day"),
+    };
+    let plural = if value == 1 { "" } else { "s" };
+    format!("{value} {unit}{plural} ago")
+}
+
+fn file_name(bookmarks_file: &Path) -> String {
+    bookmarks_file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| DEFAULT_FILE_NAME.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_path;
+    use chrono::TimeZone;
+
+    #[test]
+    fn backups_are_listed_newest_first() {
+        let paths = [
+            "Bookmarks-2024-01-05T09-00-00.bak",
+            "Bookmarks-2024-03-01T12-30-00.bak",
+            "Bookmarks",
+            "Bookmarks-2023-12-31T23-59-59.bak",
+            "Bookmarks-not-a-date.bak",
+            "Other-2024-06-01T00-00-00.bak",
+        ]
+        .map(|name| PathBuf::from("/profile").join(name));
+
+        let names: Vec<String> = collect_backups("Bookmarks", paths)
+            .into_iter()
+            .map(|backup| {
+                backup
+                    .path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+            })
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "Bookmarks-2024-03-01T12-30-00.bak",
+                "Bookmarks-2024-01-05T09-00-00.bak",
+                "Bookmarks-2023-12-31T23-59-59.bak",
+            ]
+        );
+    }
+
+    #[test]
+    fn created_backups_are_found_again() {
+        let dir = temp_path("bookmark-checker-backups");
+        fs::create_dir_all(&dir).unwrap();
+        let bookmarks = dir.join("Bookmarks");
+        fs::write(&bookmarks, "{}").unwrap();
+
+        let path = create_backup(&bookmarks, None).expect("backup");
+        let backups = list_backups(&bookmarks, &dir).expect("list");
+
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].path, path);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn ages_are_human_readable() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        let ago = |seconds| format_age(now - chrono::Duration::seconds(seconds), now);
+
+        assert_eq!(ago(5), "just now");
+        assert_eq!(ago(60), "1 minute ago");
+        assert_eq!(ago(2 * 3_600 + 59), "2 hours ago");
+        assert_eq!(ago(3 * 86_400), "3 days ago");
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+const SEQUENCE: u8 = 0x30;
+const UTC_TIME: u8 = 0x17;
+const GENERALIZED_TIME: u8 = 0x18;
+/// `[0] EXPLICIT`, wrapping the optional version at the start of a certificate body.
+const VERSION: u8 = 0xa0;
+const TIME_FORMAT: &str = "%Y%m%d%H%M%SZ";
+
+/// When a DER-encoded X.509 certificate stops being valid (its `notAfter`).
+///
+/// Only walks the fields in front of the validity period; anything unexpected
+/// yields `None` rather than a guess.
+pub(crate) fn not_after(der: &[u8]) -> Option<DateTime<Utc>> {
+    let (certificate, _) = expect_element(der, SEQUENCE)?;
+    let (mut body, _) = expect_element(certificate, SEQUENCE)?;
+    if body.first() == Some(&VERSION) {
+        body = read_element(body)?.2;
+    }
+    // The serial number, signature algorithm and issuer precede the validity.
+    for _ in 0..3 {
+        body = read_element(body)?.2;
+    }
+
+    let (validity, _) = expect_element(body, SEQUENCE)?;
+    let (_, _, after_not_before) = read_element(validity)?;
+    let (tag, time, _) = read_element(after_not_before)?;
+    parse_time(tag, time)
+}
+
+/// Days left before `not_after` when that is at most `threshold_days` away,
+/// counting partial days as whole ones.
+pub(crate) fn days_left_within(
+    not_after: DateTime<Utc>,
+    now: DateTime<Utc>,
+    threshold_days: u32,
+) -> Option<i64> {
+    let remaining = not_after - now;
+    let days = remaining.num_days() + i64::from(remaining.num_seconds() % 86_400 > 0);
+    (days <= i64::from(threshold_days)).then_some(days)
+}
+
+fn expect_element(data: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (found, contents, rest) = read_element(data)?;
+    (found == tag).then_some((contents, rest))
+}
+
+/// Splits the first element off `data` as its tag, its contents and what follows.
+fn read_element(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, data) = data.split_first()?;
+    let (&first, data) = data.split_first()?;
+
+    let (length, data) = if first < 0x80 {
+        (usize::from(first), data)
+    } else {
+        let count = usize::from(first & 0x7f);
+        if count == 0 || count > size_of::<u32>() || data.len() < count {
+            return None;
+        }
+        let (bytes, data) = data.split_at(count);
+        let length = bytes
+            .iter()
+            .fold(0usize, |length, &byte| (length << 8) | usize::from(byte));
+        (length, data)
+    };
+
+    (data.len() >= length).then(|| {
+        let (contents, rest) = data.split_at(length);
+        (tag, contents, rest)
+    })
+}
+
+/// RFC 5280 times: `YYMMDDHHMMSSZ` (years 1950-2049) or `YYYYMMDDHHMMSSZ`.
+fn parse_time(tag: u8, time: &[u8]) -> Option<DateTime<Utc>> {
+    let time = std::str::from_utf8(time).ok()?;
+    let full = match tag {
+        UTC_TIME => {
+            let year: u32 = time.get(..2)?.parse().ok()?;
+            let century = if year >= 50 { "19" } else { "20" };
+            format!("{century}{time}")
+        }
+        GENERALIZED_TIME => time.to_string(),
+        _ => return None,
+    };
+    NaiveDateTime::parse_from_str(&full, TIME_FORMAT)
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn element(tag: u8, contents: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![tag];
+        if contents.len() < 0x80 {
+            encoded.push(contents.len() as u8);
+        } else {
+            encoded.extend([0x82, (contents.len() >> 8) as u8, contents.len() as u8]);
+        }
+        encoded.extend(contents);
+        encoded
+    }
+
+    /// Just enough of a certificate for `not_after` to walk: padding stands in for
+    /// the issuer so the long length form is exercised too.
+    fn certificate(not_after: (u8, &str)) -> Vec<u8> {
+        let validity = [
+            element(UTC_TIME, b"240101000000Z"),
+            element(not_after.0, not_after.1.as_bytes()),
+        ]
+        .concat();
+        let body = [
+            element(VERSION, &element(0x02, &[2])),
+            element(0x02, &[0x01, 0x23]),
+            element(SEQUENCE, &element(0x06, &[0x2a])),
+            element(SEQUENCE, &[0; 200]),
+            element(SEQUENCE, &validity),
+        ]
+        .concat();
+        element(SEQUENCE, &element(SEQUENCE, &body))
+    }
+
+    #[test]
+    fn not_after_is_read_from_either_time_format() {
+        assert_eq!(
+            not_after(&certificate((UTC_TIME, "260315120000Z"))),
+            Some(Utc.with_ymd_and_hms(2026, 3, 15, 12, 0, 0).unwrap())
+        );
+        assert_eq!(
+            not_after(&certificate((GENERALIZED_TIME, "20510101000000Z"))),
+            Some(Utc.with_ymd_and_hms(2051, 1, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(not_after(&certificate((0x04, "260315120000Z"))), None);
+        assert_eq!(not_after(&[SEQUENCE, 0x84, 0xff]), None);
+    }
+
+    #[test]
+    fn expiry_is_reported_within_the_threshold_only() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            days_left_within(now + Duration::days(30), now, 30),
+            Some(30)
+        );
+        assert_eq!(
+            days_left_within(now + Duration::days(29) + Duration::hours(1), now, 30),
+            Some(30)
+        );
+        assert_eq!(days_left_within(now + Duration::days(31), now, 30), None);
+        assert_eq!(days_left_within(now + Duration::hours(1), now, 0), None);
+        assert_eq!(days_left_within(now - Duration::days(2), now, 0), Some(-2));
+    }
+}
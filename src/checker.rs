@@ -1,113 +1,1056 @@
-use crate::{Bookmark, BookmarkError, progress::ProgressReporter};
+use crate::autotune::ConcurrencyGate;
+use crate::certificate;
+use crate::host_timeout::HostTimeouts;
+use crate::model::{Concurrency, RequestHeader, RetryKind, TimeoutPolicy};
+use crate::progress::{self, NoProgress, Progress, ProgressGroup};
+use crate::stream::ResultStream;
+use crate::{Bookmark, BookmarkError};
+use chrono::{DateTime, Utc};
+use rayon::ThreadPoolBuilder;
 use rayon::prelude::*;
-use reqwest::StatusCode;
-use reqwest::blocking::Client;
-use std::time::Duration;
+use reqwest::blocking::{Client, ClientBuilder, Response};
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue, IF_MODIFIED_SINCE};
+use reqwest::redirect::Policy;
+use reqwest::tls::TlsInfo;
+use reqwest::{Method, StatusCode, Url};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone)]
-pub(crate) struct LinkFailure {
-    pub(crate) bookmark: Bookmark,
-    pub(crate) reason: String,
-    pub(crate) kind: FailureKind,
+/// A bookmark that failed its check, with why and how it failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkFailure {
+    pub bookmark: Bookmark,
+    pub reason: FailureReason,
+    pub kind: FailureKind,
+    /// URLs the request was redirected through, in order; only filled with
+    /// `--trace-redirects`.
+    pub redirect_chain: Vec<String>,
+}
+
+/// Why a check failed, for callers that branch on the cause. `Display` gives the
+/// wording used in reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FailureReason {
+    Http(u16),
+    Timeout,
+    Dns,
+    Tls,
+    Connect,
+    TooManyRedirects,
+    Parked(String),
+    Other(String),
+}
+
+impl Display for FailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailureReason::Http(code) => {
+                let canonical = StatusCode::from_u16(*code)
+                    .ok()
+                    .and_then(|status| status.canonical_reason())
+                    .unwrap_or("Unknown");
+                write!(f, "HTTP {code} {canonical}")
+            }
+            FailureReason::Timeout => f.write_str("Timed out"),
+            FailureReason::Dns => f.write_str("DNS lookup failed"),
+            FailureReason::Tls => f.write_str("TLS error"),
+            FailureReason::Connect => f.write_str("Connection refused"),
+            FailureReason::TooManyRedirects => f.write_str("Too many redirects"),
+            FailureReason::Parked(host) => write!(f, "Redirected to parked domain {host}"),
+            FailureReason::Other(message) => f.write_str(message),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum FailureKind {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FailureKind {
+    /// 404: possibly temporary, e.g. a page mid-migration.
     NotFound,
+    /// 410: the server states the resource is permanently gone.
+    Gone,
     Unauthorized,
+    MethodNotAllowed,
     Connection,
+    /// 200 response from a domain-parking page on a different host.
+    Parked,
+    /// Redirects never settled on a page: a loop or an overly long chain.
+    Redirected,
+}
+
+impl FailureKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            FailureKind::NotFound => "not_found",
+            FailureKind::Gone => "gone",
+            FailureKind::Unauthorized => "unauthorized",
+            FailureKind::MethodNotAllowed => "method_not_allowed",
+            FailureKind::Connection => "connection_errors",
+            FailureKind::Parked => "parked",
+            FailureKind::Redirected => "redirected",
+        }
+    }
+
+    /// This kind's bit in the `--coded-exit` status: 4 for 404/410, 8 for
+    /// unauthorized, 16 for connection errors and 32 for everything else. Bits 1
+    /// and 2 are left out: statuses 1 and 2 mean an error and a usage mistake.
+    pub fn exit_bit(self) -> i32 {
+        match self {
+            FailureKind::NotFound | FailureKind::Gone => 4,
+            FailureKind::Unauthorized => 8,
+            FailureKind::Connection => 16,
+            FailureKind::MethodNotAllowed | FailureKind::Parked | FailureKind::Redirected => 32,
+        }
+    }
+}
+
+/// A bookmark that works but deserves a look. Warnings get their own report
+/// sections and never count as failures.
+#[derive(Debug, Clone)]
+pub(crate) struct LinkWarning {
+    pub(crate) bookmark: Bookmark,
+    pub(crate) reason: WarningReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WarningReason {
+    /// The TLS certificate runs out in this many days.
+    CertificateExpiring(i64),
+    /// The site works after redirecting to this URL, which could replace the bookmark's.
+    MovedTo(String),
+}
+
+impl Display for WarningReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WarningReason::CertificateExpiring(days) => {
+                write!(f, "TLS certificate expires in {days} day(s)")
+            }
+            WarningReason::MovedTo(url) => write!(f, "Redirects to {url}"),
+        }
+    }
+}
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+/// Each retry delay is stretched or shrunk by up to this fraction, so requests that
+/// failed together do not all retry at the same moment.
+const RETRY_JITTER: f64 = 0.25;
+/// Read when `--concurrency` is left at auto, as rayon users expect.
+const RAYON_THREADS_ENV: &str = "RAYON_NUM_THREADS";
+/// Parking pages put their sales pitch near the top; no need to buffer whole pages.
+const LANDING_BODY_LIMIT: u64 = 64 * 1024;
+const PARKING_MARKERS: [&str; 4] = [
+    "domain is for sale",
+    "buy this domain",
+    "this domain may be for sale",
+    "domain has expired",
+];
+
+#[derive(Debug, Clone)]
+pub(crate) struct CheckOptions {
+    /// Only send HEAD requests; a 405 is reported instead of retried with GET.
+    pub(crate) head_only: bool,
+    /// Send a HEAD first and fall back to GET unless it found a working download.
+    pub(crate) head_first: bool,
+    /// Upper bound for the whole request, including reading the body.
+    pub(crate) timeout: Duration,
+    /// Upper bound for establishing the TCP/TLS connection alone.
+    pub(crate) connect_timeout: Option<Duration>,
+    /// Upper bound for a HEAD request; defaults to half of `timeout`.
+    pub(crate) head_timeout: Option<Duration>,
+    /// Number of worker threads checking bookmarks.
+    pub(crate) concurrency: Concurrency,
+    /// Reduce concurrency when connection errors spike, and restore it on recovery.
+    pub(crate) auto_tune: bool,
+    /// Inspect successful GET responses for domain-parking pages.
+    pub(crate) check_parked: bool,
+    /// Redirects to follow before reporting "Too many redirects"; 0 returns the
+    /// redirect response itself.
+    pub(crate) max_redirects: usize,
+    /// Record the URLs each failing bookmark was redirected through.
+    pub(crate) trace_redirects: bool,
+    /// Draw progress bars while checking.
+    pub(crate) show_progress: bool,
+    /// Extra attempts for outcomes listed in `retry_kinds`.
+    pub(crate) retries: u32,
+    pub(crate) retry_kinds: Vec<RetryKind>,
+    /// Pause before the first retry; each later retry waits one more multiple.
+    pub(crate) retry_delay: Duration,
+    /// Picks the jitter applied to each retry delay; the same seed gives the same
+    /// delays.
+    pub(crate) retry_jitter_seed: u64,
+    /// Sent with every request; a later header replaces an earlier one of the same name.
+    pub(crate) headers: Vec<RequestHeader>,
+    /// With `PerHost`, requests to a host that has answered before get a limit
+    /// derived from its first response time instead of the full timeout.
+    pub(crate) timeout_policy: TimeoutPolicy,
+    /// Idle connections kept open per host for reuse; `None` keeps reqwest's default
+    /// of no limit.
+    pub(crate) pool_max_idle_per_host: Option<usize>,
+    /// Flag working HTTPS sites whose certificate expires within this many days.
+    pub(crate) ssl_expiry_days: Option<u32>,
+    /// Stop starting new checks once one bookmark has failed.
+    pub(crate) fail_fast: bool,
+    /// Stop starting new checks at this instant. A run sets it once, so every
+    /// profile it scans shares the same budget.
+    pub(crate) deadline: Option<Instant>,
+    /// Read at most this many bytes of a GET response body before hanging up; the
+    /// status has already decided the check by then.
+    pub(crate) max_response_size: Option<u64>,
+    /// Report working bookmarks that redirect, suggesting the URL they end up at.
+    pub(crate) suggest_updates: bool,
+    /// Sent as `If-Modified-Since`, so unchanged pages can answer 304 without a body.
+    pub(crate) if_modified_since: Option<DateTime<Utc>>,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            head_only: false,
+            head_first: false,
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: None,
+            head_timeout: None,
+            concurrency: Concurrency::Auto,
+            auto_tune: false,
+            check_parked: false,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            trace_redirects: false,
+            show_progress: true,
+            retries: 0,
+            retry_kinds: RetryKind::DEFAULT.to_vec(),
+            retry_delay: DEFAULT_RETRY_DELAY,
+            retry_jitter_seed: 0,
+            headers: Vec::new(),
+            timeout_policy: TimeoutPolicy::Global,
+            pool_max_idle_per_host: None,
+            ssl_expiry_days: None,
+            fail_fast: false,
+            deadline: None,
+            max_response_size: None,
+            suggest_updates: false,
+            if_modified_since: None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CheckOutcome {
+    status: Option<StatusCode>,
+    content_type: Option<String>,
+    failure: Option<LinkFailure>,
+    warning: Option<LinkWarning>,
+    bytes: u64,
+    /// Never checked because `fail_fast` or `time_limit` had already stopped the scan.
+    skipped: bool,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct CheckSummary {
+    pub(crate) failures: Vec<LinkFailure>,
+    pub(crate) warnings: Vec<LinkWarning>,
+    pub(crate) bytes_downloaded: u64,
+    /// Positions, in the input order, of bookmarks left unchecked by `fail_fast` or
+    /// `time_limit`.
+    pub(crate) skipped: Vec<usize>,
+    /// The scan stopped because `time_limit` ran out.
+    pub(crate) out_of_time: bool,
+}
+
+/// Result of probing a single URL, independent of the HTTP client in use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ProbeOutcome {
+    Response {
+        status: StatusCode,
+        bytes: u64,
+        /// The `Content-Type` header without parameters, e.g. `application/pdf`.
+        content_type: Option<String>,
+        landing: Option<LandingPage>,
+        /// When the server's certificate expires; only read with `--check-ssl-expiry`.
+        certificate_expiry: Option<DateTime<Utc>>,
+        /// The URL answered after following redirects.
+        final_url: Option<String>,
+    },
+    Failed(FailureReason),
+}
+
+/// Where a request ended up after redirects, with the start of the body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LandingPage {
+    pub(crate) url: String,
+    pub(crate) body: String,
+}
+
+pub(crate) trait UrlProbe {
+    fn probe(&self, url: &str) -> ProbeOutcome;
+
+    /// Hands over the redirects recorded for the last probe of `url`, if any.
+    fn take_redirect_chain(&self, _url: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+pub(crate) struct HttpProbe {
+    client: Client,
+    method: Method,
+    /// Try a HEAD before `method`, which is then only sent if the HEAD left doubt.
+    head_first: bool,
+    /// Override of the client's overall timeout for HEAD requests.
+    head_timeout: Duration,
+    capture_landing: bool,
+    trace: Option<RedirectTrace>,
+    host_timeouts: Option<HostTimeouts>,
+    max_response_size: Option<u64>,
+}
+
+/// Redirect chains seen by a tracing client, keyed by the URL each request started at.
+///
+/// The client follows redirects on its own, so its policy is the only place that
+/// sees intermediate URLs.
+#[derive(Debug, Clone, Default)]
+struct RedirectTrace(Arc<Mutex<HashMap<String, Vec<String>>>>);
+
+impl RedirectTrace {
+    fn policy(&self, max_redirects: usize) -> Policy {
+        let trace = self.clone();
+        let limit = redirect_policy(max_redirects);
+        Policy::custom(move |attempt| {
+            if let Some((start, hops)) = attempt.previous().split_first() {
+                let chain = hops
+                    .iter()
+                    .chain([attempt.url()])
+                    .map(ToString::to_string)
+                    .collect();
+                trace.chains().insert(start.to_string(), chain);
+            }
+            limit.redirect(attempt)
+        })
+    }
+
+    fn take(&self, url: &str) -> Vec<String> {
+        let Ok(start) = Url::parse(url) else {
+            return Vec::new();
+        };
+        self.chains().remove(start.as_str()).unwrap_or_default()
+    }
+
+    fn chains(&self) -> MutexGuard<'_, HashMap<String, Vec<String>>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl HttpProbe {
+    pub(crate) fn new(options: &CheckOptions) -> Result<Self, BookmarkError> {
+        let method = if options.head_only {
+            Method::HEAD
+        } else {
+            Method::GET
+        };
+
+        // A HEAD response carries no body, so it can be held to a tighter limit.
+        let head_timeout = options.head_timeout.unwrap_or(options.timeout / 2);
+
+        // Without redirects to follow there is no chain worth recording.
+        let trace =
+            (options.trace_redirects && options.max_redirects > 0).then(RedirectTrace::default);
+        let mut builder = client_builder(options);
+        if let Some(trace) = &trace {
+            builder = builder.redirect(trace.policy(options.max_redirects));
+        }
+
+        let host_timeouts = (options.timeout_policy == TimeoutPolicy::PerHost).then(|| {
+            HostTimeouts::new(if method == Method::HEAD {
+                head_timeout
+            } else {
+                options.timeout
+            })
+        });
+
+        Ok(Self {
+            client: builder.build().map_err(BookmarkError::HttpClientBuild)?,
+            capture_landing: options.check_parked && method == Method::GET,
+            head_first: options.head_first && method == Method::GET,
+            head_timeout,
+            method,
+            trace,
+            host_timeouts,
+            max_response_size: options.max_response_size,
+        })
+    }
+}
+
+fn media_type(response: &Response) -> Option<String> {
+    let value = response.headers().get(CONTENT_TYPE)?.to_str().ok()?;
+    let media_type = value.split(';').next()?.trim();
+    (!media_type.is_empty()).then(|| media_type.to_ascii_lowercase())
+}
+
+fn is_html(media_type: &str) -> bool {
+    matches!(media_type, "text/html" | "application/xhtml+xml")
+}
+
+/// A successful HEAD for anything but a page: there is no body worth a GET.
+fn settles_download(outcome: &ProbeOutcome) -> bool {
+    matches!(
+        outcome,
+        ProbeOutcome::Response { status, content_type: Some(media_type), .. }
+            if status.is_success() && !is_html(media_type)
+    )
+}
+
+impl UrlProbe for HttpProbe {
+    fn probe(&self, url: &str) -> ProbeOutcome {
+        if self.head_first {
+            let head = self.send(Method::HEAD, url);
+            if settles_download(&head) {
+                return head;
+            }
+        }
+        self.send(self.method.clone(), url)
+    }
+
+    fn take_redirect_chain(&self, url: &str) -> Vec<String> {
+        self.trace
+            .as_ref()
+            .map(|trace| trace.take(url))
+            .unwrap_or_default()
+    }
 }
 
-pub(crate) fn check_bookmarks(bookmarks: &[Bookmark]) -> Result<Vec<LinkFailure>, BookmarkError> {
+impl HttpProbe {
+    fn send(&self, method: Method, url: &str) -> ProbeOutcome {
+        let host = self
+            .host_timeouts
+            .as_ref()
+            .zip(Url::parse(url).ok())
+            .and_then(|(timeouts, url)| Some((timeouts, url.host_str()?.to_string())));
+
+        let is_head = method == Method::HEAD;
+        let mut request = self.client.request(method, url);
+        let timeout = match &host {
+            Some((timeouts, host)) if is_head => {
+                Some(timeouts.timeout_for(host).min(self.head_timeout))
+            }
+            Some((timeouts, host)) => Some(timeouts.timeout_for(host)),
+            None => is_head.then_some(self.head_timeout),
+        };
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+
+        let started = Instant::now();
+        let outcome = match request.send() {
+            Ok(response) => self.read_response(response, is_head),
+            Err(err) => ProbeOutcome::Failed(connection_reason(&err)),
+        };
+        // Only answers that worked say how long the host's real pages take; an
+        // instant error page would starve them.
+        if let (Some((timeouts, host)), ProbeOutcome::Response { status, .. }) = (&host, &outcome)
+            && (status.is_success() || status.is_redirection())
+        {
+            timeouts.record(host, started.elapsed());
+        }
+        outcome
+    }
+
+    /// Turns a response into an outcome, reading the body only where it is needed.
+    fn read_response(&self, mut response: Response, is_head: bool) -> ProbeOutcome {
+        let status = response.status();
+        let certificate_expiry = response
+            .extensions()
+            .get::<TlsInfo>()
+            .and_then(TlsInfo::peer_certificate)
+            .and_then(certificate::not_after);
+        let final_url = Some(response.url().to_string());
+        let content_length = response.content_length();
+        let content_type = media_type(&response);
+        // Only pages can be parking pages, and downloads (PDFs, archives, ...)
+        // are not worth pulling down just to count their bytes.
+        let is_page = content_type.as_deref().is_none_or(is_html);
+
+        if self.capture_landing && !is_head && is_page {
+            let url = response.url().to_string();
+            let mut body = Vec::new();
+            let limit = self
+                .max_response_size
+                .map_or(LANDING_BODY_LIMIT, |max| max.min(LANDING_BODY_LIMIT));
+            let read = (&mut response)
+                .take(limit)
+                .read_to_end(&mut body)
+                .unwrap_or(0) as u64;
+            return ProbeOutcome::Response {
+                status,
+                bytes: content_length.unwrap_or(read),
+                content_type,
+                landing: Some(LandingPage {
+                    url,
+                    body: String::from_utf8_lossy(&body).into_owned(),
+                }),
+                certificate_expiry,
+                final_url,
+            };
+        }
+
+        let bytes = match content_length {
+            Some(length) => length,
+            None if !is_head && is_page => {
+                let limit = self.max_response_size.unwrap_or(u64::MAX);
+                io::copy(&mut (&mut response).take(limit), &mut io::sink()).unwrap_or(0)
+            }
+            None => 0,
+        };
+        ProbeOutcome::Response {
+            status,
+            bytes,
+            content_type,
+            landing: None,
+            certificate_expiry,
+            final_url,
+        }
+    }
+}
+
+pub(crate) fn check_bookmarks(
+    bookmarks: &[Bookmark],
+    options: &CheckOptions,
+    stream: Option<&ResultStream>,
+) -> Result<CheckSummary, BookmarkError> {
     if bookmarks.is_empty() {
-        return Ok(Vec::new());
+        return Ok(CheckSummary::default());
     }
 
-    let client = build_client()?;
-    let total = bookmarks.len();
+    with_http_probe(options, |probe| {
+        check_bookmarks_with(bookmarks, probe, options, stream)
+    })
+}
+
+/// Like `check_bookmarks`, but draws a single `label`led bar in `group` so several
+/// profiles can be checked at once.
+pub(crate) fn check_bookmarks_in_group(
+    bookmarks: &[Bookmark],
+    options: &CheckOptions,
+    stream: Option<&ResultStream>,
+    group: &ProgressGroup,
+    label: &str,
+) -> Result<CheckSummary, BookmarkError> {
+    if bookmarks.is_empty() {
+        return Ok(CheckSummary::default());
+    }
+
+    with_http_probe(options, |probe| {
+        let progress: Box<dyn Progress> = if options.show_progress {
+            group.start(bookmarks.len(), label)
+        } else {
+            Box::new(NoProgress)
+        };
+        let summary =
+            check_bookmarks_reporting(bookmarks, probe, options, stream, progress.as_ref());
+        progress.finish();
+        summary
+    })
+}
+
+/// Runs `check` with an HTTP probe on a dedicated pool sized by `resolve_workers`.
+///
+/// The probe is built once per scan and borrowed by every rayon task, so all checks
+/// share one `Client` and its connection pool; bookmarks on the same host reuse
+/// idle connections instead of opening new ones.
+fn with_http_probe<F>(options: &CheckOptions, check: F) -> Result<CheckSummary, BookmarkError>
+where
+    F: FnOnce(&HttpProbe) -> CheckSummary + Send,
+{
+    let probe = HttpProbe::new(options)?;
+    let workers = resolve_workers(
+        options.concurrency,
+        env::var(RAYON_THREADS_ENV).ok().as_deref(),
+        thread::available_parallelism().map_or(1, NonZeroUsize::get),
+    );
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(workers)
+        .build()
+        .map_err(BookmarkError::ThreadPoolBuild)?;
+    Ok(pool.install(|| check(&probe)))
+}
+
+/// Worker threads for a scan: `--concurrency` when given, then a positive
+/// `RAYON_NUM_THREADS` (capped at `Concurrency::MAX`), then one per CPU.
+fn resolve_workers(concurrency: Concurrency, env_value: Option<&str>, cpus: usize) -> usize {
+    match concurrency {
+        Concurrency::Fixed(workers) => workers,
+        Concurrency::Auto => env_value
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .filter(|&threads| threads > 0)
+            .map_or(cpus, |threads| threads.min(Concurrency::MAX)),
+    }
+}
+
+pub(crate) fn check_bookmarks_with<P>(
+    bookmarks: &[Bookmark],
+    probe: &P,
+    options: &CheckOptions,
+    stream: Option<&ResultStream>,
+) -> CheckSummary
+where
+    P: UrlProbe + Sync,
+{
+    if bookmarks.is_empty() {
+        return CheckSummary::default();
+    }
+
+    let progress: Box<dyn Progress> = if options.show_progress {
+        progress::start(
+            bookmarks.len(),
+            rayon::current_num_threads(),
+            "Checking bookmarks",
+        )
+    } else {
+        Box::new(NoProgress)
+    };
+    let summary = check_bookmarks_reporting(bookmarks, probe, options, stream, progress.as_ref());
+    progress.finish();
+    summary
+}
+
+fn check_bookmarks_reporting<P>(
+    bookmarks: &[Bookmark],
+    probe: &P,
+    options: &CheckOptions,
+    stream: Option<&ResultStream>,
+    progress: &dyn Progress,
+) -> CheckSummary
+where
+    P: UrlProbe + Sync,
+{
     let worker_count = rayon::current_num_threads();
-    let reporter = ProgressReporter::new(total, worker_count, "Checking bookmarks");
-    let handle = reporter.handle();
-
-    let failures: Vec<LinkFailure> = bookmarks
-        .par_iter()
-        .map_init(
-            || handle.clone(),
-            |progress, bookmark| {
-                if let Some(idx) = rayon::current_thread_index() {
-                    progress.worker_start(idx, format!("{} -> {}", bookmark.name, bookmark.url));
-                }
+    let stop = AtomicBool::new(false);
+    let out_of_time = AtomicBool::new(false);
+
+    let check = |bookmark: &Bookmark, idx: Option<usize>| {
+        if !stop.load(Ordering::Relaxed) && past_deadline(options.deadline, Instant::now()) {
+            out_of_time.store(true, Ordering::Relaxed);
+            stop.store(true, Ordering::Relaxed);
+        }
+        if stop.load(Ordering::Relaxed) {
+            return CheckOutcome {
+                skipped: true,
+                ..CheckOutcome::default()
+            };
+        }
+
+        if let Some(idx) = idx {
+            progress.worker_start(idx, &format!("{} -> {}", bookmark.name, bookmark.url));
+        }
+
+        let result = check_single(bookmark, probe, options);
+        if options.fail_fast && result.failure.is_some() {
+            stop.store(true, Ordering::Relaxed);
+        }
+
+        if let Some(stream) = stream {
+            let kind = result.failure.as_ref().map(|failure| failure.kind);
+            stream.record(
+                bookmark,
+                result.status,
+                result.content_type.as_deref(),
+                kind,
+            );
+        }
+
+        progress.inc();
+
+        if let Some(idx) = idx {
+            progress.worker_finish(idx);
+        }
+
+        result
+    };
+
+    let results: Vec<CheckOutcome> = if options.auto_tune {
+        check_with_adaptive_concurrency(bookmarks, worker_count, |bookmark, idx| {
+            check(bookmark, Some(idx))
+        })
+    } else {
+        bookmarks
+            .par_iter()
+            .map(|bookmark| check(bookmark, rayon::current_thread_index()))
+            .collect()
+    };
 
-                let result = check_single(bookmark, &client);
+    let mut summary = CheckSummary {
+        out_of_time: out_of_time.into_inner(),
+        ..CheckSummary::default()
+    };
+    for (position, outcome) in results.into_iter().enumerate() {
+        if outcome.skipped {
+            summary.skipped.push(position);
+        }
+        summary.bytes_downloaded += outcome.bytes;
+        summary.failures.extend(outcome.failure);
+        summary.warnings.extend(outcome.warning);
+    }
+    summary
+}
+
+/// Whether the `--limit-time` deadline has passed at `now`.
+fn past_deadline(deadline: Option<Instant>, now: Instant) -> bool {
+    deadline.is_some_and(|deadline| now >= deadline)
+}
+
+/// Runs checks from a shared queue on `worker_count` workers, admitting only as many
+/// concurrent requests as the adaptive controller currently allows.
+fn check_with_adaptive_concurrency<F>(
+    bookmarks: &[Bookmark],
+    worker_count: usize,
+    check: F,
+) -> Vec<CheckOutcome>
+where
+    F: Fn(&Bookmark, usize) -> CheckOutcome + Sync,
+{
+    let gate = ConcurrencyGate::new(worker_count);
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(bookmarks.len()));
+
+    rayon::scope(|scope| {
+        for idx in 0..worker_count.max(1) {
+            let (gate, next, results, check) = (&gate, &next, &results, &check);
+            scope.spawn(move |_| {
+                loop {
+                    let position = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(bookmark) = bookmarks.get(position) else {
+                        break;
+                    };
 
-                progress.inc();
+                    gate.acquire();
+                    let outcome = check(bookmark, idx);
+                    let failed = outcome
+                        .failure
+                        .as_ref()
+                        .is_some_and(|failure| failure.kind == FailureKind::Connection);
+                    gate.release(failed);
 
-                if let Some(idx) = rayon::current_thread_index() {
-                    progress.worker_finish(idx);
+                    results
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .push((position, outcome));
                 }
+            });
+        }
+    });
 
-                result
-            },
-        )
-        .filter_map(|failure| failure)
-        .collect();
+    let mut results = results.into_inner().unwrap_or_else(PoisonError::into_inner);
+    results.sort_by_key(|(position, _)| *position);
+    results.into_iter().map(|(_, outcome)| outcome).collect()
+}
 
-    reporter.finish();
+fn client_builder(options: &CheckOptions) -> ClientBuilder {
+    let mut headers = HeaderMap::new();
+    for header in &options.headers {
+        headers.insert(header.name().clone(), header.value().clone());
+    }
+    // An explicit `--header 'If-Modified-Since: ...'` wins.
+    if let Some(since) = options.if_modified_since.and_then(http_date) {
+        headers.entry(IF_MODIFIED_SINCE).or_insert(since);
+    }
+    let mut builder = Client::builder()
+        .timeout(options.timeout)
+        .redirect(redirect_policy(options.max_redirects))
+        .default_headers(headers);
 
-    Ok(failures)
+    if let Some(max_idle) = options.pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max_idle);
+    }
+    if options.ssl_expiry_days.is_some() {
+        builder = builder.tls_info(true);
+    }
+
+    match options.connect_timeout {
+        Some(connect_timeout) => builder.connect_timeout(connect_timeout),
+        None => builder,
+    }
 }
 
-fn build_client() -> Result<Client, BookmarkError> {
-    Client::builder()
-        .timeout(Duration::from_secs(10))
-        .redirect(reqwest::redirect::Policy::limited(10))
-        .build()
-        .map_err(BookmarkError::HttpClientBuild)
+/// `time` in the IMF-fixdate form HTTP date headers use.
+fn http_date(time: DateTime<Utc>) -> Option<HeaderValue> {
+    HeaderValue::from_str(&time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()).ok()
 }
 
-fn check_single(bookmark: &Bookmark, client: &Client) -> Option<LinkFailure> {
-    match client.get(&bookmark.url).send() {
-        Ok(response) => match response.status() {
-            StatusCode::NOT_FOUND | StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
-                Some(LinkFailure::from_status(bookmark, response.status()))
+fn redirect_policy(max_redirects: usize) -> Policy {
+    match max_redirects {
+        0 => Policy::none(),
+        max => Policy::limited(max),
+    }
+}
+
+fn check_single<P: UrlProbe>(
+    bookmark: &Bookmark,
+    probe: &P,
+    options: &CheckOptions,
+) -> CheckOutcome {
+    let mut outcome = probe_single(bookmark, probe, options);
+    if options.trace_redirects {
+        let chain = probe.take_redirect_chain(&bookmark.url);
+        if let Some(failure) = outcome.failure.as_mut() {
+            failure.redirect_chain = chain;
+        }
+    }
+    outcome
+}
+
+fn probe_single<P: UrlProbe>(
+    bookmark: &Bookmark,
+    probe: &P,
+    options: &CheckOptions,
+) -> CheckOutcome {
+    match probe_with_retries(&bookmark.url, probe, options) {
+        ProbeOutcome::Response {
+            status,
+            bytes,
+            content_type,
+            landing,
+            certificate_expiry,
+            final_url,
+        } => {
+            let failure = if is_failure_status(status, options) {
+                Some(LinkFailure::from_status(bookmark, status))
+            } else {
+                landing
+                    .filter(|_| options.check_parked && status.is_success())
+                    .and_then(|page| parked_host(bookmark, &page))
+                    .map(|host| LinkFailure::parked(bookmark, &host))
+            };
+            let warning = if failure.is_some() || !is_healthy(status) {
+                None
+            } else {
+                options
+                    .ssl_expiry_days
+                    .zip(certificate_expiry)
+                    .and_then(|(days, expiry)| {
+                        certificate::days_left_within(expiry, Utc::now(), days)
+                    })
+                    .map(WarningReason::CertificateExpiring)
+                    .or_else(|| {
+                        final_url
+                            .filter(|url| options.suggest_updates && moved_from(bookmark, url))
+                            .map(WarningReason::MovedTo)
+                    })
+                    .map(|reason| LinkWarning {
+                        bookmark: bookmark.clone(),
+                        reason,
+                    })
+            };
+
+            CheckOutcome {
+                status: Some(status),
+                content_type,
+                failure,
+                warning,
+                bytes,
+                skipped: false,
             }
+        }
+        ProbeOutcome::Failed(reason) => CheckOutcome {
+            status: None,
+            content_type: None,
+            failure: Some(LinkFailure::from_error(bookmark, reason)),
+            warning: None,
+            bytes: 0,
+            skipped: false,
+        },
+    }
+}
+
+/// Probes `url`, trying again while the outcome is one of the retry kinds and
+/// attempts remain.
+fn probe_with_retries<P: UrlProbe>(url: &str, probe: &P, options: &CheckOptions) -> ProbeOutcome {
+    let mut attempt = 0;
+    loop {
+        let outcome = probe.probe(url);
+        let retryable =
+            retry_kind(&outcome).is_some_and(|kind| options.retry_kinds.contains(&kind));
+        if !retryable || attempt >= options.retries {
+            return outcome;
+        }
+
+        attempt += 1;
+        thread::sleep(jittered_delay(
+            options.retry_delay * attempt,
+            url,
+            attempt,
+            options.retry_jitter_seed,
+        ));
+    }
+}
+
+/// `delay` scaled by a factor within `RETRY_JITTER` of 1. The factor depends on the
+/// URL and attempt as well as the seed, so bookmarks retrying together spread out.
+fn jittered_delay(delay: Duration, url: &str, attempt: u32, seed: u64) -> Duration {
+    let mut hasher = DefaultHasher::new();
+    (seed, url, attempt).hash(&mut hasher);
+    // The top 53 bits, as a fraction in [0, 1).
+    let unit = (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64;
+    delay.mul_f64(1.0 - RETRY_JITTER + 2.0 * RETRY_JITTER * unit)
+}
+
+fn retry_kind(outcome: &ProbeOutcome) -> Option<RetryKind> {
+    match outcome {
+        ProbeOutcome::Response { status, .. } if status.is_server_error() => {
+            Some(RetryKind::ServerError)
+        }
+        ProbeOutcome::Response { status, .. } => match *status {
+            StatusCode::NOT_FOUND => Some(RetryKind::NotFound),
+            StatusCode::GONE => Some(RetryKind::Gone),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Some(RetryKind::Unauthorized),
             _ => None,
         },
-        Err(err) => Some(LinkFailure::from_error(bookmark, &err)),
+        // The redirect chain is deterministic; asking again ends the same way.
+        ProbeOutcome::Failed(FailureReason::TooManyRedirects) => None,
+        ProbeOutcome::Failed(_) => Some(RetryKind::Connection),
+    }
+}
+
+/// Returns the landing host when a request left the bookmark's host and ended on a
+/// page advertising the domain for sale.
+fn parked_host(bookmark: &Bookmark, page: &LandingPage) -> Option<String> {
+    let original = bookmark.host()?;
+    let landing = Url::parse(&page.url).ok()?;
+    let host = landing.host_str()?;
+
+    if original == host {
+        return None;
+    }
+
+    let body = page.body.to_ascii_lowercase();
+    PARKING_MARKERS
+        .iter()
+        .any(|marker| body.contains(marker))
+        .then(|| host.to_string())
+}
+
+/// Whether `final_url` is somewhere other than the bookmark's URL, once both are
+/// written the way the URL standard spells them.
+fn moved_from(bookmark: &Bookmark, final_url: &str) -> bool {
+    Url::parse(&bookmark.url).is_ok_and(|original| original.as_str() != final_url)
+}
+
+/// A success, or a 304 answering an `If-Modified-Since` request: the page is
+/// still there and simply has not changed.
+fn is_healthy(status: StatusCode) -> bool {
+    status.is_success() || status == StatusCode::NOT_MODIFIED
+}
+
+fn is_failure_status(status: StatusCode, options: &CheckOptions) -> bool {
+    match status {
+        StatusCode::NOT_MODIFIED => false,
+        StatusCode::NOT_FOUND
+        | StatusCode::GONE
+        | StatusCode::UNAUTHORIZED
+        | StatusCode::FORBIDDEN => true,
+        StatusCode::METHOD_NOT_ALLOWED => options.head_only,
+        _ => false,
     }
 }
 
 impl LinkFailure {
     fn from_status(bookmark: &Bookmark, status: StatusCode) -> Self {
-        let canonical = status.canonical_reason().unwrap_or("Unknown");
         Self {
             bookmark: bookmark.clone(),
-            reason: format!("HTTP {} {}", status.as_u16(), canonical),
+            reason: FailureReason::Http(status.as_u16()),
             kind: match status {
                 StatusCode::NOT_FOUND => FailureKind::NotFound,
+                StatusCode::GONE => FailureKind::Gone,
                 StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => FailureKind::Unauthorized,
+                StatusCode::METHOD_NOT_ALLOWED => FailureKind::MethodNotAllowed,
                 _ => FailureKind::Connection,
             },
+            redirect_chain: Vec::new(),
         }
     }
 
-    fn from_error(bookmark: &Bookmark, err: &reqwest::Error) -> Self {
+    fn parked(bookmark: &Bookmark, host: &str) -> Self {
         Self {
             bookmark: bookmark.clone(),
-            reason: format!("Request failed: {err}"),
-            kind: FailureKind::Connection,
+            reason: FailureReason::Parked(host.to_string()),
+            kind: FailureKind::Parked,
+            redirect_chain: Vec::new(),
         }
     }
+
+    fn from_error(bookmark: &Bookmark, reason: FailureReason) -> Self {
+        let kind = match reason {
+            FailureReason::TooManyRedirects => FailureKind::Redirected,
+            _ => FailureKind::Connection,
+        };
+
+        Self {
+            bookmark: bookmark.clone(),
+            reason,
+            kind,
+            redirect_chain: Vec::new(),
+        }
+    }
+}
+
+fn connection_reason(err: &reqwest::Error) -> FailureReason {
+    if err.is_redirect() {
+        return FailureReason::TooManyRedirects;
+    }
+
+    let mut details = Vec::new();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        details.push(cause.to_string());
+        source = cause.source();
+    }
+
+    categorize_reason(err.is_timeout(), err.is_connect(), &details.join(": "))
+        .unwrap_or_else(|| FailureReason::Other(format!("Request failed: {err}")))
+}
+
+fn categorize_reason(is_timeout: bool, is_connect: bool, details: &str) -> Option<FailureReason> {
+    let details = details.to_ascii_lowercase();
+
+    if is_timeout || details.contains("timed out") {
+        Some(FailureReason::Timeout)
+    } else if details.contains("dns")
+        || details.contains("failed to lookup address")
+        || details.contains("name or service not known")
+        || details.contains("no such host")
+    {
+        Some(FailureReason::Dns)
+    } else if details.contains("certificate")
+        || details.contains("tls")
+        || details.contains("handshake")
+    {
+        Some(FailureReason::Tls)
+    } else if is_connect && details.contains("refused") {
+        Some(FailureReason::Connect)
+    } else {
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::temp_path;
+    use std::collections::HashMap;
 
     #[test]
     fn empty_input_returns_no_failures() {
-        let result = check_bookmarks(&[]).expect("should succeed");
-        assert!(result.is_empty());
+        let result = check_bookmarks(&[], &CheckOptions::default(), None).expect("should succeed");
+        assert!(result.failures.is_empty());
+        assert_eq!(result.bytes_downloaded, 0);
     }
 
     #[test]
@@ -115,10 +1058,13 @@ mod tests {
         let bookmark = Bookmark {
             name: "Example".into(),
             url: "https://example".into(),
+            folder_path: Vec::new(),
+            date_added: None,
         };
 
         let failure = LinkFailure::from_status(&bookmark, StatusCode::NOT_FOUND);
-        assert_eq!(failure.reason, "HTTP 404 Not Found");
+        assert_eq!(failure.reason, FailureReason::Http(404));
+        assert_eq!(failure.reason.to_string(), "HTTP 404 Not Found");
         assert_eq!(failure.bookmark.url, bookmark.url);
         assert_eq!(failure.kind, FailureKind::NotFound);
     }
@@ -128,9 +1074,991 @@ mod tests {
         let bookmark = Bookmark {
             name: "Auth".into(),
             url: "https://example/auth".into(),
+            folder_path: Vec::new(),
+            date_added: None,
         };
 
         let failure = LinkFailure::from_status(&bookmark, StatusCode::UNAUTHORIZED);
         assert_eq!(failure.kind, FailureKind::Unauthorized);
     }
+
+    #[test]
+    fn reasons_display_the_report_wording() {
+        let cases = [
+            (FailureReason::Http(404), "HTTP 404 Not Found"),
+            (FailureReason::Http(599), "HTTP 599 Unknown"),
+            (FailureReason::Timeout, "Timed out"),
+            (FailureReason::Dns, "DNS lookup failed"),
+            (FailureReason::Tls, "TLS error"),
+            (FailureReason::Connect, "Connection refused"),
+            (FailureReason::TooManyRedirects, "Too many redirects"),
+            (
+                FailureReason::Parked("lander.example".into()),
+                "Redirected to parked domain lander.example",
+            ),
+            (
+                FailureReason::Other("Request failed: boom".into()),
+                "Request failed: boom",
+            ),
+        ];
+
+        for (reason, expected) in cases {
+            assert_eq!(reason.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn connection_conditions_map_to_concise_reasons() {
+        assert_eq!(
+            categorize_reason(true, false, "operation timed out"),
+            Some(FailureReason::Timeout)
+        );
+        assert_eq!(
+            categorize_reason(
+                false,
+                true,
+                "dns error: failed to lookup address information"
+            ),
+            Some(FailureReason::Dns)
+        );
+        assert_eq!(
+            categorize_reason(false, true, "invalid peer certificate: Expired"),
+            Some(FailureReason::Tls)
+        );
+        assert_eq!(
+            categorize_reason(false, true, "Connection refused (os error 111)"),
+            Some(FailureReason::Connect)
+        );
+        assert_eq!(categorize_reason(false, false, "something odd"), None);
+    }
+
+    #[test]
+    fn uncategorized_errors_fall_back_to_raw_message() {
+        let options = CheckOptions::default();
+        let probe = HttpProbe::new(&options).expect("probe");
+        let bookmark = Bookmark {
+            name: "Broken".into(),
+            url: "not a url".into(),
+            folder_path: Vec::new(),
+            date_added: None,
+        };
+
+        let failure = check_single(&bookmark, &probe, &options)
+            .failure
+            .expect("should fail");
+        assert!(matches!(&failure.reason, FailureReason::Other(message)
+            if message.starts_with("Request failed: ")));
+        assert_eq!(failure.kind, FailureKind::Connection);
+    }
+
+    /// Answers every request with one status and counts the attempts.
+    struct CountingProbe {
+        status: StatusCode,
+        attempts: AtomicUsize,
+    }
+
+    impl UrlProbe for CountingProbe {
+        fn probe(&self, _url: &str) -> ProbeOutcome {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            response(self.status, 0)
+        }
+    }
+
+    fn attempts_for(status: StatusCode, options: &CheckOptions) -> usize {
+        let probe = CountingProbe {
+            status,
+            attempts: AtomicUsize::new(0),
+        };
+        probe_with_retries("https://flaky.example", &probe, options);
+        probe.attempts.into_inner()
+    }
+
+    #[test]
+    fn retry_delays_are_jittered_within_a_quarter() {
+        let base = Duration::from_millis(1000);
+        let delays: Vec<Duration> = (0..50)
+            .map(|idx| jittered_delay(base, &format!("https://host.example/{idx}"), 1, 42))
+            .collect();
+
+        assert!(
+            delays
+                .iter()
+                .all(|delay| (750..=1250).contains(&delay.as_millis()))
+        );
+        assert!(delays.iter().any(|delay| *delay != delays[0]));
+        assert_eq!(
+            jittered_delay(base, "https://host.example/0", 1, 42),
+            delays[0]
+        );
+    }
+
+    #[test]
+    fn only_selected_kinds_are_retried() {
+        let options = CheckOptions {
+            retries: 2,
+            retry_delay: Duration::ZERO,
+            ..CheckOptions::default()
+        };
+
+        assert_eq!(attempts_for(StatusCode::SERVICE_UNAVAILABLE, &options), 3);
+        assert_eq!(attempts_for(StatusCode::NOT_FOUND, &options), 1);
+        assert_eq!(attempts_for(StatusCode::OK, &options), 1);
+
+        let options = CheckOptions {
+            retry_kinds: vec![RetryKind::NotFound],
+            ..options
+        };
+        assert_eq!(attempts_for(StatusCode::NOT_FOUND, &options), 3);
+        assert_eq!(attempts_for(StatusCode::SERVICE_UNAVAILABLE, &options), 1);
+    }
+
+    #[test]
+    fn fail_fast_stops_after_the_first_failure() {
+        let bookmarks: Vec<Bookmark> = (0..200)
+            .map(|idx| Bookmark {
+                name: format!("Dead {idx}"),
+                url: "https://missing".into(),
+                folder_path: Vec::new(),
+                date_added: None,
+            })
+            .collect();
+        let options = CheckOptions {
+            fail_fast: true,
+            ..CheckOptions::default()
+        };
+
+        let summary = check_bookmarks_with(&bookmarks, &scripted_probe(), &options, None);
+
+        assert!(!summary.failures.is_empty());
+        assert!(!summary.skipped.is_empty());
+        assert_eq!(
+            summary.failures.len() + summary.skipped.len(),
+            bookmarks.len()
+        );
+
+        let summary = check_bookmarks_with(
+            &bookmarks,
+            &scripted_probe(),
+            &CheckOptions::default(),
+            None,
+        );
+        assert_eq!(summary.failures.len(), bookmarks.len());
+        assert!(summary.skipped.is_empty());
+    }
+
+    #[test]
+    fn time_limit_stops_new_checks_once_spent() {
+        let now = Instant::now();
+        assert!(!past_deadline(None, now));
+        assert!(!past_deadline(Some(now + Duration::from_secs(10)), now));
+        assert!(past_deadline(Some(now), now));
+
+        let bookmarks: Vec<Bookmark> = (0..20).map(|_| bookmark("https://missing")).collect();
+        let options = CheckOptions {
+            deadline: Some(Instant::now()),
+            ..CheckOptions::default()
+        };
+        let summary = check_bookmarks_with(&bookmarks, &scripted_probe(), &options, None);
+
+        assert!(summary.out_of_time);
+        assert!(summary.failures.is_empty());
+        assert_eq!(summary.skipped.len(), bookmarks.len());
+    }
+
+    #[test]
+    fn nothing_is_retried_by_default() {
+        let options = CheckOptions {
+            retry_delay: Duration::ZERO,
+            ..CheckOptions::default()
+        };
+        assert_eq!(attempts_for(StatusCode::SERVICE_UNAVAILABLE, &options), 1);
+    }
+
+    struct ScriptedProbe(HashMap<&'static str, ProbeOutcome>);
+
+    impl UrlProbe for ScriptedProbe {
+        fn probe(&self, url: &str) -> ProbeOutcome {
+            self.0
+                .get(url)
+                .cloned()
+                .unwrap_or(response(StatusCode::OK, 100))
+        }
+    }
+
+    fn scripted_probe() -> ScriptedProbe {
+        ScriptedProbe(HashMap::from([
+            ("https://missing", response(StatusCode::NOT_FOUND, 20)),
+            ("https://removed", response(StatusCode::GONE, 0)),
+            ("https://private", response(StatusCode::FORBIDDEN, 0)),
+            ("https://login", response(StatusCode::UNAUTHORIZED, 0)),
+            (
+                "https://broken",
+                response(StatusCode::INTERNAL_SERVER_ERROR, 0),
+            ),
+            (
+                "https://no-head",
+                response(StatusCode::METHOD_NOT_ALLOWED, 0),
+            ),
+            ("https://down", ProbeOutcome::Failed(FailureReason::Connect)),
+            (
+                "https://expired.example",
+                landing_response(
+                    "https://parking.example/lander?domain=expired.example",
+                    "<h1>This domain is FOR SALE!</h1><a>Buy this domain</a>",
+                ),
+            ),
+            (
+                "https://shop.example",
+                landing_response(
+                    "https://shop.example/",
+                    "<p>Buy this domain name at our registrar shop.</p>",
+                ),
+            ),
+        ]))
+    }
+
+    fn response(status: StatusCode, bytes: u64) -> ProbeOutcome {
+        ProbeOutcome::Response {
+            status,
+            bytes,
+            content_type: None,
+            landing: None,
+            certificate_expiry: None,
+            final_url: None,
+        }
+    }
+
+    fn landing_response(url: &str, body: &str) -> ProbeOutcome {
+        ProbeOutcome::Response {
+            status: StatusCode::OK,
+            bytes: body.len() as u64,
+            content_type: Some("text/html".into()),
+            landing: Some(LandingPage {
+                url: url.into(),
+                body: body.into(),
+            }),
+            certificate_expiry: None,
+            final_url: None,
+        }
+    }
+
+    fn bookmark(url: &str) -> Bookmark {
+        Bookmark {
+            name: url.trim_start_matches("https://").into(),
+            url: url.into(),
+            folder_path: Vec::new(),
+            date_added: None,
+        }
+    }
+
+    #[test]
+    fn check_parked_flags_parking_page_on_another_host() {
+        let probe = scripted_probe();
+        let options = CheckOptions {
+            check_parked: true,
+            ..CheckOptions::default()
+        };
+
+        let failure = check_single(&bookmark("https://expired.example"), &probe, &options)
+            .failure
+            .expect("parked page should fail");
+        assert_eq!(failure.kind, FailureKind::Parked);
+        assert_eq!(
+            failure.reason,
+            FailureReason::Parked("parking.example".into())
+        );
+
+        assert!(
+            check_single(&bookmark("https://shop.example"), &probe, &options)
+                .failure
+                .is_none()
+        );
+        assert!(
+            check_single(
+                &bookmark("https://expired.example"),
+                &probe,
+                &CheckOptions::default()
+            )
+            .failure
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn check_single_classifies_scripted_outcomes() {
+        let probe = scripted_probe();
+        let options = CheckOptions::default();
+
+        assert!(
+            check_single(&bookmark("https://ok"), &probe, &options)
+                .failure
+                .is_none()
+        );
+        assert!(
+            check_single(&bookmark("https://broken"), &probe, &options)
+                .failure
+                .is_none()
+        );
+
+        let cases = [
+            (
+                "https://missing",
+                FailureKind::NotFound,
+                FailureReason::Http(404),
+            ),
+            (
+                "https://removed",
+                FailureKind::Gone,
+                FailureReason::Http(410),
+            ),
+            (
+                "https://private",
+                FailureKind::Unauthorized,
+                FailureReason::Http(403),
+            ),
+            (
+                "https://login",
+                FailureKind::Unauthorized,
+                FailureReason::Http(401),
+            ),
+            (
+                "https://down",
+                FailureKind::Connection,
+                FailureReason::Connect,
+            ),
+        ];
+        for (url, kind, reason) in cases {
+            let failure = check_single(&bookmark(url), &probe, &options)
+                .failure
+                .expect("should fail");
+            assert_eq!(failure.kind, kind, "{url}");
+            assert_eq!(failure.reason, reason, "{url}");
+        }
+    }
+
+    #[test]
+    fn check_bookmarks_with_collects_only_failures() {
+        let bookmarks: Vec<Bookmark> = ["https://ok", "https://missing", "https://down"]
+            .into_iter()
+            .map(bookmark)
+            .collect();
+
+        let mut failures = check_bookmarks_with(
+            &bookmarks,
+            &scripted_probe(),
+            &CheckOptions::default(),
+            None,
+        )
+        .failures;
+        failures.sort_by(|a, b| a.bookmark.url.cmp(&b.bookmark.url));
+
+        let urls: Vec<&str> = failures.iter().map(|f| f.bookmark.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://down", "https://missing"]);
+    }
+
+    #[test]
+    fn check_bookmarks_with_tallies_downloaded_bytes() {
+        let bookmarks: Vec<Bookmark> = [
+            "https://ok",
+            "https://missing",
+            "https://down",
+            "https://ok",
+        ]
+        .into_iter()
+        .map(bookmark)
+        .collect();
+
+        let summary = check_bookmarks_with(
+            &bookmarks,
+            &scripted_probe(),
+            &CheckOptions::default(),
+            None,
+        );
+        assert_eq!(summary.bytes_downloaded, 220);
+    }
+
+    #[test]
+    fn checks_without_a_progress_display_give_the_same_results() {
+        let bookmarks: Vec<Bookmark> = ["https://ok", "https://missing", "https://down"]
+            .into_iter()
+            .map(bookmark)
+            .collect();
+        let options = CheckOptions::default();
+
+        let summary = check_bookmarks_reporting(
+            &bookmarks,
+            &scripted_probe(),
+            &options,
+            None,
+            &progress::NoProgress,
+        );
+        let mut urls: Vec<&str> = summary
+            .failures
+            .iter()
+            .map(|f| f.bookmark.url.as_str())
+            .collect();
+        urls.sort();
+
+        assert_eq!(urls, vec!["https://down", "https://missing"]);
+        assert_eq!(
+            summary.bytes_downloaded,
+            check_bookmarks_with(&bookmarks, &scripted_probe(), &options, None).bytes_downloaded
+        );
+    }
+
+    #[test]
+    fn max_redirects_limits_the_redirects_followed() {
+        // /0 redirects to /1 and so on up to /4, which answers.
+        let (base, _) = serve(|request| {
+            let hop: u32 = request
+                .split(' ')
+                .nth(1)
+                .and_then(|path| path.trim_start_matches('/').parse().ok())
+                .unwrap_or(0);
+            if hop < 4 {
+                empty_response(&format!("302 Found\r\nLocation: /{}", hop + 1))
+            } else {
+                empty_response("200 OK")
+            }
+        });
+        let url = format!("{base}/0");
+
+        let outcome = |max_redirects| {
+            let options = CheckOptions {
+                max_redirects,
+                ..CheckOptions::default()
+            };
+            HttpProbe::new(&options).expect("probe").probe(&url)
+        };
+        assert!(matches!(
+            outcome(4),
+            ProbeOutcome::Response { status, .. } if status == StatusCode::OK
+        ));
+        assert_eq!(
+            outcome(3),
+            ProbeOutcome::Failed(FailureReason::TooManyRedirects)
+        );
+    }
+
+    #[test]
+    fn zero_max_redirects_returns_the_redirect_itself() {
+        let (base, _) = serve_once(empty_response("301 Moved Permanently\r\nLocation: /new"));
+
+        let options = CheckOptions {
+            max_redirects: 0,
+            ..CheckOptions::default()
+        };
+        let debug = format!("{:?}", client_builder(&options));
+        assert!(debug.contains("redirect_policy: Policy(None)"), "{debug}");
+
+        let probe = HttpProbe::new(&options).expect("probe");
+        let ProbeOutcome::Response { status, .. } = probe.probe(&format!("{base}/old")) else {
+            panic!("redirect should not be followed");
+        };
+        assert_eq!(status, StatusCode::MOVED_PERMANENTLY);
+    }
+
+    /// A local server answering each request with `respond(request)`, where the request
+    /// is what the client sent. Returns the server's base URL and a channel of those
+    /// requests, each queued before its answer is written.
+    fn serve(
+        mut respond: impl FnMut(&str) -> String + Send + 'static,
+    ) -> (String, std::sync::mpsc::Receiver<String>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, received) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().map_while(Result::ok) {
+                let mut request = [0u8; 4096];
+                let read = stream.read(&mut request).unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..read]).into_owned();
+                let response = respond(&request);
+                let _ = sender.send(request);
+                let _ = std::io::Write::write_all(&mut stream, response.as_bytes());
+            }
+        });
+        (base, received)
+    }
+
+    /// Like `serve`, giving every request the same `response`.
+    fn serve_once(response: String) -> (String, std::sync::mpsc::Receiver<String>) {
+        serve(move |_| response.clone())
+    }
+
+    /// A complete answer without a body, from a status such as "404 Not Found" and
+    /// any header lines after it.
+    fn empty_response(status: &str) -> String {
+        format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+    }
+
+    /// The method of each request in `received` so far.
+    fn methods(received: &std::sync::mpsc::Receiver<String>) -> Vec<String> {
+        received
+            .try_iter()
+            .map(|request| request.split(' ').next().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    /// The head of the one request a probe built from `options` sends, lowercased.
+    fn sent_request(options: &CheckOptions) -> String {
+        let (base, received) = serve_once(empty_response("200 OK"));
+
+        let probe = HttpProbe::new(options).expect("probe");
+        assert!(matches!(
+            probe.probe(&format!("{base}/")),
+            ProbeOutcome::Response { status, .. } if status == StatusCode::OK
+        ));
+        received
+            .recv_timeout(Duration::from_secs(5))
+            .expect("request")
+            .to_ascii_lowercase()
+    }
+
+    #[test]
+    fn configured_headers_are_sent_with_each_request() {
+        let request = sent_request(&CheckOptions {
+            headers: vec![
+                "X-Region: eu".parse().unwrap(),
+                RequestHeader::accept_language("de-DE").unwrap(),
+            ],
+            ..CheckOptions::default()
+        });
+        assert!(request.contains("x-region: eu\r\n"), "{request}");
+        assert!(request.contains("accept-language: de-de\r\n"), "{request}");
+    }
+
+    #[test]
+    fn a_later_header_replaces_an_earlier_one() {
+        // `--accept-language de --header 'Accept-Language: fr'`
+        let request = sent_request(&CheckOptions {
+            headers: vec![
+                RequestHeader::accept_language("de").unwrap(),
+                "Accept-Language: fr".parse().unwrap(),
+            ],
+            ..CheckOptions::default()
+        });
+        assert!(request.contains("accept-language: fr\r\n"), "{request}");
+        assert_eq!(request.matches("accept-language").count(), 1, "{request}");
+    }
+
+    #[test]
+    fn workers_come_from_the_flag_then_the_environment_then_the_cpus() {
+        let cases = [
+            (Concurrency::Fixed(4), Some("16"), 4),
+            (Concurrency::Fixed(4), None, 4),
+            (Concurrency::Auto, Some("16"), 16),
+            (Concurrency::Auto, Some(" 2 "), 2),
+            (Concurrency::Auto, Some("100000"), Concurrency::MAX),
+            (Concurrency::Auto, Some("0"), 8),
+            (Concurrency::Auto, Some("many"), 8),
+            (Concurrency::Auto, None, 8),
+        ];
+
+        for (concurrency, env_value, expected) in cases {
+            assert_eq!(
+                resolve_workers(concurrency, env_value, 8),
+                expected,
+                "{concurrency:?} with {env_value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn pool_limit_controls_connection_reuse() {
+        fn connections_for_two_checks(pool_max_idle_per_host: Option<usize>) -> usize {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+            let url = format!("http://{}/", listener.local_addr().unwrap());
+            let accepted = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            let counter = Arc::clone(&accepted);
+            std::thread::spawn(move || {
+                for mut stream in listener.incoming().flatten() {
+                    counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    std::thread::spawn(move || {
+                        let mut request = [0u8; 4096];
+                        while stream.read(&mut request).is_ok_and(|read| read > 0) {
+                            let _ = std::io::Write::write_all(
+                                &mut stream,
+                                b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n",
+                            );
+                        }
+                    });
+                }
+            });
+
+            let options = CheckOptions {
+                pool_max_idle_per_host,
+                ..CheckOptions::default()
+            };
+            let probe = HttpProbe::new(&options).expect("probe");
+            for _ in 0..2 {
+                assert!(matches!(
+                    probe.probe(&url),
+                    ProbeOutcome::Response { status, .. } if status == StatusCode::OK
+                ));
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            accepted.load(std::sync::atomic::Ordering::SeqCst)
+        }
+
+        assert_eq!(connections_for_two_checks(None), 1);
+        assert_eq!(connections_for_two_checks(Some(0)), 2);
+    }
+
+    #[test]
+    fn overall_timeout_bounds_slow_responses() {
+        // The listener accepts connections but never answers, so only the overall
+        // timeout can end the request.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let options = CheckOptions {
+            timeout: Duration::from_millis(200),
+            connect_timeout: Some(Duration::from_secs(5)),
+            ..CheckOptions::default()
+        };
+
+        let probe = HttpProbe::new(&options).expect("probe");
+        let started = std::time::Instant::now();
+        let outcome = probe.probe(&url);
+
+        assert_eq!(outcome, ProbeOutcome::Failed(FailureReason::Timeout));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn head_requests_use_the_shorter_head_timeout() {
+        let head_only = CheckOptions {
+            head_only: true,
+            timeout: Duration::from_secs(10),
+            ..CheckOptions::default()
+        };
+        let probe = HttpProbe::new(&head_only).expect("probe");
+        assert_eq!(probe.head_timeout, Duration::from_secs(5));
+
+        // The listener never answers, so the HEAD limit rather than the overall one ends it.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let options = CheckOptions {
+            head_timeout: Some(Duration::from_millis(200)),
+            ..head_only
+        };
+        let probe = HttpProbe::new(&options).expect("probe");
+        let started = std::time::Instant::now();
+
+        assert_eq!(
+            probe.probe(&url),
+            ProbeOutcome::Failed(FailureReason::Timeout)
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn per_host_limits_do_not_lift_the_head_timeout() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let options = CheckOptions {
+            head_first: true,
+            timeout: Duration::from_secs(10),
+            head_timeout: Some(Duration::from_millis(200)),
+            timeout_policy: TimeoutPolicy::PerHost,
+            ..CheckOptions::default()
+        };
+        let probe = HttpProbe::new(&options).expect("probe");
+        let started = std::time::Instant::now();
+
+        assert_eq!(
+            probe.send(Method::HEAD, &url),
+            ProbeOutcome::Failed(FailureReason::Timeout)
+        );
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn per_host_limits_are_learned_only_from_working_answers() {
+        let mut statuses = ["500 Internal Server Error", "200 OK"].into_iter();
+        let (base, _) = serve(move |_| empty_response(statuses.next().unwrap_or("200 OK")));
+        let url = format!("{base}/");
+        let options = CheckOptions {
+            timeout: Duration::from_secs(10),
+            timeout_policy: TimeoutPolicy::PerHost,
+            ..CheckOptions::default()
+        };
+        let probe = HttpProbe::new(&options).expect("probe");
+        let timeouts = probe.host_timeouts.as_ref().expect("per-host");
+
+        probe.probe(&url);
+        assert_eq!(timeouts.timeout_for("127.0.0.1"), Duration::from_secs(10));
+        probe.probe(&url);
+        assert!(timeouts.timeout_for("127.0.0.1") < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn successful_head_for_a_pdf_is_healthy_without_a_get() {
+        let (base, received) = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/pdf; name=manual.pdf\r\n\
+             Content-Length: 1048576\r\nConnection: close\r\n\r\n"
+                .to_string(),
+        );
+
+        let options = CheckOptions {
+            head_first: true,
+            ..CheckOptions::default()
+        };
+        let probe = HttpProbe::new(&options).expect("probe");
+        let outcome = check_single(&bookmark(&format!("{base}/manual.pdf")), &probe, &options);
+
+        assert!(outcome.failure.is_none());
+        assert_eq!(outcome.status, Some(StatusCode::OK));
+        assert_eq!(outcome.content_type.as_deref(), Some("application/pdf"));
+        // The probe is done once it returns, so anything it sent is already queued.
+        assert_eq!(methods(&received), ["HEAD"]);
+    }
+
+    #[test]
+    fn head_first_falls_back_to_get_for_pages() {
+        let (base, received) = serve_once(empty_response("200 OK\r\nContent-Type: text/html"));
+
+        let options = CheckOptions {
+            head_first: true,
+            ..CheckOptions::default()
+        };
+        let probe = HttpProbe::new(&options).expect("probe");
+        let outcome = check_single(&bookmark(&format!("{base}/")), &probe, &options);
+
+        assert!(outcome.failure.is_none());
+        assert_eq!(methods(&received), ["HEAD", "GET"]);
+    }
+
+    #[test]
+    fn not_modified_answer_to_a_conditional_request_is_healthy() {
+        let (base, received) =
+            serve_once("HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_string());
+
+        let options = CheckOptions {
+            if_modified_since: Some("2024-05-01T08:30:00Z".parse().unwrap()),
+            ..CheckOptions::default()
+        };
+        let probe = HttpProbe::new(&options).expect("probe");
+        let outcome = check_single(&bookmark(&format!("{base}/")), &probe, &options);
+
+        assert!(outcome.failure.is_none());
+        assert_eq!(outcome.status, Some(StatusCode::NOT_MODIFIED));
+        assert!(
+            received
+                .recv_timeout(Duration::from_secs(5))
+                .expect("request")
+                .to_ascii_lowercase()
+                .contains("if-modified-since: wed, 01 may 2024 08:30:00 gmt")
+        );
+    }
+
+    #[test]
+    fn max_response_size_stops_reading_huge_bodies() {
+        const CHUNK: usize = 64 * 1024;
+        const CHUNKS: usize = 512;
+
+        // A 32 MiB chunked page, so its size is only known by reading all of it.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}/huge", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept");
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request);
+            let _ = std::io::Write::write_all(
+                &mut stream,
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\
+                  Transfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+            );
+            let chunk = [
+                format!("{CHUNK:x}\r\n").into_bytes(),
+                vec![b'x'; CHUNK],
+                b"\r\n".to_vec(),
+            ]
+            .concat();
+            let mut sent = 0;
+            for _ in 0..CHUNKS {
+                if std::io::Write::write_all(&mut stream, &chunk).is_err() {
+                    break;
+                }
+                sent += CHUNK;
+            }
+            let _ = std::io::Write::write_all(&mut stream, b"0\r\n\r\n");
+            sent
+        });
+
+        let options = CheckOptions {
+            max_response_size: Some(4096),
+            ..CheckOptions::default()
+        };
+        let probe = HttpProbe::new(&options).expect("probe");
+        let ProbeOutcome::Response { status, bytes, .. } = probe.probe(&url) else {
+            panic!("expected a response");
+        };
+        drop(probe);
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(bytes, 4096);
+        assert!(server.join().unwrap() < CHUNK * CHUNKS);
+    }
+
+    #[test]
+    fn suggest_updates_captures_where_a_working_bookmark_redirects() {
+        // /old moves permanently to /new, which answers.
+        let (base, _) = serve(|request| {
+            if request.starts_with("GET /old") {
+                empty_response("301 Moved Permanently\r\nLocation: /new")
+            } else {
+                empty_response("200 OK")
+            }
+        });
+
+        let options = CheckOptions {
+            suggest_updates: true,
+            ..CheckOptions::default()
+        };
+        let probe = HttpProbe::new(&options).expect("probe");
+        let outcome = check_single(&bookmark(&format!("{base}/old")), &probe, &options);
+        assert!(outcome.failure.is_none());
+        let warning = outcome.warning.expect("a redirect should be reported");
+
+        assert_eq!(
+            warning.reason,
+            WarningReason::MovedTo(format!("{base}/new"))
+        );
+        assert_eq!(
+            crate::report::ReportEntry::from(&warning).suggested_url,
+            Some(format!("{base}/new"))
+        );
+        assert!(
+            check_single(&bookmark(&format!("{base}/new")), &probe, &options)
+                .warning
+                .is_none()
+        );
+        assert!(
+            check_single(
+                &bookmark(&format!("{base}/old")),
+                &probe,
+                &CheckOptions::default()
+            )
+            .warning
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn redirect_loops_are_reported_as_redirected() {
+        // Every request bounces between /a and /b, so the redirect limit is hit.
+        let (base, _) = serve(|request| {
+            let target = if request.starts_with("GET /a") {
+                "/b"
+            } else {
+                "/a"
+            };
+            empty_response(&format!("302 Found\r\nLocation: {target}"))
+        });
+        let url = format!("{base}/a");
+
+        let options = CheckOptions::default();
+        let probe = HttpProbe::new(&options).expect("probe");
+        let failure = check_single(&bookmark(&url), &probe, &options)
+            .failure
+            .expect("redirect loop should fail");
+
+        assert_eq!(failure.kind, FailureKind::Redirected);
+        assert_eq!(failure.reason, FailureReason::TooManyRedirects);
+        assert_eq!(failure.reason.to_string(), "Too many redirects");
+    }
+
+    #[test]
+    fn trace_redirects_records_each_hop_in_order() {
+        // /a redirects to /b, which redirects to /c, which is gone.
+        let (base, _) = serve(|request| {
+            if request.starts_with("GET /a") {
+                empty_response("302 Found\r\nLocation: /b")
+            } else if request.starts_with("GET /b") {
+                empty_response("302 Found\r\nLocation: /c")
+            } else {
+                empty_response("404 Not Found")
+            }
+        });
+
+        let options = CheckOptions {
+            trace_redirects: true,
+            ..CheckOptions::default()
+        };
+        let probe = HttpProbe::new(&options).expect("probe");
+        let failure = check_single(&bookmark(&format!("{base}/a")), &probe, &options)
+            .failure
+            .expect("should fail");
+
+        assert_eq!(failure.kind, FailureKind::NotFound);
+        assert_eq!(
+            failure.redirect_chain,
+            vec![format!("{base}/b"), format!("{base}/c")]
+        );
+        assert!(probe.take_redirect_chain(&format!("{base}/a")).is_empty());
+    }
+
+    #[test]
+    fn check_bookmarks_with_streams_every_result() {
+        let path = temp_path("bookmark-checker-checker-stream").with_extension("jsonl");
+        let bookmarks: Vec<Bookmark> = ["https://ok", "https://missing", "https://down"]
+            .into_iter()
+            .map(bookmark)
+            .collect();
+
+        let stream = ResultStream::create(&path).expect("stream");
+        check_bookmarks_with(
+            &bookmarks,
+            &scripted_probe(),
+            &CheckOptions::default(),
+            Some(&stream),
+        );
+        drop(stream);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), bookmarks.len());
+        assert!(contents.contains(r#""kind":"not_found""#));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn auto_tuned_checks_cover_every_bookmark() {
+        let bookmarks: Vec<Bookmark> = (0..50)
+            .map(|idx| {
+                if idx % 2 == 0 {
+                    bookmark("https://down")
+                } else {
+                    bookmark("https://ok")
+                }
+            })
+            .collect();
+        let options = CheckOptions {
+            auto_tune: true,
+            ..CheckOptions::default()
+        };
+
+        let summary = check_bookmarks_with(&bookmarks, &scripted_probe(), &options, None);
+        assert_eq!(summary.failures.len(), 25);
+        assert_eq!(summary.bytes_downloaded, 2_500);
+    }
+
+    #[test]
+    fn head_only_reports_method_not_allowed_instead_of_passing() {
+        let probe = scripted_probe();
+        let target = bookmark("https://no-head");
+
+        let default = check_single(&target, &probe, &CheckOptions::default()).failure;
+        assert!(default.is_none());
+
+        let head_only = CheckOptions {
+            head_only: true,
+            ..CheckOptions::default()
+        };
+        let failure = check_single(&target, &probe, &head_only)
+            .failure
+            .expect("should fail");
+        assert_eq!(failure.kind, FailureKind::MethodNotAllowed);
+        assert_eq!(failure.reason, FailureReason::Http(405));
+    }
 }
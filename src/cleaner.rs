@@ -1,13 +1,42 @@
-use crate::model::{BookmarkError, BookmarkLocation};
-use chrono::Utc;
-use serde::Deserialize;
+use crate::atomic::write_atomic;
+use crate::backup::create_backup;
+use crate::ignore::IgnoreList;
+use crate::model::{Bookmark, BookmarkError, BookmarkLocation, NormalizedUrl};
+use crate::report::{FailureReport, ReportEntry};
+use crate::tracking::strip_tracking_params;
 use serde_json::Value;
 use std::collections::HashSet;
 use std::fs;
+use std::io::BufRead;
 use std::path::{Path, PathBuf};
 
+#[derive(Debug, Default)]
+pub(crate) struct CleanOptions {
+    pub ignore: IgnoreList,
+    pub backup_dir: Option<PathBuf>,
+    /// Also remove `unauthorized`, `connection_errors` and `parked` entries, which may be
+    /// transient or misdetected.
+    pub include_transient: bool,
+    /// Also remove folders left empty by the cleanup; the roots are always kept.
+    pub prune_empty_folders: bool,
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
 pub(crate) struct CleanupResult {
+    pub removed: usize,
+    /// Folders removed because the cleanup emptied them.
+    pub pruned_folders: usize,
+    pub backup_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct NormalizeResult {
+    pub rewritten: usize,
+    pub backup_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct DedupeResult {
     pub removed: usize,
     pub backup_path: Option<PathBuf>,
 }
@@ -15,73 +44,206 @@ pub(crate) struct CleanupResult {
 pub(crate) fn clean_failures(
     location: &BookmarkLocation,
     report_path: &Path,
+    options: &CleanOptions,
 ) -> Result<CleanupResult, BookmarkError> {
     if !report_path.exists() {
         return Ok(CleanupResult::default());
     }
 
-    let report_contents = fs::read_to_string(report_path)?;
-    let report: FailureReport =
-        serde_yaml::from_str(&report_contents).map_err(BookmarkError::ReportParse)?;
+    let urls = clean_targets(FailureReport::load(report_path)?, options.include_transient);
+    remove_urls(location, urls, options)
+}
 
-    let targets = report.into_targets();
+/// Removes the bookmarks whose URL is among `urls`, unless ignored, backing up the
+/// Bookmarks file first when anything is removed.
+pub(crate) fn remove_urls(
+    location: &BookmarkLocation,
+    urls: HashSet<String>,
+    options: &CleanOptions,
+) -> Result<CleanupResult, BookmarkError> {
+    let targets: HashSet<NormalizedUrl> = urls
+        .into_iter()
+        .filter(|url| !options.ignore.is_ignored(url))
+        .map(|url| NormalizedUrl::new(&url))
+        .collect();
     if targets.is_empty() {
         return Ok(CleanupResult::default());
     }
 
-    let backup_path = create_backup(&location.file)?;
     let mut data: Value = serde_json::from_str(&fs::read_to_string(&location.file)?)?;
-    let removed = remove_targets(&mut data, &targets);
+    let removal = remove_targets(&mut data, &targets, options.prune_empty_folders);
 
-    if removed > 0 {
-        let updated =
-            serde_json::to_string_pretty(&data).map_err(BookmarkError::BookmarkSerialization)?;
-        fs::write(&location.file, updated)?;
+    // Nothing changes on disk, so a backup would only duplicate the previous one.
+    if removal.removed == 0 {
+        return Ok(CleanupResult::default());
     }
 
+    let backup_path = create_backup(&location.file, options.backup_dir.as_deref())?;
+    write_atomic(&location.file, |writer| {
+        serde_json::to_writer_pretty(writer, &data).map_err(BookmarkError::BookmarkSerialization)
+    })?;
+
     Ok(CleanupResult {
+        removed: removal.removed,
+        pruned_folders: removal.pruned,
+        backup_path: Some(backup_path),
+    })
+}
+
+/// Strips tracking query parameters from every bookmark URL, backing up the
+/// Bookmarks file first when anything changes.
+pub(crate) fn strip_tracking(
+    location: &BookmarkLocation,
+    backup_dir: Option<&Path>,
+) -> Result<NormalizeResult, BookmarkError> {
+    let mut data: Value = serde_json::from_str(&fs::read_to_string(&location.file)?)?;
+    let rewritten = strip_tracking_node(&mut data);
+
+    if rewritten == 0 {
+        return Ok(NormalizeResult::default());
+    }
+
+    let backup_path = create_backup(&location.file, backup_dir)?;
+    write_atomic(&location.file, |writer| {
+        serde_json::to_writer_pretty(writer, &data).map_err(BookmarkError::BookmarkSerialization)
+    })?;
+
+    Ok(NormalizeResult {
+        rewritten,
+        backup_path: Some(backup_path),
+    })
+}
+
+/// Trims bookmark and folder names and collapses runs of whitespace inside them,
+/// backing up the Bookmarks file first when anything changes.
+pub(crate) fn normalize_names(
+    location: &BookmarkLocation,
+    backup_dir: Option<&Path>,
+) -> Result<NormalizeResult, BookmarkError> {
+    let mut data: Value = serde_json::from_str(&fs::read_to_string(&location.file)?)?;
+    let rewritten = normalize_name_nodes(&mut data);
+
+    if rewritten == 0 {
+        return Ok(NormalizeResult::default());
+    }
+
+    let backup_path = create_backup(&location.file, backup_dir)?;
+    write_atomic(&location.file, |writer| {
+        serde_json::to_writer_pretty(writer, &data).map_err(BookmarkError::BookmarkSerialization)
+    })?;
+
+    Ok(NormalizeResult {
+        rewritten,
+        backup_path: Some(backup_path),
+    })
+}
+
+/// Removes every bookmark whose URL matches an earlier one, keeping the first in
+/// file order, and backs up the Bookmarks file first when anything changes.
+pub(crate) fn remove_duplicates(
+    location: &BookmarkLocation,
+    backup_dir: Option<&Path>,
+) -> Result<DedupeResult, BookmarkError> {
+    let mut data: Value = serde_json::from_str(&fs::read_to_string(&location.file)?)?;
+    let removed = remove_duplicate_nodes(&mut data, &mut HashSet::new());
+
+    if removed == 0 {
+        return Ok(DedupeResult::default());
+    }
+
+    let backup_path = create_backup(&location.file, backup_dir)?;
+    write_atomic(&location.file, |writer| {
+        serde_json::to_writer_pretty(writer, &data).map_err(BookmarkError::BookmarkSerialization)
+    })?;
+
+    Ok(DedupeResult {
         removed,
         backup_path: Some(backup_path),
     })
 }
 
-fn create_backup(bookmarks_file: &Path) -> Result<PathBuf, BookmarkError> {
-    let timestamp = Utc::now().format("%Y-%m-%dT%H-%M-%S");
-    let file_name = bookmarks_file
-        .file_name()
-        .map(|name| name.to_string_lossy().into_owned())
-        .unwrap_or_else(|| "Bookmarks".to_string());
-    let backup_name = format!("{file_name}-{timestamp}.bak");
-    let backup_path = bookmarks_file.with_file_name(backup_name);
-    fs::copy(bookmarks_file, &backup_path)?;
-    Ok(backup_path)
+/// Reads one URL per line, as piped to `--clean --stdin`, skipping blank lines.
+pub(crate) fn read_url_list<R: BufRead>(reader: R) -> Result<HashSet<String>, BookmarkError> {
+    let mut urls = HashSet::new();
+    for line in reader.lines() {
+        let line = line?;
+        let url = line.trim();
+        if !url.is_empty() {
+            urls.insert(url.to_string());
+        }
+    }
+    Ok(urls)
+}
+
+/// Loads every URL recorded in the report, regardless of failure kind.
+pub(crate) fn load_report_targets(report_path: &Path) -> Result<HashSet<String>, BookmarkError> {
+    Ok(FailureReport::load(report_path)?
+        .into_entries()
+        .map(|entry| entry.url)
+        .filter(|url| !url.is_empty())
+        .collect())
+}
+
+/// Rebuilds the bookmarks recorded in the report, once per URL, in report order.
+pub(crate) fn load_report_bookmarks(report_path: &Path) -> Result<Vec<Bookmark>, BookmarkError> {
+    let mut seen = HashSet::new();
+
+    Ok(FailureReport::load(report_path)?
+        .into_entries()
+        .filter_map(ReportEntry::into_bookmark)
+        .filter(|bookmark| seen.insert(bookmark.url.clone()))
+        .collect())
 }
 
-fn remove_targets(root: &mut Value, targets: &HashSet<String>) -> usize {
-    let (removed, _) = remove_node(root, targets);
-    removed
+/// Bookmarks removed from a tree, and folders pruned because that emptied them.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Removal {
+    removed: usize,
+    pruned: usize,
 }
 
-fn remove_node(node: &mut Value, targets: &HashSet<String>) -> (usize, bool) {
+fn remove_targets(
+    root: &mut Value,
+    targets: &HashSet<NormalizedUrl>,
+    prune_empty_folders: bool,
+) -> Removal {
+    let mut removal = Removal::default();
+    remove_node(root, targets, prune_empty_folders, &mut removal);
+    removal
+}
+
+/// Returns whether `node` itself is a target and should be removed by its parent.
+fn remove_node(
+    node: &mut Value,
+    targets: &HashSet<NormalizedUrl>,
+    prune_empty_folders: bool,
+    removal: &mut Removal,
+) -> bool {
     match node {
         Value::Object(map) => {
             if map.get("type").and_then(Value::as_str) == Some("url")
                 && let Some(url) = map.get("url").and_then(Value::as_str)
-                && targets.contains(url)
+                && targets.contains(&NormalizedUrl::new(url))
             {
-                return (1, true);
+                removal.removed += 1;
+                return true;
             }
 
-            let mut removed = 0;
-
             if let Some(Value::Array(children)) = map.get_mut("children") {
                 let mut index = 0;
                 while index < children.len() {
-                    let (child_removed, should_remove_child) =
-                        remove_node(&mut children[index], targets);
-                    removed += child_removed;
+                    let before = removal.removed + removal.pruned;
+                    let should_remove_child =
+                        remove_node(&mut children[index], targets, prune_empty_folders, removal);
+                    let emptied = prune_empty_folders
+                        && removal.removed + removal.pruned > before
+                        && is_empty_folder(&children[index]);
+
                     if should_remove_child {
                         children.remove(index);
+                    } else if emptied {
+                        removal.pruned += 1;
+                        children.remove(index);
                     } else {
                         index += 1;
                     }
@@ -94,9 +256,7 @@ fn remove_node(node: &mut Value, targets: &HashSet<String>) -> (usize, bool) {
                     continue;
                 }
 
-                let (child_removed, should_remove_child) = remove_node(value, targets);
-                removed += child_removed;
-                if should_remove_child {
+                if remove_node(value, targets, prune_empty_folders, removal) {
                     keys_to_remove.push(key.clone());
                 }
             }
@@ -105,65 +265,156 @@ fn remove_node(node: &mut Value, targets: &HashSet<String>) -> (usize, bool) {
                 map.remove(&key);
             }
 
-            (removed, false)
+            false
         }
         Value::Array(array) => {
-            let mut removed = 0;
             let mut index = 0;
             while index < array.len() {
-                let (child_removed, should_remove_child) = remove_node(&mut array[index], targets);
-                removed += child_removed;
-                if should_remove_child {
+                if remove_node(&mut array[index], targets, prune_empty_folders, removal) {
                     array.remove(index);
                 } else {
                     index += 1;
                 }
             }
 
-            (removed, false)
+            false
         }
-        _ => (0, false),
+        _ => false,
     }
 }
 
-#[derive(Debug, Default, Deserialize)]
-struct FailureReport {
-    #[serde(default)]
-    not_found: Vec<FailureEntry>,
-    #[serde(default)]
-    unauthorized: Vec<FailureEntry>,
-    #[serde(default)]
-    connection_errors: Vec<FailureEntry>,
+/// Folders only ever sit in a parent's `children`, so the roots (values of the
+/// `roots` object) can never be pruned.
+fn is_empty_folder(node: &Value) -> bool {
+    node.get("type").and_then(Value::as_str) == Some("folder")
+        && node
+            .get("children")
+            .and_then(Value::as_array)
+            .is_some_and(Vec::is_empty)
 }
 
-impl FailureReport {
-    fn into_targets(self) -> HashSet<String> {
-        self.not_found
-            .into_iter()
-            .chain(self.unauthorized)
-            .chain(self.connection_errors)
-            .filter_map(|entry| entry.url)
-            .collect()
+fn remove_duplicate_nodes(node: &mut Value, seen: &mut HashSet<NormalizedUrl>) -> usize {
+    match node {
+        Value::Object(map) => map
+            .values_mut()
+            .map(|value| remove_duplicate_nodes(value, seen))
+            .sum(),
+        Value::Array(array) => {
+            let mut removed = 0;
+            array.retain_mut(|child| {
+                if is_seen_url(child, seen) {
+                    removed += 1;
+                    false
+                } else {
+                    removed += remove_duplicate_nodes(child, seen);
+                    true
+                }
+            });
+            removed
+        }
+        _ => 0,
+    }
+}
+
+/// Whether `node` is a bookmark whose URL was already seen; records it otherwise.
+fn is_seen_url(node: &Value, seen: &mut HashSet<NormalizedUrl>) -> bool {
+    node.get("type").and_then(Value::as_str) == Some("url")
+        && node
+            .get("url")
+            .and_then(Value::as_str)
+            .is_some_and(|url| !seen.insert(NormalizedUrl::new(url)))
+}
+
+fn strip_tracking_node(node: &mut Value) -> usize {
+    match node {
+        Value::Object(map) => {
+            if map.get("type").and_then(Value::as_str) == Some("url") {
+                let cleaned = map
+                    .get("url")
+                    .and_then(Value::as_str)
+                    .and_then(strip_tracking_params);
+                return match cleaned {
+                    Some(url) => {
+                        map.insert("url".to_string(), Value::String(url));
+                        1
+                    }
+                    None => 0,
+                };
+            }
+
+            map.values_mut().map(strip_tracking_node).sum()
+        }
+        Value::Array(array) => array.iter_mut().map(strip_tracking_node).sum(),
+        _ => 0,
+    }
+}
+
+fn normalize_name_nodes(node: &mut Value) -> usize {
+    match node {
+        Value::Object(map) => {
+            let is_entry = matches!(
+                map.get("type").and_then(Value::as_str),
+                Some("url" | "folder")
+            );
+            let renamed = map
+                .get("name")
+                .and_then(Value::as_str)
+                .filter(|_| is_entry)
+                .and_then(normalized_name);
+            let changed = match renamed {
+                Some(name) => {
+                    map.insert("name".to_string(), Value::String(name));
+                    1
+                }
+                None => 0,
+            };
+
+            changed + map.values_mut().map(normalize_name_nodes).sum::<usize>()
+        }
+        Value::Array(array) => array.iter_mut().map(normalize_name_nodes).sum(),
+        _ => 0,
     }
 }
 
-#[derive(Debug, Deserialize)]
-struct FailureEntry {
-    #[serde(default)]
-    url: Option<String>,
+/// `name` trimmed with inner whitespace runs (newlines, tabs, ...) collapsed to a
+/// single space, or `None` when it is already in that form.
+fn normalized_name(name: &str) -> Option<String> {
+    let normalized = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    (normalized != name).then_some(normalized)
+}
+
+/// `gone` and `not_found` entries are removed by default; `parked` is a heuristic,
+/// so it joins the transient kinds. Servers rejecting HEAD requests
+/// (`method_not_allowed`) and redirect loops (`redirected`) point at live but
+/// misbehaving sites, so they are never treated as dead links; neither are sites
+/// whose certificate is merely about to expire (`ssl_warnings`) or that only moved
+/// (`updatable`).
+fn clean_targets(report: FailureReport, include_transient: bool) -> HashSet<String> {
+    let mut entries: Vec<ReportEntry> = report.gone.into_iter().chain(report.not_found).collect();
+
+    if include_transient {
+        entries.extend(report.unauthorized);
+        entries.extend(report.connection_errors);
+        entries.extend(report.parked);
+    }
+
+    entries
+        .into_iter()
+        .map(|entry| entry.url)
+        .filter(|url| !url.is_empty())
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::model::BookmarkLocation;
+    use crate::test_support::temp_dir;
     use std::fs;
-    use std::path::PathBuf;
-    use std::time::{SystemTime, UNIX_EPOCH};
 
     #[test]
     fn removes_bookmarks_listed_in_report() {
-        let temp_dir = temp_dir();
+        let temp_dir = temp_dir("bookmark-cleaner");
         let bookmarks_path = temp_dir.join("Bookmarks");
         fs::write(&bookmarks_path, sample_bookmarks_json()).unwrap();
 
@@ -175,7 +426,8 @@ mod tests {
             file: bookmarks_path.clone(),
         };
 
-        let result = clean_failures(&location, &report_path).expect("clean");
+        let result =
+            clean_failures(&location, &report_path, &CleanOptions::default()).expect("clean");
         assert_eq!(result.removed, 1);
         assert!(result.backup_path.unwrap().exists());
 
@@ -186,9 +438,37 @@ mod tests {
         fs::remove_dir_all(temp_dir).unwrap();
     }
 
+    #[test]
+    fn backup_is_written_to_requested_directory() {
+        let temp_dir = temp_dir("bookmark-cleaner");
+        let bookmarks_path = temp_dir.join("Bookmarks");
+        fs::write(&bookmarks_path, sample_bookmarks_json()).unwrap();
+
+        let report_path = temp_dir.join("bookmark_failures.yml");
+        fs::write(&report_path, sample_report_yaml()).unwrap();
+
+        let location = BookmarkLocation {
+            directory: temp_dir.clone(),
+            file: bookmarks_path.clone(),
+        };
+
+        let backup_dir = temp_dir.join("backups");
+        let options = CleanOptions {
+            backup_dir: Some(backup_dir.clone()),
+            ..CleanOptions::default()
+        };
+        let result = clean_failures(&location, &report_path, &options).expect("clean");
+
+        let backup = result.backup_path.expect("backup");
+        assert_eq!(backup.parent(), Some(backup_dir.as_path()));
+        assert!(backup.exists());
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
     #[test]
     fn no_report_returns_zero_without_backup() {
-        let temp_dir = temp_dir();
+        let temp_dir = temp_dir("bookmark-cleaner");
         let bookmarks_path = temp_dir.join("Bookmarks");
         fs::write(&bookmarks_path, sample_bookmarks_json()).unwrap();
 
@@ -197,7 +477,12 @@ mod tests {
             file: bookmarks_path.clone(),
         };
 
-        let result = clean_failures(&location, &temp_dir.join("missing.yml")).expect("clean");
+        let result = clean_failures(
+            &location,
+            &temp_dir.join("missing.yml"),
+            &CleanOptions::default(),
+        )
+        .expect("clean");
         assert_eq!(result.removed, 0);
         assert!(result.backup_path.is_none());
 
@@ -206,7 +491,7 @@ mod tests {
 
     #[test]
     fn preserves_bookmarks_when_no_match_found() {
-        let temp_dir = temp_dir();
+        let temp_dir = temp_dir("bookmark-cleaner");
         let bookmarks_path = temp_dir.join("Bookmarks");
         fs::write(&bookmarks_path, sample_bookmarks_json()).unwrap();
 
@@ -220,25 +505,404 @@ mod tests {
             file: bookmarks_path.clone(),
         };
 
-        let result = clean_failures(&location, &report_path).expect("clean");
+        let result =
+            clean_failures(&location, &report_path, &CleanOptions::default()).expect("clean");
         assert_eq!(result.removed, 0);
-        assert!(result.backup_path.is_some());
+        assert!(result.backup_path.is_none());
 
         let updated = fs::read_to_string(&bookmarks_path).unwrap();
         assert_eq!(updated, original);
+        let backups = fs::read_dir(&temp_dir)
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".bak"))
+            .count();
+        assert_eq!(backups, 0);
 
         fs::remove_dir_all(temp_dir).unwrap();
     }
 
-    fn temp_dir() -> PathBuf {
-        let mut dir = std::env::temp_dir();
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
+    #[test]
+    fn matches_report_urls_after_normalization() {
+        let mut data: Value = serde_json::from_str(sample_bookmarks_json()).unwrap();
+        let targets = HashSet::from([NormalizedUrl::new("HTTPS://Remove.me/")]);
+
+        assert_eq!(remove_targets(&mut data, &targets, false).removed, 1);
+        let updated = data.to_string();
+        assert!(updated.contains("https://keep.me"));
+        assert!(!updated.contains("https://remove.me"));
+    }
+
+    #[test]
+    fn pruning_removes_only_folders_the_cleanup_emptied() {
+        let mut data = serde_json::json!({
+            "roots": {
+                "bookmark_bar": {"type": "folder", "name": "Bar", "children": [
+                    {"type": "folder", "name": "Old", "children": [
+                        {"type": "url", "name": "Gone", "url": "https://remove.me"}
+                    ]},
+                    {"type": "folder", "name": "Mixed", "children": [
+                        {"type": "url", "name": "Gone", "url": "https://remove.me"},
+                        {"type": "url", "name": "Keep", "url": "https://keep.me"}
+                    ]},
+                    {"type": "folder", "name": "Outer", "children": [
+                        {"type": "folder", "name": "Inner", "children": [
+                            {"type": "url", "name": "Gone", "url": "https://remove.me"}
+                        ]}
+                    ]},
+                    {"type": "folder", "name": "Placeholder", "children": []}
+                ]},
+                "other": {"type": "folder", "name": "Other", "children": [
+                    {"type": "url", "name": "Gone", "url": "https://remove.me"}
+                ]}
+            }
+        });
+        let targets = HashSet::from([NormalizedUrl::new("https://remove.me")]);
+
+        let removal = remove_targets(&mut data, &targets, true);
+
+        assert_eq!(
+            removal,
+            Removal {
+                removed: 4,
+                pruned: 3
+            }
+        );
+        let folders: Vec<&str> = data["roots"]["bookmark_bar"]["children"]
+            .as_array()
             .unwrap()
-            .as_nanos();
-        dir.push(format!("bookmark-cleaner-{unique}"));
-        fs::create_dir_all(&dir).unwrap();
-        dir
+            .iter()
+            .filter_map(|child| child["name"].as_str())
+            .collect();
+        assert_eq!(folders, vec!["Mixed", "Placeholder"]);
+        assert_eq!(data["roots"]["other"]["children"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn ignored_urls_are_protected_from_removal() {
+        let temp_dir = temp_dir("bookmark-cleaner");
+        let bookmarks_path = temp_dir.join("Bookmarks");
+        fs::write(&bookmarks_path, sample_bookmarks_json()).unwrap();
+
+        let report_path = temp_dir.join("bookmark_failures.yml");
+        fs::write(&report_path, sample_report_yaml()).unwrap();
+
+        let location = BookmarkLocation {
+            directory: temp_dir.clone(),
+            file: bookmarks_path.clone(),
+        };
+
+        let options = CleanOptions {
+            ignore: IgnoreList::parse("# keep this one\nhttps://remove.*\n"),
+            ..CleanOptions::default()
+        };
+        let result = clean_failures(&location, &report_path, &options).expect("clean");
+        assert_eq!(result.removed, 0);
+        assert!(result.backup_path.is_none());
+
+        let updated = fs::read_to_string(&bookmarks_path).unwrap();
+        assert!(updated.contains("https://remove.me"));
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn default_clean_removes_gone_and_not_found_only() {
+        let temp_dir = temp_dir("bookmark-cleaner");
+        let bookmarks_path = temp_dir.join("Bookmarks");
+        fs::write(&bookmarks_path, sample_bookmarks_json()).unwrap();
+
+        let report_path = temp_dir.join("bookmark_failures.yml");
+        fs::write(
+            &report_path,
+            "gone:\n  - url: https://remove.me\nconnection_errors:\n  - url: https://keep.me\n",
+        )
+        .unwrap();
+
+        let location = BookmarkLocation {
+            directory: temp_dir.clone(),
+            file: bookmarks_path.clone(),
+        };
+
+        let result =
+            clean_failures(&location, &report_path, &CleanOptions::default()).expect("clean");
+        assert_eq!(result.removed, 1);
+
+        let updated = fs::read_to_string(&bookmarks_path).unwrap();
+        assert!(updated.contains("https://keep.me"));
+        assert!(!updated.contains("https://remove.me"));
+
+        let options = CleanOptions {
+            include_transient: true,
+            ..CleanOptions::default()
+        };
+        let result = clean_failures(&location, &report_path, &options).expect("clean");
+        assert_eq!(result.removed, 1);
+        assert!(
+            !fs::read_to_string(&bookmarks_path)
+                .unwrap()
+                .contains("https://keep.me")
+        );
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn report_bookmarks_keep_name_and_folder() {
+        let temp_dir = temp_dir("bookmark-cleaner");
+        let report_path = temp_dir.join("bookmark_failures.yml");
+        fs::write(
+            &report_path,
+            "not_found:\n  - name: Remove\n    url: https://remove.me\n    folder: Bar/Old\n\
+             connection_errors:\n  - url: https://down.me\n  - url: https://remove.me\n",
+        )
+        .unwrap();
+
+        let bookmarks = load_report_bookmarks(&report_path).expect("load");
+        assert_eq!(
+            bookmarks,
+            vec![
+                Bookmark {
+                    name: "Remove".into(),
+                    url: "https://remove.me".into(),
+                    folder_path: vec!["Bar".into(), "Old".into()],
+                    date_added: None,
+                },
+                Bookmark {
+                    name: "https://down.me".into(),
+                    url: "https://down.me".into(),
+                    folder_path: Vec::new(),
+                    date_added: None,
+                },
+            ]
+        );
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn urls_read_from_a_list_are_removed() {
+        let temp_dir = temp_dir("bookmark-cleaner");
+        let bookmarks_path = temp_dir.join("Bookmarks");
+        fs::write(
+            &bookmarks_path,
+            r#"{"roots": {"bookmark_bar": {"type": "folder", "children": [
+                {"type": "url", "name": "Old", "url": "https://old.example/"},
+                {"type": "url", "name": "Stale", "url": "https://stale.example/page"},
+                {"type": "url", "name": "Keep", "url": "https://keep.example"}
+            ]}}}"#,
+        )
+        .unwrap();
+        let location = BookmarkLocation {
+            directory: temp_dir.clone(),
+            file: bookmarks_path.clone(),
+        };
+
+        let stdin = "https://OLD.example\n\n  https://stale.example/page  \r\n";
+        let urls = read_url_list(stdin.as_bytes()).expect("read");
+        assert_eq!(urls.len(), 2);
+
+        let result = remove_urls(&location, urls, &CleanOptions::default()).expect("clean");
+        assert_eq!(result.removed, 2);
+        assert!(
+            result
+                .backup_path
+                .as_ref()
+                .is_some_and(|path| path.exists())
+        );
+
+        let contents = fs::read_to_string(&bookmarks_path).unwrap();
+        assert!(!contents.contains("old.example"));
+        assert!(!contents.contains("stale.example"));
+        assert!(contents.contains("https://keep.example"));
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn strip_tracking_rewrites_urls_and_backs_up() {
+        let temp_dir = temp_dir("bookmark-cleaner");
+        let bookmarks_path = temp_dir.join("Bookmarks");
+        fs::write(
+            &bookmarks_path,
+            r#"{"roots": {"bookmark_bar": {"children": [
+                {"type": "url", "name": "Tracked", "url": "https://news.example/a?id=7&utm_source=feed"},
+                {"type": "url", "name": "Clean", "url": "https://keep.me/?page=2"}
+            ]}}}"#,
+        )
+        .unwrap();
+        let location = BookmarkLocation {
+            directory: temp_dir.clone(),
+            file: bookmarks_path.clone(),
+        };
+
+        let result = strip_tracking(&location, None).expect("normalize");
+        assert_eq!(result.rewritten, 1);
+        assert!(
+            result
+                .backup_path
+                .as_ref()
+                .is_some_and(|path| path.exists())
+        );
+
+        let contents = fs::read_to_string(&bookmarks_path).unwrap();
+        assert!(contents.contains("https://news.example/a?id=7\""));
+        assert!(contents.contains("https://keep.me/?page=2"));
+
+        let again = strip_tracking(&location, None).expect("normalize");
+        assert_eq!(again, NormalizeResult::default());
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn names_are_trimmed_and_collapsed_leaving_others_untouched() {
+        let temp_dir = temp_dir("bookmark-cleaner");
+        let bookmarks_path = temp_dir.join("Bookmarks");
+        fs::write(
+            &bookmarks_path,
+            r#"{"roots": {"bookmark_bar": {"type": "folder", "name": "Bookmarks bar", "children": [
+                {"type": "url", "name": "  Padded\n", "url": "https://padded.example"},
+                {"type": "folder", "name": "Read\t\tlater", "children": [
+                    {"type": "url", "name": "Already fine", "url": "https://fine.example"}
+                ]}
+            ]}}}"#,
+        )
+        .unwrap();
+        let location = BookmarkLocation {
+            directory: temp_dir.clone(),
+            file: bookmarks_path.clone(),
+        };
+
+        let result = normalize_names(&location, None).expect("normalize names");
+        assert_eq!(result.rewritten, 2);
+        assert!(
+            result
+                .backup_path
+                .as_ref()
+                .is_some_and(|path| path.exists())
+        );
+
+        let data: Value =
+            serde_json::from_str(&fs::read_to_string(&bookmarks_path).unwrap()).unwrap();
+        let bar = &data["roots"]["bookmark_bar"];
+        assert_eq!(bar["name"], "Bookmarks bar");
+        assert_eq!(bar["children"][0]["name"], "Padded");
+        assert_eq!(bar["children"][1]["name"], "Read later");
+        assert_eq!(bar["children"][1]["children"][0]["name"], "Already fine");
+
+        let again = normalize_names(&location, None).expect("normalize names");
+        assert_eq!(again, NormalizeResult::default());
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn duplicates_are_removed_keeping_the_first() {
+        let temp_dir = temp_dir("bookmark-cleaner");
+        let bookmarks_path = temp_dir.join("Bookmarks");
+        fs::write(
+            &bookmarks_path,
+            r#"{"roots": {
+                "bookmark_bar": {"type": "folder", "children": [
+                    {"type": "url", "name": "First", "url": "https://dup.example/"},
+                    {"type": "url", "name": "Other", "url": "https://other.example"},
+                    {"type": "folder", "name": "Nested", "children": [
+                        {"type": "url", "name": "Second", "url": "https://dup.example/"}
+                    ]}
+                ]},
+                "other": {"type": "folder", "children": [
+                    {"type": "url", "name": "Third", "url": "HTTPS://DUP.example"}
+                ]}
+            }}"#,
+        )
+        .unwrap();
+        let location = BookmarkLocation {
+            directory: temp_dir.clone(),
+            file: bookmarks_path.clone(),
+        };
+
+        let result = remove_duplicates(&location, None).expect("dedupe");
+        assert_eq!(result.removed, 2);
+        assert!(
+            result
+                .backup_path
+                .as_ref()
+                .is_some_and(|path| path.exists())
+        );
+
+        let contents = fs::read_to_string(&bookmarks_path).unwrap();
+        assert!(contents.contains("First"));
+        assert!(contents.contains("https://other.example"));
+        assert!(!contents.contains("Second"));
+        assert!(!contents.contains("Third"));
+        assert!(contents.contains("Nested"));
+
+        let again = remove_duplicates(&location, None).expect("dedupe");
+        assert_eq!(again, DedupeResult::default());
+
+        fs::remove_dir_all(temp_dir).unwrap();
+    }
+
+    #[test]
+    fn written_reports_read_back_with_the_same_targets() {
+        use crate::checker::{FailureKind, FailureReason, LinkFailure};
+        use crate::report::FailureReporter;
+
+        let failures: Vec<LinkFailure> = [
+            ("https://missing.example", FailureKind::NotFound),
+            ("https://gone.example", FailureKind::Gone),
+            ("https://private.example", FailureKind::Unauthorized),
+            ("https://head.example", FailureKind::MethodNotAllowed),
+            ("https://down.example", FailureKind::Connection),
+            ("https://parked.example", FailureKind::Parked),
+            ("https://loop.example", FailureKind::Redirected),
+        ]
+        .into_iter()
+        .map(|(url, kind)| LinkFailure {
+            bookmark: Bookmark {
+                name: url.into(),
+                url: url.into(),
+                folder_path: vec!["Bar".into()],
+                date_added: None,
+            },
+            reason: FailureReason::Other("test".into()),
+            kind,
+            redirect_chain: Vec::new(),
+        })
+        .collect();
+
+        let temp_dir = temp_dir("bookmark-cleaner");
+        let report_path = temp_dir.join("bookmark_failures.yml");
+        FailureReporter::new(&report_path)
+            .write_report(&failures)
+            .expect("write");
+
+        let all: HashSet<String> = failures
+            .iter()
+            .map(|failure| failure.bookmark.url.clone())
+            .collect();
+        assert_eq!(load_report_targets(&report_path).unwrap(), all);
+
+        let report = || FailureReport::load(&report_path).unwrap();
+        let strict: HashSet<String> = ["https://missing.example", "https://gone.example"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(clean_targets(report(), false), strict);
+
+        let transient = &strict
+            | &[
+                "https://private.example",
+                "https://down.example",
+                "https://parked.example",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(clean_targets(report(), true), transient);
+
+        fs::remove_dir_all(temp_dir).unwrap();
     }
 
     fn sample_bookmarks_json() -> &'static str {
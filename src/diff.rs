@@ -0,0 +1,119 @@
+use crate::checker::LinkFailure;
+use crate::model::{Bookmark, BookmarkError, NormalizedUrl};
+use crate::report::FailureReport;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Failing URLs from an earlier report, keyed for comparison and kept as written.
+pub(crate) type PreviousFailures = BTreeMap<NormalizedUrl, String>;
+
+/// How a scan's failures changed since an earlier report, for `--diff`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct ReportDiff {
+    pub(crate) newly_broken: Vec<String>,
+    pub(crate) fixed: Vec<String>,
+    pub(crate) still_broken: Vec<String>,
+}
+
+pub(crate) fn load_previous_failures(path: &Path) -> Result<PreviousFailures, BookmarkError> {
+    Ok(FailureReport::load(path)?
+        .into_entries()
+        .filter(|entry| !entry.url.is_empty())
+        .map(|entry| (NormalizedUrl::new(&entry.url), entry.url))
+        .collect())
+}
+
+/// Sorts URLs into newly broken, fixed and still broken. A previously failing URL
+/// only counts as fixed if this scan checked it; filtered-out bookmarks are left
+/// out entirely.
+pub(crate) fn diff_failures(
+    previous: &PreviousFailures,
+    checked: &[Bookmark],
+    failures: &[LinkFailure],
+) -> ReportDiff {
+    let mut current = BTreeMap::new();
+    for failure in failures {
+        current
+            .entry(failure.bookmark.normalized_url())
+            .or_insert_with(|| failure.bookmark.url.clone());
+    }
+    let checked: HashSet<NormalizedUrl> = checked.iter().map(Bookmark::normalized_url).collect();
+
+    let mut diff = ReportDiff::default();
+    for (normalized, url) in &current {
+        if previous.contains_key(normalized) {
+            diff.still_broken.push(url.clone());
+        } else {
+            diff.newly_broken.push(url.clone());
+        }
+    }
+    diff.fixed = previous
+        .iter()
+        .filter(|(normalized, _)| checked.contains(normalized) && !current.contains_key(normalized))
+        .map(|(_, url)| url.clone())
+        .collect();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::{FailureKind, FailureReason};
+    use crate::test_support::temp_path;
+    use std::fs;
+
+    fn bookmark(url: &str) -> Bookmark {
+        Bookmark {
+            name: url.to_string(),
+            url: url.to_string(),
+            folder_path: Vec::new(),
+            date_added: None,
+        }
+    }
+
+    fn failure(url: &str) -> LinkFailure {
+        LinkFailure {
+            bookmark: bookmark(url),
+            reason: FailureReason::Http(404),
+            kind: FailureKind::NotFound,
+            redirect_chain: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn failures_are_sorted_against_the_previous_report() {
+        let dir = temp_path("bookmark-diff");
+        fs::create_dir_all(&dir).expect("dir");
+        let report = dir.join("previous.yml");
+        fs::write(
+            &report,
+            "not_found:\n- name: a\n  url: https://still.example/\n  reason: HTTP 404\n\
+             unauthorized: []\n\
+             connection_errors:\n- name: b\n  url: https://fixed.example\n  reason: timeout\n\
+             - name: c\n  url: https://skipped.example\n  reason: timeout\n",
+        )
+        .expect("write report");
+
+        let previous = load_previous_failures(&report).expect("load");
+        let checked = [
+            bookmark("https://STILL.example"),
+            bookmark("https://fixed.example"),
+            bookmark("https://new.example"),
+        ];
+        let failures = [
+            failure("https://STILL.example"),
+            failure("https://new.example"),
+        ];
+        let diff = diff_failures(&previous, &checked, &failures);
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(
+            diff,
+            ReportDiff {
+                newly_broken: vec!["https://new.example".to_string()],
+                fixed: vec!["https://fixed.example".to_string()],
+                still_broken: vec!["https://STILL.example".to_string()],
+            }
+        );
+    }
+}
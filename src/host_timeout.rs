@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// How many times its first response time a host gets before a check gives up.
+const LATENCY_MULTIPLIER: u32 = 5;
+/// Learned limits never drop below this, so one quick redirect or error page cannot
+/// starve the host's heavier pages.
+const MIN_HOST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Per-host time limits for `--timeout-policy per-host`, learned from the first
+/// response each host gives and never longer than the global timeout.
+#[derive(Debug)]
+pub(crate) struct HostTimeouts {
+    ceiling: Duration,
+    learned: Mutex<HashMap<String, Duration>>,
+}
+
+impl HostTimeouts {
+    pub(crate) fn new(ceiling: Duration) -> Self {
+        Self {
+            ceiling,
+            learned: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The limit for the next request to `host`; the global one until it has answered.
+    pub(crate) fn timeout_for(&self, host: &str) -> Duration {
+        self.learned().get(host).copied().unwrap_or(self.ceiling)
+    }
+
+    /// Records how long `host` took to answer. Only its first response counts, so a
+    /// limit cannot ratchet down as later requests come in faster.
+    pub(crate) fn record(&self, host: &str, latency: Duration) {
+        let ceiling = self.ceiling;
+        self.learned()
+            .entry(host.to_string())
+            .or_insert_with(|| derive_timeout(latency, ceiling));
+    }
+
+    fn learned(&self) -> MutexGuard<'_, HashMap<String, Duration>> {
+        self.learned.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A multiple of the observed latency, kept between `MIN_HOST_TIMEOUT` and `ceiling`.
+fn derive_timeout(latency: Duration, ceiling: Duration) -> Duration {
+    latency
+        .saturating_mul(LATENCY_MULTIPLIER)
+        .clamp(MIN_HOST_TIMEOUT.min(ceiling), ceiling)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CEILING: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn timeout_is_a_multiple_of_the_first_latency() {
+        assert_eq!(
+            derive_timeout(Duration::from_millis(800), CEILING),
+            Duration::from_secs(4)
+        );
+    }
+
+    #[test]
+    fn derived_timeout_is_clamped() {
+        assert_eq!(
+            derive_timeout(Duration::from_millis(50), CEILING),
+            MIN_HOST_TIMEOUT
+        );
+        assert_eq!(derive_timeout(Duration::from_secs(3), CEILING), CEILING);
+        assert_eq!(derive_timeout(Duration::MAX, CEILING), CEILING);
+        assert_eq!(
+            derive_timeout(Duration::from_millis(50), Duration::from_secs(1)),
+            Duration::from_secs(1)
+        );
+    }
+
+    #[test]
+    fn hosts_learn_from_their_first_response_only() {
+        let timeouts = HostTimeouts::new(CEILING);
+        assert_eq!(timeouts.timeout_for("fast.example"), CEILING);
+
+        timeouts.record("fast.example", Duration::from_millis(600));
+        timeouts.record("fast.example", Duration::from_millis(100));
+        timeouts.record("slow.example", Duration::from_secs(5));
+
+        assert_eq!(timeouts.timeout_for("fast.example"), Duration::from_secs(3));
+        assert_eq!(timeouts.timeout_for("slow.example"), CEILING);
+        assert_eq!(timeouts.timeout_for("other.example"), CEILING);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::model::BookmarkError;
+use crate::pattern::wildcard_match;
+use std::fs;
+use std::path::Path;
+
+pub const IGNORE_FILE: &str = ".bookmarkignore";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct IgnoreList {
+    patterns: Vec<String>,
+}
+
+impl IgnoreList {
+    pub(crate) fn load(path: &Path) -> Result<Self, BookmarkError> {
+        let contents = fs::read_to_string(path)?;
+        Ok(Self::parse(&contents))
+    }
+
+    pub(crate) fn parse(contents: &str) -> Self {
+        let patterns = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+
+        Self { patterns }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub(crate) fn is_ignored(&self, url: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| wildcard_match(pattern, url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_skips_comments_and_blank_lines() {
+        let list =
+            IgnoreList::parse("# intranet\n\nhttps://intranet.local/*\n  https://keep.me  \n");
+        assert_eq!(
+            list.patterns,
+            vec!["https://intranet.local/*", "https://keep.me"]
+        );
+    }
+
+    #[test]
+    fn matches_exact_urls_and_wildcards() {
+        let list = IgnoreList::parse("https://keep.me\nhttps://*.corp.example/*");
+        assert!(list.is_ignored("https://keep.me"));
+        assert!(!list.is_ignored("https://keep.me/other"));
+        assert!(list.is_ignored("https://wiki.corp.example/page"));
+        assert!(!list.is_ignored("https://corp.example.org/page"));
+    }
+}
@@ -1,13 +1,42 @@
+mod atomic;
+mod autotune;
+mod backup;
+mod certificate;
 mod checker;
 mod cleaner;
+mod diff;
+mod host_timeout;
+mod ignore;
 mod locator;
+mod mixed_content;
 mod model;
+mod opener;
 mod parser;
+mod pattern;
 mod progress;
 mod report;
 mod runner;
+mod sample;
+mod state;
+mod stats;
+mod stream;
+#[cfg(test)]
+mod test_support;
+mod tracking;
 mod version;
+#[cfg(feature = "webhook")]
+mod webhook;
 
-pub use model::{Bookmark, BookmarkError, BookmarkLocation, RunConfig};
+pub use checker::{FailureKind, FailureReason, LinkFailure};
+pub use ignore::IGNORE_FILE;
+pub use locator::{bookmarks_location, detect_browsers};
+pub use model::{
+    Bookmark, BookmarkError, BookmarkLocation, Browser, ColorChoice, Concurrency, InputFormat,
+    NormalizedUrl, Platform, ReportFormat, RequestHeader, RetryKind, RunConfig, ScanOutcome,
+    SortOrder, TimeoutPolicy,
+};
+pub use report::{FAILURE_REPORT_FILE, FailureReporter, Reporter};
 pub use runner::{gather_bookmarks, gather_bookmarks_for_profile, run, run_with_config};
 pub use version::VERSION;
+#[cfg(feature = "webhook")]
+pub use webhook::WebhookReporter;
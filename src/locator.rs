@@ -1,45 +1,191 @@
-use crate::{BookmarkError, BookmarkLocation};
+use crate::pattern::wildcard_match;
+use crate::{BookmarkError, BookmarkLocation, Browser, Platform};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Default-profile bookmarks of `browser` for a user whose home directory is `home`,
+/// laid out the way `platform` stores them. No environment overrides apply.
+pub fn bookmarks_location(platform: Platform, browser: Browser, home: &Path) -> BookmarkLocation {
+    location_for(platform, browser, home)
+}
+
+/// Supported browsers installed for the current user, judged by whether each one's
+/// default profile directory exists. Empty on platforms without a known layout.
+pub fn detect_browsers() -> Vec<Browser> {
+    Platform::current()
+        .zip(dirs::home_dir())
+        .map(|(platform, home)| detect_browsers_in(platform, &home))
+        .unwrap_or_default()
+}
+
+fn detect_browsers_in(platform: Platform, home: &Path) -> Vec<Browser> {
+    Browser::ALL
+        .into_iter()
+        .filter(|&browser| location_for(platform, browser, home).directory.is_dir())
+        .collect()
+}
+
+fn location_for(platform: Platform, browser: Browser, home: &Path) -> BookmarkLocation {
+    let directory = browser_data_dir(platform, browser, home).join("Default");
+    BookmarkLocation {
+        file: directory.join("Bookmarks"),
+        directory,
+    }
+}
+
+fn browser_data_dir(platform: Platform, browser: Browser, home: &Path) -> PathBuf {
+    let segments: &[&str] = match (platform, browser) {
+        (Platform::MacOs, Browser::Chrome) => {
+            &["Library", "Application Support", "Google", "Chrome"]
+        }
+        (Platform::MacOs, Browser::Edge) => &["Library", "Application Support", "Microsoft Edge"],
+        (Platform::Linux, Browser::Chrome) => &[".config", "google-chrome"],
+        (Platform::Linux, Browser::Edge) => &[".config", "microsoft-edge"],
+        (Platform::Windows, Browser::Chrome) => {
+            &["AppData", "Local", "Google", "Chrome", "User Data"]
+        }
+        (Platform::Windows, Browser::Edge) => {
+            &["AppData", "Local", "Microsoft", "Edge", "User Data"]
+        }
+    };
+
+    segments
+        .iter()
+        .fold(home.to_path_buf(), |path, segment| path.join(segment))
+}
+
 pub(crate) fn locate() -> Result<BookmarkLocation, BookmarkError> {
-    let directory = bookmarks_directory().ok_or(BookmarkError::UnsupportedPlatform)?;
-    let file = bookmarks_file().ok_or(BookmarkError::UnsupportedPlatform)?;
+    let directory = bookmarks_directory()?;
+    let file = bookmarks_file()?;
     Ok(BookmarkLocation { directory, file })
 }
 
-pub(crate) fn bookmarks_directory() -> Option<PathBuf> {
+pub(crate) fn bookmarks_directory() -> Result<PathBuf, BookmarkError> {
     platform::bookmarks_dir()
 }
 
-pub(crate) fn bookmarks_file() -> Option<PathBuf> {
+pub(crate) fn bookmarks_file() -> Result<PathBuf, BookmarkError> {
     platform::bookmarks_file()
 }
 
-pub(crate) fn list_profiles() -> Result<Vec<BookmarkLocation>, BookmarkError> {
-    let root = profiles_root()?;
-    collect_profiles_from(&root)
+const SYSTEM_PROFILES: [&str; 2] = ["System Profile", "Guest Profile"];
+const DEFAULT_PROFILE_ALIAS: &str = "default";
+const DEFAULT_PROFILE_DIR: &str = "Default";
+const CHROME_BACKUP_FILE: &str = "Bookmarks.bak";
+
+/// Profiles under `user_data_dir` when given (`--user-data-dir`), otherwise under
+/// the platform's Chrome user data directory.
+pub(crate) fn list_profiles(
+    include_system: bool,
+    user_data_dir: Option<&Path>,
+) -> Result<Vec<BookmarkLocation>, BookmarkError> {
+    let root = profiles_root(user_data_dir)?;
+    collect_profiles_from(&root, include_system)
 }
 
-pub(crate) fn locate_profile(profile: Option<&str>) -> Result<BookmarkLocation, BookmarkError> {
+pub(crate) fn list_profiles_matching(
+    pattern: &str,
+    include_system: bool,
+    user_data_dir: Option<&Path>,
+) -> Result<Vec<BookmarkLocation>, BookmarkError> {
+    let profiles = list_profiles(include_system, user_data_dir)?;
+    Ok(filter_profiles_by_glob(profiles, pattern))
+}
+
+pub(crate) fn locate_profile(
+    profile: Option<&str>,
+    user_data_dir: Option<&Path>,
+) -> Result<BookmarkLocation, BookmarkError> {
     match profile {
-        None => locate(),
+        None => default_profile(user_data_dir),
         Some(name) => {
-            let root = profiles_root()?;
-            find_profile_by_name(&root, name)
+            let root = profiles_root(user_data_dir)?;
+            find_profile_by_name(&root, name, user_data_dir)
         }
     }
 }
 
-fn profiles_root() -> Result<PathBuf, BookmarkError> {
-    let default_dir = bookmarks_directory().ok_or(BookmarkError::UnsupportedPlatform)?;
+/// The profile at zero-based `index` in `--list-profiles` order.
+pub(crate) fn locate_profile_at(
+    index: usize,
+    include_system: bool,
+    user_data_dir: Option<&Path>,
+) -> Result<BookmarkLocation, BookmarkError> {
+    let root = profiles_root(user_data_dir)?;
+    find_profile_by_index(&root, index, include_system)
+}
+
+/// The profile whose `Bookmarks` file changed last, usually the one in daily use.
+pub(crate) fn locate_most_recent_profile(
+    include_system: bool,
+    user_data_dir: Option<&Path>,
+) -> Result<BookmarkLocation, BookmarkError> {
+    let root = profiles_root(user_data_dir)?;
+    find_most_recent_profile(&root, include_system, user_data_dir)
+}
+
+pub(crate) fn locate_profiles(
+    names: &[String],
+    user_data_dir: Option<&Path>,
+) -> Result<Vec<BookmarkLocation>, BookmarkError> {
+    let root = profiles_root(user_data_dir)?;
+    find_profiles_by_name(&root, names, user_data_dir)
+}
+
+/// Best-effort check for a running Chrome: it holds a lock file in the user data
+/// directory while open. A crash can leave a stale lock, so treat this as a hint.
+pub(crate) fn chrome_appears_running(location: &BookmarkLocation) -> bool {
+    location
+        .directory
+        .parent()
+        .map(|root| {
+            ["SingletonLock", "lockfile"]
+                .iter()
+                .any(|name| fs::symlink_metadata(root.join(name)).is_ok())
+        })
+        .unwrap_or(false)
+}
+
+/// The same profile read from `Bookmarks.bak`, the copy Chrome keeps of the previous
+/// `Bookmarks` file.
+pub(crate) fn chrome_backup(location: &BookmarkLocation) -> BookmarkLocation {
+    BookmarkLocation {
+        directory: location.directory.clone(),
+        file: location.directory.join(CHROME_BACKUP_FILE),
+    }
+}
+
+fn profiles_root(user_data_dir: Option<&Path>) -> Result<PathBuf, BookmarkError> {
+    if let Some(root) = user_data_dir {
+        return Ok(root.to_path_buf());
+    }
+
+    let default_dir = bookmarks_directory()?;
     default_dir
         .parent()
         .map(|parent| parent.to_path_buf())
         .ok_or_else(|| BookmarkError::MissingBookmarksDir(default_dir))
 }
 
-fn collect_profiles_from(root: &Path) -> Result<Vec<BookmarkLocation>, BookmarkError> {
+/// The profile Chrome opens without `--profile-directory`: `Default` inside an
+/// overridden user data directory, or the platform default otherwise.
+fn default_profile(user_data_dir: Option<&Path>) -> Result<BookmarkLocation, BookmarkError> {
+    match user_data_dir {
+        Some(root) => {
+            let directory = root.join(DEFAULT_PROFILE_DIR);
+            Ok(BookmarkLocation {
+                file: directory.join("Bookmarks"),
+                directory,
+            })
+        }
+        None => locate(),
+    }
+}
+
+fn collect_profiles_from(
+    root: &Path,
+    include_system: bool,
+) -> Result<Vec<BookmarkLocation>, BookmarkError> {
     if !root.exists() {
         return Ok(Vec::new());
     }
@@ -50,6 +196,10 @@ fn collect_profiles_from(root: &Path) -> Result<Vec<BookmarkLocation>, BookmarkE
         let entry = entry?;
         if entry.file_type()?.is_dir() {
             let directory = entry.path();
+            if !include_system && is_system_profile(&directory) {
+                continue;
+            }
+
             let file = directory.join("Bookmarks");
             if file.exists() {
                 profiles.push(BookmarkLocation { directory, file });
@@ -62,9 +212,99 @@ fn collect_profiles_from(root: &Path) -> Result<Vec<BookmarkLocation>, BookmarkE
     Ok(profiles)
 }
 
-fn find_profile_by_name(root: &Path, name: &str) -> Result<BookmarkLocation, BookmarkError> {
+fn is_system_profile(directory: &Path) -> bool {
+    directory
+        .file_name()
+        .and_then(|value| value.to_str())
+        .map(|name| SYSTEM_PROFILES.contains(&name))
+        .unwrap_or(false)
+}
+
+fn filter_profiles_by_glob(
+    profiles: Vec<BookmarkLocation>,
+    pattern: &str,
+) -> Vec<BookmarkLocation> {
+    let pattern = pattern.to_ascii_lowercase();
+
+    profiles
+        .into_iter()
+        .filter(|profile| {
+            profile
+                .directory
+                .file_name()
+                .and_then(|value| value.to_str())
+                .map(|name| wildcard_match(&pattern, &name.to_ascii_lowercase()))
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+fn find_profiles_by_name(
+    root: &Path,
+    names: &[String],
+    user_data_dir: Option<&Path>,
+) -> Result<Vec<BookmarkLocation>, BookmarkError> {
+    let mut locations: Vec<BookmarkLocation> = Vec::with_capacity(names.len());
+
+    for name in names {
+        let location = find_profile_by_name(root, name, user_data_dir)?;
+        if !locations.contains(&location) {
+            locations.push(location);
+        }
+    }
+
+    Ok(locations)
+}
+
+fn find_profile_by_index(
+    root: &Path,
+    index: usize,
+    include_system: bool,
+) -> Result<BookmarkLocation, BookmarkError> {
+    let profiles = collect_profiles_from(root, include_system)?;
+    let count = profiles.len();
+    profiles
+        .into_iter()
+        .nth(index)
+        .ok_or(BookmarkError::ProfileIndexOutOfRange(index, count))
+}
+
+/// Falls back to the default profile when no modification time can be read.
+fn find_most_recent_profile(
+    root: &Path,
+    include_system: bool,
+    user_data_dir: Option<&Path>,
+) -> Result<BookmarkLocation, BookmarkError> {
+    let newest = collect_profiles_from(root, include_system)?
+        .into_iter()
+        .filter_map(|profile| {
+            let modified = fs::metadata(&profile.file)
+                .and_then(|metadata| metadata.modified())
+                .ok()?;
+            Some((modified, profile))
+        })
+        .max_by_key(|(modified, _)| *modified);
+
+    match newest {
+        Some((_, profile)) => Ok(profile),
+        None => default_profile(user_data_dir),
+    }
+}
+
+fn find_profile_by_name(
+    root: &Path,
+    name: &str,
+    user_data_dir: Option<&Path>,
+) -> Result<BookmarkLocation, BookmarkError> {
+    // "default" means whatever profile Chrome opens without `--profile`, even when
+    // that directory is not literally named "Default".
+    if name.eq_ignore_ascii_case(DEFAULT_PROFILE_ALIAS) {
+        return default_profile(user_data_dir);
+    }
+
     let target = name.to_ascii_lowercase();
-    let profiles = collect_profiles_from(root)?;
+    // An explicitly named profile is always honored, even if it is a system profile.
+    let profiles = collect_profiles_from(root, true)?;
 
     profiles
         .into_iter()
@@ -83,16 +323,20 @@ fn find_profile_by_name(root: &Path, name: &str) -> Result<BookmarkLocation, Boo
 mod platform {
     use super::*;
 
-    pub(super) fn bookmarks_dir() -> Option<PathBuf> {
-        dirs::home_dir().map(|home| bookmarks_dir_from_home(home.as_path()))
+    pub(super) fn bookmarks_dir() -> Result<PathBuf, BookmarkError> {
+        dirs::home_dir()
+            .map(|home| bookmarks_dir_from_home(home.as_path()))
+            .ok_or(BookmarkError::HomeDirUnavailable)
     }
 
-    pub(super) fn bookmarks_file() -> Option<PathBuf> {
-        dirs::home_dir().map(|home| bookmarks_file_from_home(home.as_path()))
+    pub(super) fn bookmarks_file() -> Result<PathBuf, BookmarkError> {
+        dirs::home_dir()
+            .map(|home| bookmarks_file_from_home(home.as_path()))
+            .ok_or(BookmarkError::HomeDirUnavailable)
     }
 
     pub(super) fn bookmarks_dir_from_home(home: &Path) -> PathBuf {
-        home.join("Library/Application Support/Google/Chrome/Default")
+        location_for(Platform::MacOs, Browser::Chrome, home).directory
     }
 
     pub(super) fn bookmarks_file_from_home(home: &Path) -> PathBuf {
@@ -123,23 +367,55 @@ mod platform {
 #[cfg(target_os = "linux")]
 mod platform {
     use super::*;
+    use std::env;
 
-    pub(super) fn bookmarks_dir() -> Option<PathBuf> {
-        dirs::home_dir().map(|home| bookmarks_dir_from_home(home.as_path()))
+    pub(super) fn bookmarks_dir() -> Result<PathBuf, BookmarkError> {
+        match overridden_user_data_dir() {
+            Some(root) => Ok(root.join(DEFAULT_PROFILE_DIR)),
+            None => dirs::home_dir()
+                .map(|home| bookmarks_dir_from_home(home.as_path()))
+                .ok_or(BookmarkError::HomeDirUnavailable),
+        }
     }
 
-    pub(super) fn bookmarks_file() -> Option<PathBuf> {
-        dirs::home_dir().map(|home| bookmarks_file_from_home(home.as_path()))
+    pub(super) fn bookmarks_file() -> Result<PathBuf, BookmarkError> {
+        match overridden_user_data_dir() {
+            Some(root) => Ok(root.join(DEFAULT_PROFILE_DIR).join("Bookmarks")),
+            None => dirs::home_dir()
+                .map(|home| bookmarks_file_from_home(home.as_path()))
+                .ok_or(BookmarkError::HomeDirUnavailable),
+        }
     }
 
     pub(super) fn bookmarks_dir_from_home(home: &Path) -> PathBuf {
-        home.join(".config/google-chrome/Default")
+        location_for(Platform::Linux, Browser::Chrome, home).directory
     }
 
     pub(super) fn bookmarks_file_from_home(home: &Path) -> PathBuf {
         bookmarks_dir_from_home(home).join("Bookmarks")
     }
 
+    fn overridden_user_data_dir() -> Option<PathBuf> {
+        let user_data_dir = env::var_os("CHROME_USER_DATA_DIR").map(PathBuf::from);
+        let config_home = env::var_os("CHROME_CONFIG_HOME").map(PathBuf::from);
+        user_data_dir_from(user_data_dir.as_deref(), config_home.as_deref())
+    }
+
+    /// Mirrors Chrome's own resolution: `$CHROME_USER_DATA_DIR` wins, then
+    /// `$CHROME_CONFIG_HOME`; `None` leaves the default under the home directory.
+    pub(super) fn user_data_dir_from(
+        user_data_dir: Option<&Path>,
+        config_home: Option<&Path>,
+    ) -> Option<PathBuf> {
+        match (user_data_dir, config_home) {
+            (Some(dir), _) if !dir.as_os_str().is_empty() => Some(dir.to_path_buf()),
+            (_, Some(config)) if !config.as_os_str().is_empty() => {
+                Some(config.join("google-chrome"))
+            }
+            _ => None,
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
@@ -156,6 +432,29 @@ mod platform {
                 PathBuf::from("/home/example/.config/google-chrome/Default/Bookmarks")
             );
         }
+
+        #[test]
+        fn user_data_dir_override_replaces_default_root() {
+            let override_dir = PathBuf::from("/opt/chrome-portable/data");
+            assert_eq!(
+                user_data_dir_from(Some(&override_dir), Some(Path::new("/srv/config"))),
+                Some(override_dir)
+            );
+            assert_eq!(user_data_dir_from(None, None), None);
+        }
+
+        #[test]
+        fn config_home_override_is_used_when_user_data_dir_unset() {
+            let config_home = PathBuf::from("/srv/config");
+            assert_eq!(
+                user_data_dir_from(None, Some(&config_home)),
+                Some(PathBuf::from("/srv/config/google-chrome"))
+            );
+            assert_eq!(
+                user_data_dir_from(Some(Path::new("")), Some(&config_home)),
+                Some(PathBuf::from("/srv/config/google-chrome"))
+            );
+        }
     }
 }
 
@@ -164,16 +463,19 @@ mod platform {
     use super::*;
     use std::env;
 
-    pub(super) fn bookmarks_dir() -> Option<PathBuf> {
+    // %LOCALAPPDATA% lives under the user's profile; without it there is no home to search.
+    pub(super) fn bookmarks_dir() -> Result<PathBuf, BookmarkError> {
         env::var_os("LOCALAPPDATA")
             .map(PathBuf::from)
             .map(|base| bookmarks_dir_from_local_app_data(base.as_path()))
+            .ok_or(BookmarkError::HomeDirUnavailable)
     }
 
-    pub(super) fn bookmarks_file() -> Option<PathBuf> {
+    pub(super) fn bookmarks_file() -> Result<PathBuf, BookmarkError> {
         env::var_os("LOCALAPPDATA")
             .map(PathBuf::from)
             .map(|base| bookmarks_file_from_local_app_data(base.as_path()))
+            .ok_or(BookmarkError::HomeDirUnavailable)
     }
 
     pub(super) fn bookmarks_dir_from_local_app_data(base: &Path) -> PathBuf {
@@ -212,26 +514,110 @@ mod platform {
     use super::*;
 
     #[allow(clippy::unnecessary_wraps)]
-    pub(super) fn bookmarks_dir() -> Option<PathBuf> {
-        None
+    pub(super) fn bookmarks_dir() -> Result<PathBuf, BookmarkError> {
+        Err(BookmarkError::UnsupportedPlatform)
     }
 
     #[allow(clippy::unnecessary_wraps)]
-    pub(super) fn bookmarks_file() -> Option<PathBuf> {
-        None
+    pub(super) fn bookmarks_file() -> Result<PathBuf, BookmarkError> {
+        Err(BookmarkError::UnsupportedPlatform)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::temp_dir;
     use std::fs;
     use std::path::PathBuf;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn browser_locations_follow_each_platform_layout() {
+        let home = Path::new("home");
+        let cases = [
+            (
+                Platform::MacOs,
+                Browser::Chrome,
+                &["Library", "Application Support", "Google", "Chrome"][..],
+            ),
+            (
+                Platform::MacOs,
+                Browser::Edge,
+                &["Library", "Application Support", "Microsoft Edge"][..],
+            ),
+            (
+                Platform::Linux,
+                Browser::Chrome,
+                &[".config", "google-chrome"][..],
+            ),
+            (
+                Platform::Linux,
+                Browser::Edge,
+                &[".config", "microsoft-edge"][..],
+            ),
+            (
+                Platform::Windows,
+                Browser::Chrome,
+                &["AppData", "Local", "Google", "Chrome", "User Data"][..],
+            ),
+            (
+                Platform::Windows,
+                Browser::Edge,
+                &["AppData", "Local", "Microsoft", "Edge", "User Data"][..],
+            ),
+        ];
+
+        for (platform, browser, segments) in cases {
+            let mut directory: PathBuf = home
+                .iter()
+                .chain(segments.iter().map(|s| s.as_ref()))
+                .collect();
+            directory.push("Default");
+
+            let location = location_for(platform, browser, home);
+            assert_eq!(location.directory, directory, "{platform:?} {browser:?}");
+            assert_eq!(location.file, directory.join("Bookmarks"));
+        }
+    }
+
+    #[test]
+    fn current_platform_is_known_exactly_where_bookmarks_can_be_located() {
+        assert_eq!(
+            Platform::current().is_some(),
+            !matches!(locate(), Err(BookmarkError::UnsupportedPlatform))
+        );
+    }
+
+    #[test]
+    fn detection_reports_browsers_whose_directory_exists() {
+        let home = temp_dir("bookmark-checker-profiles");
+        assert!(detect_browsers_in(Platform::Linux, &home).is_empty());
+
+        let edge = location_for(Platform::Linux, Browser::Edge, &home).directory;
+        fs::create_dir_all(&edge).unwrap();
+        assert_eq!(
+            detect_browsers_in(Platform::Linux, &home),
+            vec![Browser::Edge]
+        );
+        // Chrome on another platform's layout does not count.
+        fs::create_dir_all(location_for(Platform::MacOs, Browser::Chrome, &home).directory)
+            .unwrap();
+        assert_eq!(
+            detect_browsers_in(Platform::Linux, &home),
+            vec![Browser::Edge]
+        );
+
+        fs::create_dir_all(location_for(Platform::Linux, Browser::Chrome, &home).directory)
+            .unwrap();
+        assert_eq!(detect_browsers_in(Platform::Linux, &home), Browser::ALL);
+
+        fs::remove_dir_all(home).unwrap();
+    }
 
     #[test]
     fn collect_profiles_includes_directories_with_bookmarks() {
-        let root = temp_profile_root();
+        let root = temp_dir("bookmark-checker-profiles");
         let default_dir = root.join("Default");
         let profile_dir = root.join("Profile 1");
         let ignored_dir = root.join("System Profile");
@@ -244,7 +630,7 @@ mod tests {
 
         fs::create_dir_all(&ignored_dir).unwrap();
 
-        let profiles = collect_profiles_from(&root).expect("profiles should be collected");
+        let profiles = collect_profiles_from(&root, false).expect("profiles should be collected");
 
         assert_eq!(profiles.len(), 2);
         assert!(
@@ -261,24 +647,212 @@ mod tests {
         fs::remove_dir_all(&root).unwrap();
     }
 
+    #[test]
+    fn collect_profiles_skips_system_profiles_unless_requested() {
+        let root = temp_dir("bookmark-checker-profiles");
+        let default_dir = root.join("Default");
+        let system_dir = root.join("System Profile");
+
+        fs::create_dir_all(&default_dir).unwrap();
+        fs::write(default_dir.join("Bookmarks"), "{}").unwrap();
+
+        fs::create_dir_all(&system_dir).unwrap();
+        fs::write(system_dir.join("Bookmarks"), "{}").unwrap();
+
+        let profiles = collect_profiles_from(&root, false).expect("profiles should be collected");
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].directory, default_dir);
+
+        let profiles = collect_profiles_from(&root, true).expect("profiles should be collected");
+        assert_eq!(profiles.len(), 2);
+        assert!(
+            profiles
+                .iter()
+                .any(|profile| profile.directory == system_dir)
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn profile_glob_matches_prefix_pattern() {
+        let root = temp_dir("bookmark-checker-profiles");
+        for name in ["Work", "Work 2", "Personal"] {
+            let dir = root.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("Bookmarks"), "{}").unwrap();
+        }
+
+        let profiles = collect_profiles_from(&root, false).expect("profiles should be collected");
+        let matched = filter_profiles_by_glob(profiles, "Work*");
+
+        assert_eq!(
+            matched
+                .iter()
+                .map(|profile| profile.directory.clone())
+                .collect::<Vec<_>>(),
+            vec![root.join("Work"), root.join("Work 2")]
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn chrome_lock_file_marks_profile_as_in_use() {
+        let root = temp_dir("bookmark-checker-profiles");
+        let location = BookmarkLocation {
+            directory: root.join("Default"),
+            file: root.join("Default").join("Bookmarks"),
+        };
+
+        assert!(!chrome_appears_running(&location));
+
+        fs::write(root.join("lockfile"), "").unwrap();
+        assert!(chrome_appears_running(&location));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
     #[test]
     fn find_profile_by_name_is_case_insensitive() {
-        let root = temp_profile_root();
+        let root = temp_dir("bookmark-checker-profiles");
         let profile_dir = root.join("Profile 2");
 
         fs::create_dir_all(&profile_dir).unwrap();
         fs::write(profile_dir.join("Bookmarks"), "{}").unwrap();
 
-        let location = find_profile_by_name(&root, "profile 2").expect("profile should be found");
+        let location =
+            find_profile_by_name(&root, "profile 2", None).expect("profile should be found");
         assert_eq!(location.directory, profile_dir);
 
         fs::remove_dir_all(&root).unwrap();
     }
 
+    #[test]
+    fn profiles_are_selected_by_sorted_position() {
+        let root = temp_dir("bookmark-checker-profiles");
+        for name in ["Profile 2", "Default", "Profile 1", "System Profile"] {
+            let dir = root.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("Bookmarks"), "{}").unwrap();
+        }
+
+        let first = find_profile_by_index(&root, 0, false).expect("index 0");
+        let third = find_profile_by_index(&root, 2, false).expect("index 2");
+        assert_eq!(first.directory, root.join("Default"));
+        assert_eq!(third.directory, root.join("Profile 2"));
+
+        let system = find_profile_by_index(&root, 3, true).expect("system profile");
+        assert_eq!(system.directory, root.join("System Profile"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn out_of_range_profile_index_is_an_error() {
+        let root = temp_dir("bookmark-checker-profiles");
+        let dir = root.join("Default");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Bookmarks"), "{}").unwrap();
+
+        let err = find_profile_by_index(&root, 1, false).unwrap_err();
+        assert!(matches!(err, BookmarkError::ProfileIndexOutOfRange(1, 1)));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn most_recently_modified_profile_is_chosen() {
+        let root = temp_dir("bookmark-checker-profiles");
+        let now = SystemTime::now();
+        for (name, age) in [("Default", 300), ("Profile 1", 10), ("Profile 2", 60)] {
+            let dir = root.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            let file = fs::File::create(dir.join("Bookmarks")).unwrap();
+            file.set_modified(now - Duration::from_secs(age)).unwrap();
+        }
+
+        let newest = find_most_recent_profile(&root, false, Some(&root)).expect("profile");
+        assert_eq!(newest.directory, root.join("Profile 1"));
+
+        fs::remove_dir_all(&root).unwrap();
+
+        let empty = temp_dir("bookmark-checker-profiles");
+        let fallback = find_most_recent_profile(&empty, false, Some(&empty)).expect("default");
+        assert_eq!(fallback.directory, empty.join("Default"));
+        fs::remove_dir_all(&empty).unwrap();
+    }
+
+    #[test]
+    fn repeated_profile_names_resolve_each_location_once() {
+        let root = temp_dir("bookmark-checker-profiles");
+        for name in ["Work", "Personal", "Other"] {
+            let dir = root.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("Bookmarks"), "{}").unwrap();
+        }
+
+        let names = ["work", "Personal", "Work"].map(String::from);
+        let locations =
+            find_profiles_by_name(&root, &names, None).expect("profiles should be found");
+        assert_eq!(
+            locations
+                .iter()
+                .map(|location| location.directory.clone())
+                .collect::<Vec<_>>(),
+            vec![root.join("Work"), root.join("Personal")]
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn user_data_dir_override_replaces_the_profiles_root() {
+        let root = temp_dir("bookmark-checker-profiles");
+        for name in ["Default", "Profile 1"] {
+            let dir = root.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("Bookmarks"), "{}").unwrap();
+        }
+
+        let profiles = list_profiles(false, Some(&root)).expect("profiles");
+        assert_eq!(
+            profiles
+                .iter()
+                .map(|location| location.directory.clone())
+                .collect::<Vec<_>>(),
+            vec![root.join("Default"), root.join("Profile 1")]
+        );
+
+        let named = locate_profile(Some("profile 1"), Some(&root)).expect("named profile");
+        assert_eq!(named.directory, root.join("Profile 1"));
+
+        let default = locate_profile(None, Some(&root)).expect("default profile");
+        assert_eq!(default.file, root.join("Default").join("Bookmarks"));
+        assert_eq!(
+            locate_profile(Some("default"), Some(&root)).expect("alias"),
+            default
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn default_alias_resolves_like_no_profile() {
+        let root = temp_dir("bookmark-checker-profiles");
+
+        for name in ["default", "Default", "DEFAULT"] {
+            let location = find_profile_by_name(&root, name, None).expect("alias should resolve");
+            assert_eq!(location, locate().expect("default location"));
+        }
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
     #[test]
     fn find_profile_by_name_errors_for_unknown_profile() {
-        let root = temp_profile_root();
-        let err = find_profile_by_name(&root, "Missing").expect_err("should error");
+        let root = temp_dir("bookmark-checker-profiles");
+        let err = find_profile_by_name(&root, "Missing", None).expect_err("should error");
         match err {
             BookmarkError::ProfileNotFound(name) => assert_eq!(name, "Missing"),
             other => panic!("unexpected error: {other:?}"),
@@ -286,15 +860,4 @@ mod tests {
 
         fs::remove_dir_all(&root).unwrap();
     }
-
-    fn temp_profile_root() -> PathBuf {
-        let mut root = std::env::temp_dir();
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        root.push(format!("bookmark-checker-profiles-{unique}"));
-        fs::create_dir_all(&root).unwrap();
-        root
-    }
 }
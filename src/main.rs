@@ -1,5 +1,10 @@
-use bookmark_checker::{RunConfig, VERSION, run_with_config};
+use bookmark_checker::{
+    ColorChoice, InputFormat, ReportFormat, RequestHeader, RetryKind, RunConfig, TimeoutPolicy,
+    VERSION, run_with_config,
+};
+use chrono::{DateTime, Utc};
 use std::env;
+use std::path::PathBuf;
 use std::process;
 
 const HELP: &str = r#"bookmark-checker — audit Chrome bookmarks for unreachable URLs.
@@ -7,14 +12,146 @@ const HELP: &str = r#"bookmark-checker — audit Chrome bookmarks for unreachabl
 USAGE:
     bookmark-checker --scan [OPTIONS]    (alias: -s)
     bookmark-checker --list-profiles
+    bookmark-checker --detect
+    bookmark-checker --count [--profile <name>]
+    bookmark-checker --validate-only [--profile <name>]
     bookmark-checker --clean [--profile <name>]
+    bookmark-checker --normalize [--profile <name>]
+    bookmark-checker --dedupe-write [--profile <name>]
+    bookmark-checker --normalize-names [--profile <name>]
+    bookmark-checker --list-backups [--profile <name>]
+    bookmark-checker --check-mixed-content [--suggest-https] [--profile <name>]
+    bookmark-checker --recheck <report.yml> [--update-report]
 
 OPTIONS:
     -s, --scan                   Check bookmarks and record unreachable URLs.
     -m, --max-bookmarks <count>  Limit how many bookmarks to check before stopping.
+        --depth <n>              Skip folders nested more than n levels deep (default: 61).
+        --bar-only               Only check bookmarks in the bookmarks bar.
+        --input-format <auto|json|html>
+                                 Read the bookmarks file as this format (default: auto,
+                                 judged from its first characters).
+        --use-chrome-backup      Read Chrome's Bookmarks.bak instead of Bookmarks.
+        --sample <count>         Check a random selection of this many bookmarks.
+        --shuffle                Check bookmarks in random order to spread load across hosts.
+        --seed <number>          Seed for --sample or --shuffle to reproduce a run.
+        --resume                 Skip URLs already listed in bookmark_failures.yml.
+        --since-last-run         Only check bookmarks added since the profile was last scanned.
+        --recheck-failures       Only check URLs listed in bookmark_failures.yml.
+        --plan                   List the bookmarks a scan would check, then exit.
+        --fail-fast              Stop checking at the first dead link.
+        --no-preflight           Scan without first checking that the network is reachable
+                                 (a HEAD to www.google.com, then www.cloudflare.com).
+        --preflight-url <url>    Check reachability with a HEAD to this URL instead, e.g.
+                                 an intranet host.
+        --min-interval-between-runs <secs>
+                                 Refuse to scan if the last recorded scan started less than
+                                 this many seconds ago.
+        --force                  Scan even within --min-interval-between-runs.
+        --limit-time <secs>      Stop starting checks after this many seconds and report
+                                 what was checked.
+        --timeout <secs>         Give up on a bookmark after this many seconds (default: 10).
+        --connect-timeout <secs> Give up connecting to a host after this many seconds.
+        --concurrency <n>        Check this many bookmarks at once (0 = RAYON_NUM_THREADS or
+                                 one per CPU, max 512).
+        --parallelism-auto-tune  Back off concurrency when connection errors spike.
+        --pool-max-idle-per-host <n>
+                                 Keep at most n idle connections open per host
+                                 (default: no limit; 0 disables reuse).
+        --max-response-size <bytes>
+                                 Read at most this many bytes of a page before hanging up.
+        --if-modified-since <time>
+                                 Send "If-Modified-Since: <time>" (RFC 3339, e.g.
+                                 2024-05-01T00:00:00Z); 304 Not Modified counts as working.
+        --head-only              Send HEAD requests only; report 405 responses as failures.
+        --head-first             Send a HEAD first; only pages and failed HEADs get a GET.
+        --head-timeout <secs>    With --head-only or --head-first, limit each HEAD request
+                                 (default: half of --timeout).
+        --check-parked           Flag bookmarks that redirect to a domain parking page.
+        --check-ssl-expiry <days>
+                                 Warn about HTTPS sites whose certificate expires within
+                                 this many days.
+        --suggest-updates        List working bookmarks that redirect, with the URL to update to.
+        --max-redirects <n>      Follow at most this many redirects (default: 10; 0 disables).
+        --retries <n>            Try again up to n times when a check fails transiently.
+        --retry-kinds <list>     Outcomes --retries repeats (default: connection,server_error;
+                                 also not_found, gone, unauthorized).
+        --timeout-policy <global|per-host>
+                                 per-host limits each host to a multiple of its first
+                                 response time, up to --timeout (default: global).
+        --header <'Name: Value'> Send this header with every check (repeatable).
+        --accept-language <lang> Send "Accept-Language: <lang>" with every check.
+        --trace-redirects        Record the URLs each failing bookmark redirected through.
+        --stream <path>          Append a JSON line per checked bookmark ("-" for stdout).
+        --format <yaml|sarif|junit>
+                                 Report format (default: yaml).
+        --sort <name|url|folder> Order report entries within each section (default: url).
+        --report-empty           Write the report even when every bookmark passes.
+        --append                 Merge failures into an existing YAML report instead of
+                                 replacing it.
+        --report-max-entries <n> Keep at most n entries per report section, counting the
+                                 rest under "omitted".
+        --open-report            Open the written report in its default application.
+        --no-write               Never create or modify files; print failures instead of
+                                 writing a report.
+        --group-by-host          Print failures grouped by host, most failures first.
+        --compact                Print only "bookmarks: <n> ok, <n> dead", without progress.
+        --color <auto|always|never>
+                                 Color listed failures by kind: red for not found/gone,
+                                 yellow for unauthorized, magenta for connection errors
+                                 (default: auto, which honors NO_COLOR).
+        --min-failures <count>   Exit with status 1 once at least this many bookmarks fail.
+        --coded-exit             Exit with a status whose bits name the failure kinds:
+                                 4 not found/gone, 8 unauthorized, 16 connection,
+                                 32 anything else. Statuses 1 (error) and 2 (usage
+                                 mistake) keep their meaning.
+        --webhook <url>          Post a failure summary to a Slack/Discord webhook
+                                 (requires the `webhook` feature).
+        --recheck <report.yml>   Re-verify only the URLs listed in an earlier report.
+        --update-report          With --recheck, rewrite the report with what is still broken.
+        --diff <report.yml>      After the scan, list URLs newly broken, fixed and still
+                                 broken since an earlier report.
     -l, --list-profiles          List detected Chrome profiles and exit.
-    -p, --profile <name>         Select a profile instead of the default "Default".
-    -c, --clean                  Remove bookmarks listed in bookmark_failures.yml.
+        --detect                 List the supported browsers installed for this user and exit.
+        --count                  Print bookmark counts per top-level folder and exit.
+        --validate-only          Parse the bookmarks file, print the count, and exit
+                                 (non-zero if it is malformed or empty).
+        --normalize              Strip tracking parameters (utm_*, fbclid, gclid) from bookmark
+                                 URLs, backing up the Bookmarks file first.
+        --dedupe-write           Remove bookmarks whose URL repeats an earlier bookmark's,
+                                 backing up the Bookmarks file first.
+        --normalize-names        Trim bookmark and folder names and collapse inner whitespace,
+                                 backing up the Bookmarks file first.
+        --list-backups           List Bookmarks backups written by --clean, --normalize,
+                                 --normalize-names and --dedupe-write, newest first.
+        --check-mixed-content    List bookmarks that use plain http:// and exit.
+        --suggest-https          With --check-mixed-content, test whether each https://
+                                 variant responds, so the bookmark can be upgraded.
+        --include-system-profiles
+                                 Include "System Profile" and "Guest Profile" when listing
+                                 or matching profiles.
+    -p, --profile <name>         Select a profile instead of the default ("default" selects it
+                                 explicitly). Repeat with --scan to check several profiles.
+        --profile-index <n>      Select the nth profile shown by --list-profiles (0 = first).
+        --profile-auto           Select the profile whose bookmarks were modified most recently.
+        --profile-glob <pattern> Scan every profile whose name matches (e.g. "Work*").
+        --user-data-dir <dir>    Find profiles in this Chrome "User Data" directory instead of
+                                 the default one (e.g. a copied or portable install).
+        --parallel-profiles <n>  Scan up to n of those profiles at once, writing
+                                 bookmark_failures-<profile>.yml for each.
+        --report-prefix <str>    With several profiles, name each profile's report
+                                 <str>-<profile>.<ext> instead.
+        --profile-report-stdout  With several profiles, print one YAML report keyed by
+                                 profile name instead of writing report files.
+    -c, --clean                  Remove gone and not-found bookmarks listed in
+                                 bookmark_failures.yml.
+        --clean-all              With --clean, also remove unauthorized and connection errors.
+        --stdin                  With --clean, remove the URLs read from stdin, one per line,
+                                 instead of those in the report.
+        --prune-empty-folders    With --clean, also remove folders the cleanup leaves empty.
+        --ignore-file <path>     Read URLs to never check or clean (default: .bookmarkignore).
+    -o, --output-dir <dir>       Write the failure report and cleanup backups to this directory.
+        --print-config           Print the effective configuration as YAML and exit.
     -V, -v, --version            Print the app version and exit.
     -h, --help                   Show this help text.
 
@@ -23,6 +160,8 @@ GUIDE:
     - Use `--max-bookmarks` with `--scan` to limit the number checked.
     - Run `--clean` after a scan writes bookmark_failures.yml to prune entries.
     - Use `--list-profiles` to discover Chrome profiles before scanning.
+    - Set BOOKMARK_CHECKER_PROFILE to use a profile by default instead of "Default".
+    - List URLs in .bookmarkignore (`*` wildcards, `#` comments) to skip them.
     - Run without flags or use `--help` anytime to view this message again.
 "#;
 
@@ -45,9 +184,32 @@ fn main() {
         return;
     }
 
-    if let Err(err) = run_with_config(config) {
-        eprintln!("{err}");
-        process::exit(1);
+    let min_failures = config.min_failures;
+    let coded_exit = config.coded_exit;
+
+    match run_with_config(config, None) {
+        Ok(outcome) => {
+            let failure_status = if coded_exit {
+                outcome.coded_exit_status()
+            } else {
+                1
+            };
+            if let Some(min) = min_failures {
+                if outcome.meets_failure_threshold(min) {
+                    eprintln!(
+                        "{} bookmark(s) failed, reaching the --min-failures threshold of {min}.",
+                        outcome.failures
+                    );
+                    process::exit(failure_status);
+                }
+            } else if coded_exit && failure_status != 0 {
+                process::exit(failure_status);
+            }
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
     }
 }
 
@@ -55,6 +217,7 @@ fn parse_args() -> Result<RunConfig, String> {
     let mut args = env::args().skip(1);
     let mut config = RunConfig {
         scan: false,
+        preflight: true,
         ..RunConfig::default()
     };
 
@@ -71,21 +234,424 @@ fn parse_args() -> Result<RunConfig, String> {
                 })?;
                 config.max_bookmarks = Some(parsed);
             }
+            "--sample" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--sample requires a numerical value".to_string())?;
+                let parsed = value.parse::<usize>().map_err(|_| {
+                    format!("Invalid sample size '{value}'. Expected a non-negative integer.")
+                })?;
+                config.sample = Some(parsed);
+            }
+            "--shuffle" => {
+                config.shuffle = true;
+            }
+            "--seed" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--seed requires a numerical value".to_string())?;
+                let parsed = value.parse::<u64>().map_err(|_| {
+                    format!("Invalid seed '{value}'. Expected a non-negative integer.")
+                })?;
+                config.seed = Some(parsed);
+            }
+            "--input-format" => {
+                let names = format!("auto, {}", InputFormat::NAMES.join(", "));
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("--input-format requires one of: {names}"))?;
+                config.input_format = if value.eq_ignore_ascii_case("auto") {
+                    None
+                } else {
+                    Some(value.parse().map_err(|_| {
+                        format!("Invalid input format '{value}'. Expected one of: {names}.")
+                    })?)
+                };
+            }
+            "--bar-only" => {
+                config.bar_only = true;
+            }
+            "--use-chrome-backup" => {
+                config.use_chrome_backup = true;
+            }
+            "--depth" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--depth requires a numerical value".to_string())?;
+                let parsed = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&depth| depth > 0)
+                    .ok_or_else(|| {
+                        format!("Invalid depth '{value}'. Expected a positive integer.")
+                    })?;
+                config.max_depth = Some(parsed);
+            }
             "--list-profiles" | "-l" => {
                 config.list_profiles = true;
             }
+            "--validate-only" => {
+                config.validate_only = true;
+            }
+            "--count" => {
+                config.count = true;
+            }
+            "--normalize-names" => {
+                config.normalize_names = true;
+            }
+            "--normalize" => {
+                config.normalize = true;
+            }
+            "--dedupe-write" => {
+                config.dedupe_write = true;
+            }
+            "--detect" => {
+                config.detect = true;
+            }
+            "--list-backups" => {
+                config.list_backups = true;
+            }
+            "--check-mixed-content" => {
+                config.check_mixed_content = true;
+            }
+            "--suggest-https" => {
+                config.suggest_https = true;
+            }
+            "--include-system-profiles" => {
+                config.include_system_profiles = true;
+            }
             "--profile" | "-p" => {
                 let value = args
                     .next()
                     .ok_or_else(|| "--profile requires a profile name".to_string())?;
-                config.profile = Some(value);
+                config.profiles.push(value);
+            }
+            "--profile-index" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--profile-index requires a numerical value".to_string())?;
+                let parsed = value.parse::<usize>().map_err(|_| {
+                    format!("Invalid profile index '{value}'. Expected a non-negative integer.")
+                })?;
+                config.profile_index = Some(parsed);
+            }
+            "--profile-auto" => {
+                config.profile_auto = true;
+            }
+            "--profile-glob" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--profile-glob requires a pattern".to_string())?;
+                config.profile_glob = Some(value);
+            }
+            "--user-data-dir" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--user-data-dir requires a directory".to_string())?;
+                config.user_data_dir = Some(PathBuf::from(value));
+            }
+            "--parallel-profiles" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--parallel-profiles requires a numerical value".to_string())?;
+                let parsed = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&limit| limit > 0)
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid parallel profile count '{value}'. Expected a positive integer."
+                        )
+                    })?;
+                config.parallel_profiles = Some(parsed);
+            }
+            "--profile-report-stdout" => {
+                config.profile_report_stdout = true;
+            }
+            "--report-prefix" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--report-prefix requires a file name prefix".to_string())?;
+                config.report_prefix = Some(value);
             }
             "--clean" | "-c" => {
                 config.clean = true;
             }
+            "--timeout" => {
+                config.timeout_secs = Some(parse_seconds(args.next(), "--timeout")?);
+            }
+            "--connect-timeout" => {
+                config.connect_timeout_secs =
+                    Some(parse_seconds(args.next(), "--connect-timeout")?);
+            }
+            "--head-timeout" => {
+                config.head_timeout_secs = Some(parse_seconds(args.next(), "--head-timeout")?);
+            }
+            "--concurrency" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--concurrency requires a numerical value".to_string())?;
+                config.concurrency = value.parse()?;
+            }
+            "--parallelism-auto-tune" => {
+                config.auto_tune = true;
+            }
+            "--check-parked" => {
+                config.check_parked = true;
+            }
+            "--suggest-updates" => {
+                config.suggest_updates = true;
+            }
+            "--check-ssl-expiry" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--check-ssl-expiry requires a number of days".to_string())?;
+                let parsed = value.parse::<u32>().map_err(|_| {
+                    format!("Invalid day count '{value}'. Expected a non-negative integer.")
+                })?;
+                config.check_ssl_expiry_days = Some(parsed);
+            }
+            "--head-only" => {
+                config.head_only = true;
+            }
+            "--head-first" => {
+                config.head_first = true;
+            }
+            "--trace-redirects" => {
+                config.trace_redirects = true;
+            }
+            "--pool-max-idle-per-host" => {
+                let value = args.next().ok_or_else(|| {
+                    "--pool-max-idle-per-host requires a numerical value".to_string()
+                })?;
+                let parsed = value.parse::<usize>().map_err(|_| {
+                    format!(
+                        "Invalid idle connection limit '{value}'. Expected a non-negative integer."
+                    )
+                })?;
+                config.pool_max_idle_per_host = Some(parsed);
+            }
+            "--max-response-size" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--max-response-size requires a numerical value".to_string())?;
+                let parsed = value.parse::<u64>().map_err(|_| {
+                    format!("Invalid response size '{value}'. Expected a non-negative integer.")
+                })?;
+                config.max_response_size = Some(parsed);
+            }
+            "--if-modified-since" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--if-modified-since requires a time".to_string())?;
+                let parsed = DateTime::parse_from_rfc3339(&value).map_err(|_| {
+                    format!(
+                        "Invalid time '{value}'. Expected RFC 3339, such as 2024-05-01T00:00:00Z."
+                    )
+                })?;
+                config.if_modified_since = Some(parsed.with_timezone(&Utc));
+            }
+            "--max-redirects" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--max-redirects requires a numerical value".to_string())?;
+                let parsed = value.parse::<usize>().map_err(|_| {
+                    format!("Invalid redirect limit '{value}'. Expected a non-negative integer.")
+                })?;
+                config.max_redirects = Some(parsed);
+            }
+            "--retries" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--retries requires a numerical value".to_string())?;
+                let parsed = value.parse::<u32>().map_err(|_| {
+                    format!("Invalid retry count '{value}'. Expected a non-negative integer.")
+                })?;
+                config.retries = Some(parsed);
+            }
+            "--retry-kinds" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--retry-kinds requires a comma-separated list".to_string())?;
+                config.retry_kinds = Some(RetryKind::parse_list(&value)?);
+            }
+            "--timeout-policy" => {
+                let value = args.next().ok_or_else(|| {
+                    format!(
+                        "--timeout-policy requires one of: {}",
+                        TimeoutPolicy::NAMES.join(", ")
+                    )
+                })?;
+                config.timeout_policy = Some(value.parse()?);
+            }
+            "--header" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--header requires 'Name: Value'".to_string())?;
+                config.headers.push(value.parse()?);
+            }
+            "--accept-language" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--accept-language requires a language tag".to_string())?;
+                config.headers.push(RequestHeader::accept_language(&value)?);
+            }
+            "--stream" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--stream requires a path or '-'".to_string())?;
+                config.stream = Some(PathBuf::from(value));
+            }
+            "--format" => {
+                let value = args.next().ok_or_else(|| {
+                    format!(
+                        "--format requires one of: {}",
+                        ReportFormat::NAMES.join(", ")
+                    )
+                })?;
+                config.format = Some(value.parse()?);
+            }
+            "--min-failures" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--min-failures requires a numerical value".to_string())?;
+                let parsed = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&count| count > 0)
+                    .ok_or_else(|| {
+                        format!("Invalid failure threshold '{value}'. Expected a positive integer.")
+                    })?;
+                config.min_failures = Some(parsed);
+            }
+            "--coded-exit" => {
+                config.coded_exit = true;
+            }
+            "--webhook" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--webhook requires a URL".to_string())?;
+                config.webhook = Some(value);
+            }
+            "--report-empty" => {
+                config.report_empty = true;
+            }
+            "--append" => {
+                config.append = true;
+            }
+            "--report-max-entries" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--report-max-entries requires a numerical value".to_string())?;
+                let parsed = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|max| *max > 0)
+                    .ok_or_else(|| {
+                        format!("Invalid entry limit '{value}'. Expected a positive integer.")
+                    })?;
+                config.report_max_entries = Some(parsed);
+            }
+            "--open-report" => {
+                config.open_report = true;
+            }
+            "--plan" => {
+                config.plan = true;
+            }
+            "--fail-fast" => {
+                config.fail_fast = true;
+            }
+            "--no-preflight" => {
+                config.preflight = false;
+            }
+            "--preflight-url" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--preflight-url requires a URL".to_string())?;
+                config.preflight_url = Some(value);
+            }
+            "--min-interval-between-runs" => {
+                config.min_interval_secs =
+                    Some(parse_seconds(args.next(), "--min-interval-between-runs")?);
+            }
+            "--force" => {
+                config.force = true;
+            }
+            "--limit-time" => {
+                config.limit_time_secs = Some(parse_seconds(args.next(), "--limit-time")?);
+            }
+            "--no-write" => {
+                config.no_write = true;
+            }
+            "--group-by-host" => {
+                config.group_by_host = true;
+            }
+            "--compact" => {
+                config.compact = true;
+            }
+            "--color" => {
+                let value = args.next().ok_or_else(|| {
+                    format!("--color requires one of: {}", ColorChoice::NAMES.join(", "))
+                })?;
+                config.color = Some(value.parse()?);
+            }
+            "--sort" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--sort requires one of: name, url, folder".to_string())?;
+                config.sort = Some(value.parse()?);
+            }
+            "--resume" => {
+                config.resume = true;
+            }
+            "--since-last-run" => {
+                config.since_last_run = true;
+            }
+            "--recheck-failures" => {
+                config.recheck_failures = true;
+            }
+            "--stdin" => {
+                config.clean_stdin = true;
+            }
+            "--clean-all" => {
+                config.clean_all = true;
+            }
+            "--prune-empty-folders" => {
+                config.prune_empty_folders = true;
+            }
+            "--ignore-file" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--ignore-file requires a path".to_string())?;
+                config.ignore_file = Some(PathBuf::from(value));
+            }
+            "--recheck" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--recheck requires a report path".to_string())?;
+                config.recheck = Some(PathBuf::from(value));
+            }
+            "--update-report" => {
+                config.update_report = true;
+            }
+            "--diff" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--diff requires a report path".to_string())?;
+                config.diff = Some(PathBuf::from(value));
+            }
+            "--output-dir" | "-o" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--output-dir requires a directory".to_string())?;
+                config.output_dir = Some(PathBuf::from(value));
+            }
             "--scan" | "-s" => {
                 config.scan = true;
             }
+            "--print-config" => {
+                config.print_config = true;
+            }
             "--version" | "-V" | "-v" => {
                 config.show_version = true;
             }
@@ -99,42 +665,17 @@ fn parse_args() -> Result<RunConfig, String> {
         }
     }
 
-    if config.clean && config.list_profiles {
-        return Err("--clean cannot be combined with --list-profiles".into());
-    }
-
-    if config.scan && config.clean {
-        return Err("--scan cannot be combined with --clean".into());
-    }
-
-    if config.scan && config.list_profiles {
-        return Err("--scan cannot be combined with --list-profiles".into());
-    }
-
-    if config.show_version
-        && (config.clean
-            || config.list_profiles
-            || config.max_bookmarks.is_some()
-            || config.profile.is_some()
-            || config.scan)
-    {
-        return Err("--version cannot be combined with other options".into());
-    }
-
-    if config.max_bookmarks.is_some() && !config.scan {
-        return Err("--max-bookmarks requires --scan".into());
-    }
+    config.validate()?;
 
-    if config.profile.is_some() && !config.scan && !config.clean {
-        return Err("--profile requires --scan or --clean".into());
-    }
+    Ok(config)
+}
 
-    if !config.scan && !config.clean && !config.list_profiles && !config.show_version {
-        // Without a primary action this should have been caught earlier. Treat as misuse.
-        return Err(
-            "No action provided. Use --scan, --clean, --list-profiles, or --version.".into(),
-        );
+fn parse_seconds(value: Option<String>, flag: &str) -> Result<u64, String> {
+    let value = value.ok_or_else(|| format!("{flag} requires a number of seconds"))?;
+    match value.parse::<u64>() {
+        Ok(seconds) if seconds > 0 => Ok(seconds),
+        _ => Err(format!(
+            "Invalid {flag} value '{value}'. Expected a positive number of seconds."
+        )),
     }
-
-    Ok(config)
 }
@@ -0,0 +1,124 @@
+use crate::checker::{ProbeOutcome, UrlProbe};
+use crate::model::Bookmark;
+use rayon::prelude::*;
+
+const INSECURE_SCHEME: &str = "http://";
+
+/// A bookmark served over plain HTTP, and whether its HTTPS variant answered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InsecureBookmark {
+    pub(crate) bookmark: Bookmark,
+    /// `None` when the HTTPS variant was not probed.
+    pub(crate) https_available: Option<bool>,
+}
+
+/// Bookmarks whose URL uses the `http://` scheme, in their original order.
+pub(crate) fn find_insecure(bookmarks: &[Bookmark]) -> Vec<InsecureBookmark> {
+    bookmarks
+        .iter()
+        .filter(|bookmark| https_variant(&bookmark.url).is_some())
+        .map(|bookmark| InsecureBookmark {
+            bookmark: bookmark.clone(),
+            https_available: None,
+        })
+        .collect()
+}
+
+/// Probes the `https://` variant of each insecure bookmark; a successful response
+/// means the bookmark can be upgraded safely.
+pub(crate) fn probe_https<P>(insecure: &mut [InsecureBookmark], probe: &P)
+where
+    P: UrlProbe + Sync,
+{
+    insecure.par_iter_mut().for_each(|entry| {
+        entry.https_available = https_variant(&entry.bookmark.url).map(|url| {
+            matches!(
+                probe.probe(&url),
+                ProbeOutcome::Response { status, .. } if status.is_success()
+            )
+        });
+    });
+}
+
+fn https_variant(url: &str) -> Option<String> {
+    let scheme = url.get(..INSECURE_SCHEME.len())?;
+    scheme
+        .eq_ignore_ascii_case(INSECURE_SCHEME)
+        .then(|| format!("https://{}", &url[INSECURE_SCHEME.len()..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::FailureReason;
+    use reqwest::StatusCode;
+
+    fn bookmark(url: &str) -> Bookmark {
+        Bookmark {
+            name: url.into(),
+            url: url.into(),
+            folder_path: Vec::new(),
+            date_added: None,
+        }
+    }
+
+    struct HttpsOnly(&'static str);
+
+    impl UrlProbe for HttpsOnly {
+        fn probe(&self, url: &str) -> ProbeOutcome {
+            if url == self.0 {
+                ProbeOutcome::Response {
+                    status: StatusCode::OK,
+                    bytes: 0,
+                    content_type: None,
+                    landing: None,
+                    certificate_expiry: None,
+                    final_url: None,
+                }
+            } else {
+                ProbeOutcome::Failed(FailureReason::Connect)
+            }
+        }
+    }
+
+    #[test]
+    fn finds_only_plain_http_bookmarks() {
+        let bookmarks: Vec<Bookmark> = [
+            "http://old.example/page",
+            "https://secure.example/",
+            "HTTP://SHOUTY.example/",
+            "ftp://files.example/",
+            "http:",
+        ]
+        .into_iter()
+        .map(bookmark)
+        .collect();
+
+        let urls: Vec<String> = find_insecure(&bookmarks)
+            .into_iter()
+            .map(|entry| entry.bookmark.url)
+            .collect();
+        assert_eq!(
+            urls,
+            vec!["http://old.example/page", "HTTP://SHOUTY.example/"]
+        );
+    }
+
+    #[test]
+    fn suggests_https_only_when_the_variant_responds() {
+        let bookmarks = vec![
+            bookmark("http://upgradable.example/path?q=1"),
+            bookmark("http://legacy.example/"),
+        ];
+        let mut insecure = find_insecure(&bookmarks);
+
+        probe_https(
+            &mut insecure,
+            &HttpsOnly("https://upgradable.example/path?q=1"),
+        );
+
+        let available: Vec<Option<bool>> =
+            insecure.iter().map(|entry| entry.https_available).collect();
+        assert_eq!(available, vec![Some(true), Some(false)]);
+    }
+}
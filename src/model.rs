@@ -1,53 +1,1375 @@
+use crate::checker::LinkFailure;
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+use reqwest::header::{ACCEPT_LANGUAGE, HeaderName, HeaderValue};
+use serde::Serialize;
 use std::error::Error as StdError;
 use std::fmt::{self, Display};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bookmark {
     pub name: String,
     pub url: String,
+    pub folder_path: Vec<String>,
+    /// When the bookmark was added, if the file records it.
+    pub date_added: Option<DateTime<Utc>>,
+}
+
+impl Bookmark {
+    pub fn normalized_url(&self) -> NormalizedUrl {
+        NormalizedUrl::new(&self.url)
+    }
+
+    /// The URL's host, lowercased as the URL standard requires; `None` when the
+    /// URL does not parse or has no host (such as `mailto:` links).
+    pub fn host(&self) -> Option<String> {
+        Url::parse(&self.url).ok()?.host_str().map(str::to_string)
+    }
+
+    /// Whether the URL is worth an HTTP request: only `http` and `https` URLs are,
+    /// never `chrome://`, `javascript:`, `file://` or empty ones.
+    pub fn is_checkable(&self) -> bool {
+        Url::parse(&self.url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    }
+}
+
+/// A URL normalized for comparison: the scheme and host are lowercased and
+/// trailing slashes are stripped from the path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NormalizedUrl(String);
+
+impl NormalizedUrl {
+    pub fn new(url: &str) -> Self {
+        let url = url.trim();
+        let Some((scheme, remainder)) = url.split_once("://") else {
+            return Self(url.to_string());
+        };
+
+        let authority_end = remainder.find(['/', '?', '#']).unwrap_or(remainder.len());
+        let (authority, rest) = remainder.split_at(authority_end);
+        let path_end = rest.find(['?', '#']).unwrap_or(rest.len());
+        let (path, suffix) = rest.split_at(path_end);
+
+        Self(format!(
+            "{}://{}{}{}",
+            scheme.to_ascii_lowercase(),
+            authority.to_ascii_lowercase(),
+            path.trim_end_matches('/'),
+            suffix
+        ))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for NormalizedUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BookmarkLocation {
     pub directory: PathBuf,
     pub file: PathBuf,
 }
 
-#[derive(Debug)]
-pub enum BookmarkError {
-    UnsupportedPlatform,
-    MissingBookmarksDir(PathBuf),
-    MissingBookmarksFile(PathBuf),
-    ProfileNotFound(String),
-    Io(io::Error),
-    InvalidFormat(serde_json::Error),
-    BookmarkSerialization(serde_json::Error),
-    HttpClientBuild(reqwest::Error),
-    ReportWrite(serde_yaml::Error),
-    ReportParse(serde_yaml::Error),
-}
+/// What a run found; only actions that check URLs report failures. Iterating a
+/// `&ScanOutcome` yields the failed bookmarks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanOutcome {
+    /// Bookmarks whose URL was checked, including the failures.
+    pub checked: usize,
+    pub failures: usize,
+    /// The `FailureKind::exit_bit` of every kind that failed, or'ed together.
+    pub failure_bits: i32,
+    /// The bookmarks that failed, in the order their profiles were scanned.
+    pub failed_links: Vec<LinkFailure>,
+}
+
+impl ScanOutcome {
+    /// How many bookmarks were checked, including the failures.
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+
+    /// How many checked bookmarks failed.
+    pub fn failed(&self) -> usize {
+        self.failures
+    }
+
+    /// Whether every checked bookmark passed.
+    pub fn is_clean(&self) -> bool {
+        self.failures == 0
+    }
+
+    /// Whether enough bookmarks failed to treat the run as failed.
+    pub fn meets_failure_threshold(&self, min_failures: usize) -> bool {
+        self.failures >= min_failures
+    }
+
+    /// The `--coded-exit` status: `failure_bits`, so 0 means nothing failed. The
+    /// kind bits start at 4, so a coded status never reads as status 1 (an error)
+    /// or 2 (a usage mistake).
+    pub fn coded_exit_status(&self) -> i32 {
+        self.failure_bits
+    }
+}
+
+impl<'a> IntoIterator for &'a ScanOutcome {
+    type Item = &'a LinkFailure;
+    type IntoIter = std::slice::Iter<'a, LinkFailure>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.failed_links.iter()
+    }
+}
+
+/// Chromium-based browsers whose profile layout is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Browser {
+    Chrome,
+    Edge,
+}
+
+impl Browser {
+    pub const ALL: [Browser; 2] = [Browser::Chrome, Browser::Edge];
+}
+
+/// Operating systems whose browser profile layout is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Platform {
+    MacOs,
+    Linux,
+    Windows,
+}
+
+impl Platform {
+    /// The platform this binary was built for, or `None` where no layout is known.
+    pub fn current() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            Some(Platform::MacOs)
+        } else if cfg!(target_os = "windows") {
+            Some(Platform::Windows)
+        } else if cfg!(target_os = "linux") {
+            Some(Platform::Linux)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for Browser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Browser::Chrome => "Google Chrome",
+            Browser::Edge => "Microsoft Edge",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum BookmarkError {
+    UnsupportedPlatform,
+    HomeDirUnavailable,
+    MissingBookmarksDir(PathBuf),
+    MissingBookmarksFile(PathBuf),
+    /// A Bookmarks file that parses but holds no bookmarks.
+    EmptyBookmarks(PathBuf),
+    /// A Bookmarks file with no content at all, zero bytes or only whitespace,
+    /// typically caught mid-write.
+    BlankBookmarksFile(PathBuf),
+    /// A Bookmarks file that ends before its JSON does.
+    TruncatedBookmarksFile(PathBuf, serde_json::Error),
+    /// Invalid JSON in a Bookmarks file, with the text around the error.
+    MalformedBookmarksFile(PathBuf, serde_json::Error, String),
+    /// A browser's HTML bookmark export where Chrome's JSON file was expected.
+    HtmlBookmarksExport(PathBuf),
+    /// Content that is neither Chrome's JSON nor an HTML export.
+    UnrecognizedBookmarksFile(PathBuf),
+    /// `--use-chrome-backup` was given but the profile has no `Bookmarks.bak`.
+    MissingChromeBackup(PathBuf),
+    ProfileNotFound(String),
+    /// A `--profile-index` and how many profiles were found.
+    ProfileIndexOutOfRange(usize, usize),
+    Io(io::Error),
+    InvalidFormat(serde_json::Error),
+    BookmarkSerialization(serde_json::Error),
+    HttpClientBuild(reqwest::Error),
+    ReportWrite(serde_yaml::Error),
+    ReportSerialization(serde_json::Error),
+    ReportParse(serde_yaml::Error),
+    ConfigSerialization(serde_yaml::Error),
+    StateFile(serde_yaml::Error),
+    WebhookDelivery(reqwest::Error),
+    /// The report that `--open-report` could not launch.
+    ReportOpen(PathBuf, io::Error),
+    ThreadPoolBuild(rayon::ThreadPoolBuildError),
+    /// A `RunConfig` rejected by `RunConfig::validate`, with its message.
+    InvalidConfig(String),
+    /// The pre-scan connectivity check reached none of its endpoints.
+    NoNetwork,
+    /// The latest recorded scan, within `--min-interval-between-runs` seconds.
+    RunTooSoon(DateTime<Utc>, u64),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Name,
+    #[default]
+    Url,
+    Folder,
+}
+
+impl FromStr for SortOrder {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "name" => Ok(SortOrder::Name),
+            "url" => Ok(SortOrder::Url),
+            "folder" => Ok(SortOrder::Folder),
+            _ => Err(format!(
+                "Invalid sort order '{value}'. Expected one of: name, url, folder."
+            )),
+        }
+    }
+}
+
+/// How many bookmarks are checked at once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(into = "usize")]
+pub enum Concurrency {
+    /// `RAYON_NUM_THREADS` when set, otherwise one worker per CPU; written as `0`.
+    #[default]
+    Auto,
+    Fixed(usize),
+}
+
+impl Concurrency {
+    /// More workers than this only piles up open sockets without speeding up checks.
+    pub const MAX: usize = 512;
+}
+
+impl From<Concurrency> for usize {
+    fn from(value: Concurrency) -> Self {
+        match value {
+            Concurrency::Auto => 0,
+            Concurrency::Fixed(workers) => workers,
+        }
+    }
+}
+
+impl FromStr for Concurrency {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.parse::<usize>() {
+            Ok(0) => Ok(Concurrency::Auto),
+            Ok(workers) if workers <= Self::MAX => Ok(Concurrency::Fixed(workers)),
+            _ => Err(format!(
+                "Invalid concurrency '{value}'. Expected 0 (auto) up to {}.",
+                Self::MAX
+            )),
+        }
+    }
+}
+
+/// Outcomes that `--retries` tries again, chosen with `--retry-kinds`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryKind {
+    /// Timeouts, DNS, TLS and refused connections.
+    Connection,
+    /// Any 5xx response.
+    ServerError,
+    NotFound,
+    Gone,
+    /// 401 and 403 responses.
+    Unauthorized,
+}
+
+impl RetryKind {
+    /// Transient by nature; a 404 or 410 is usually the server's final answer.
+    pub const DEFAULT: [RetryKind; 2] = [RetryKind::Connection, RetryKind::ServerError];
+    /// Values accepted by `FromStr`, in the order they are listed to users.
+    pub const NAMES: [&'static str; 5] = [
+        "connection",
+        "server_error",
+        "not_found",
+        "gone",
+        "unauthorized",
+    ];
+
+    /// Parses a comma-separated list such as `connection,server_error`.
+    pub fn parse_list(value: &str) -> Result<Vec<RetryKind>, String> {
+        let mut kinds = Vec::new();
+        for name in value
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let kind = name.parse()?;
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+
+        if kinds.is_empty() {
+            return Err("--retry-kinds requires at least one kind".into());
+        }
+        Ok(kinds)
+    }
+}
+
+impl FromStr for RetryKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "connection" => Ok(RetryKind::Connection),
+            "server_error" => Ok(RetryKind::ServerError),
+            "not_found" => Ok(RetryKind::NotFound),
+            "gone" => Ok(RetryKind::Gone),
+            "unauthorized" => Ok(RetryKind::Unauthorized),
+            _ => Err(format!(
+                "Invalid retry kind '{value}'. Expected one of: {}.",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// How long each check may take, chosen with `--timeout-policy`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeoutPolicy {
+    /// `--timeout` applies to every request.
+    #[default]
+    Global,
+    /// Each host gets a multiple of its first response time, up to `--timeout`.
+    PerHost,
+}
+
+impl TimeoutPolicy {
+    /// Values accepted by `FromStr`, in the order they are listed to users.
+    pub const NAMES: [&'static str; 2] = ["global", "per-host"];
+}
+
+impl FromStr for TimeoutPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "global" => Ok(TimeoutPolicy::Global),
+            "per-host" => Ok(TimeoutPolicy::PerHost),
+            _ => Err(format!(
+                "Invalid timeout policy '{value}'. Expected one of: {}.",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Whether failure listings are colored, chosen with `--color`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and `NO_COLOR` is unset.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Values accepted by `FromStr`, in the order they are listed to users.
+    pub const NAMES: [&'static str; 3] = ["auto", "always", "never"];
+
+    /// Resolves `Auto` against the environment; a non-empty `NO_COLOR` wins.
+    pub fn enabled(self, no_color: bool, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => is_terminal && !no_color,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!(
+                "Invalid color choice '{value}'. Expected one of: {}.",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// A header sent with every check, given as `--header 'Name: Value'`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(into = "String")]
+pub struct RequestHeader {
+    name: HeaderName,
+    value: HeaderValue,
+}
+
+impl RequestHeader {
+    pub fn new(name: &str, value: &str) -> Result<Self, String> {
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .map_err(|_| format!("Invalid header name '{}'.", name.trim()))?;
+        let value = HeaderValue::from_str(value.trim())
+            .map_err(|_| format!("Invalid value for header '{name}'."))?;
+        Ok(Self { name, value })
+    }
+
+    pub fn accept_language(language: &str) -> Result<Self, String> {
+        Self::new(ACCEPT_LANGUAGE.as_str(), language)
+    }
+
+    pub fn name(&self) -> &HeaderName {
+        &self.name
+    }
+
+    pub fn value(&self) -> &HeaderValue {
+        &self.value
+    }
+}
+
+impl FromStr for RequestHeader {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, header_value) = value
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid header '{value}'. Expected 'Name: Value'."))?;
+        Self::new(name, header_value)
+    }
+}
+
+impl From<RequestHeader> for String {
+    fn from(header: RequestHeader) -> Self {
+        format!(
+            "{}: {}",
+            header.name,
+            String::from_utf8_lossy(header.value.as_bytes())
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Yaml,
+    Sarif,
+    JUnit,
+}
+
+impl ReportFormat {
+    /// Values accepted by `FromStr`, in the order they are listed to users.
+    pub const NAMES: [&'static str; 3] = ["yaml", "sarif", "junit"];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ReportFormat::Yaml => "yml",
+            ReportFormat::Sarif => "sarif",
+            ReportFormat::JUnit => "xml",
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "yaml" | "yml" => Ok(ReportFormat::Yaml),
+            "sarif" => Ok(ReportFormat::Sarif),
+            "junit" => Ok(ReportFormat::JUnit),
+            _ => Err(format!(
+                "Invalid report format '{value}'. Expected one of: {}.",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// What a bookmarks file holds: judged from how its content starts, unless
+/// `--input-format` says.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputFormat {
+    /// Chrome's own `Bookmarks` file.
+    Json,
+    /// A "Export bookmarks" HTML file.
+    Html,
+}
+
+impl InputFormat {
+    /// Values accepted by `FromStr`, in the order they are listed to users.
+    pub const NAMES: [&'static str; 2] = ["json", "html"];
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(InputFormat::Json),
+            "html" => Ok(InputFormat::Html),
+            _ => Err(format!(
+                "Invalid input format '{value}'. Expected one of: {}.",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// Everything a run needs; `--print-config` shows it as YAML.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunConfig {
+    pub max_bookmarks: Option<usize>,
+    pub max_depth: Option<usize>,
+    /// Only collect bookmarks from the bookmarks bar.
+    pub bar_only: bool,
+    /// Read bookmarks files as this format instead of sniffing it.
+    pub input_format: Option<InputFormat>,
+    pub use_chrome_backup: bool,
+    pub sample: Option<usize>,
+    pub seed: Option<u64>,
+    pub shuffle: bool,
+    pub list_profiles: bool,
+    pub count: bool,
+    pub validate_only: bool,
+    pub normalize: bool,
+    pub dedupe_write: bool,
+    pub normalize_names: bool,
+    pub list_backups: bool,
+    pub detect: bool,
+    pub check_mixed_content: bool,
+    pub suggest_https: bool,
+    pub include_system_profiles: bool,
+    pub scan: bool,
+    pub profiles: Vec<String>,
+    pub profile_glob: Option<String>,
+    /// Chrome user data directory to find profiles in, instead of the platform default.
+    pub user_data_dir: Option<PathBuf>,
+    /// Select the profile at this zero-based position in `--list-profiles` order.
+    pub profile_index: Option<usize>,
+    /// Select the profile whose bookmarks changed most recently.
+    pub profile_auto: bool,
+    /// Scan this many profiles at once, with a report per profile.
+    pub parallel_profiles: Option<usize>,
+    /// Start of each per-profile report name, before `-<profile>.<ext>`.
+    pub report_prefix: Option<String>,
+    pub profile_report_stdout: bool,
+    pub clean: bool,
+    pub clean_all: bool,
+    pub clean_stdin: bool,
+    /// With `--clean`, also remove folders the cleanup leaves empty.
+    pub prune_empty_folders: bool,
+    pub resume: bool,
+    /// Print only a one-line summary of the scan, without progress bars.
+    pub compact: bool,
+    pub color: Option<ColorChoice>,
+    /// Only check bookmarks added since each profile's previous scan.
+    pub since_last_run: bool,
+    pub recheck_failures: bool,
+    pub recheck: Option<PathBuf>,
+    pub update_report: bool,
+    /// Earlier report that `--diff` compares this scan's failures with.
+    pub diff: Option<PathBuf>,
+    pub sort: Option<SortOrder>,
+    pub format: Option<ReportFormat>,
+    pub ignore_file: Option<PathBuf>,
+    pub output_dir: Option<PathBuf>,
+    pub report_empty: bool,
+    /// Merge failures into an existing report instead of replacing it.
+    pub append: bool,
+    /// Keep at most this many entries per report section.
+    pub report_max_entries: Option<usize>,
+    pub open_report: bool,
+    /// List what `--scan` would check, after every filter, instead of checking it.
+    pub plan: bool,
+    /// Stop checking once one bookmark has failed.
+    pub fail_fast: bool,
+    /// Check that the network is reachable before scanning. Off for library
+    /// callers; the command line turns it on unless `--no-preflight` is given.
+    pub preflight: bool,
+    /// Probe this URL for the connectivity check instead of the default endpoints.
+    pub preflight_url: Option<String>,
+    /// Refuse to scan this soon after the latest recorded scan.
+    pub min_interval_secs: Option<u64>,
+    /// Scan even within `min_interval_secs`.
+    pub force: bool,
+    /// Stop starting checks after this many seconds, reporting what was checked.
+    pub limit_time_secs: Option<u64>,
+    pub no_write: bool,
+    pub min_failures: Option<usize>,
+    pub coded_exit: bool,
+    pub webhook: Option<String>,
+    pub group_by_host: bool,
+    pub head_only: bool,
+    pub head_first: bool,
+    pub timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+    pub head_timeout_secs: Option<u64>,
+    pub concurrency: Concurrency,
+    pub auto_tune: bool,
+    pub check_parked: bool,
+    pub check_ssl_expiry_days: Option<u32>,
+    pub suggest_updates: bool,
+    pub max_redirects: Option<usize>,
+    /// Extra attempts for outcomes listed in `retry_kinds`.
+    pub retries: Option<u32>,
+    pub retry_kinds: Option<Vec<RetryKind>>,
+    pub timeout_policy: Option<TimeoutPolicy>,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub max_response_size: Option<u64>,
+    pub if_modified_since: Option<DateTime<Utc>>,
+    /// From `--header` and `--accept-language`, in the order given.
+    pub headers: Vec<RequestHeader>,
+    pub trace_redirects: bool,
+    pub stream: Option<PathBuf>,
+    pub show_version: bool,
+    #[serde(skip)]
+    pub print_config: bool,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            max_bookmarks: None,
+            max_depth: None,
+            bar_only: false,
+            input_format: None,
+            use_chrome_backup: false,
+            sample: None,
+            seed: None,
+            shuffle: false,
+            list_profiles: false,
+            count: false,
+            validate_only: false,
+            normalize: false,
+            dedupe_write: false,
+            normalize_names: false,
+            list_backups: false,
+            detect: false,
+            check_mixed_content: false,
+            suggest_https: false,
+            include_system_profiles: false,
+            scan: true,
+            profiles: Vec::new(),
+            profile_glob: None,
+            user_data_dir: None,
+            profile_index: None,
+            profile_auto: false,
+            parallel_profiles: None,
+            report_prefix: None,
+            profile_report_stdout: false,
+            clean: false,
+            clean_all: false,
+            clean_stdin: false,
+            prune_empty_folders: false,
+            resume: false,
+            compact: false,
+            color: None,
+            since_last_run: false,
+            recheck_failures: false,
+            recheck: None,
+            update_report: false,
+            diff: None,
+            sort: None,
+            format: None,
+            ignore_file: None,
+            output_dir: None,
+            report_empty: false,
+            append: false,
+            report_max_entries: None,
+            open_report: false,
+            plan: false,
+            fail_fast: false,
+            preflight: false,
+            preflight_url: None,
+            min_interval_secs: None,
+            force: false,
+            limit_time_secs: None,
+            no_write: false,
+            min_failures: None,
+            coded_exit: false,
+            webhook: None,
+            group_by_host: false,
+            head_only: false,
+            head_first: false,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+            head_timeout_secs: None,
+            concurrency: Concurrency::Auto,
+            auto_tune: false,
+            check_parked: false,
+            check_ssl_expiry_days: None,
+            suggest_updates: false,
+            max_redirects: None,
+            retries: None,
+            retry_kinds: None,
+            timeout_policy: None,
+            pool_max_idle_per_host: None,
+            max_response_size: None,
+            if_modified_since: None,
+            headers: Vec::new(),
+            trace_redirects: false,
+            stream: None,
+            show_version: false,
+            print_config: false,
+        }
+    }
+}
+
+impl RunConfig {
+    /// Whether `--stream -` sends the JSON lines to stdout.
+    pub(crate) fn streams_to_stdout(&self) -> bool {
+        self.stream.as_deref() == Some(Path::new("-"))
+    }
+
+    /// The first given flag that creates or modifies a file, which `--no-write`
+    /// refuses.
+    fn writing_flag(&self) -> Option<&'static str> {
+        let writing = [
+            (self.clean, "--clean"),
+            (self.normalize, "--normalize"),
+            (self.normalize_names, "--normalize-names"),
+            (self.dedupe_write, "--dedupe-write"),
+            (self.update_report, "--update-report"),
+            (self.report_empty, "--report-empty"),
+            (self.append, "--append"),
+            (self.report_max_entries.is_some(), "--report-max-entries"),
+            (self.open_report, "--open-report"),
+            (self.output_dir.is_some(), "--output-dir"),
+            (
+                self.stream
+                    .as_deref()
+                    .is_some_and(|path| path != Path::new("-")),
+                "--stream <path>",
+            ),
+        ];
+        writing
+            .into_iter()
+            .find(|(set, _)| *set)
+            .map(|(_, flag)| flag)
+    }
+
+    /// The action flags given, of which a run takes at most one.
+    fn selected_actions(&self) -> impl Iterator<Item = &'static str> {
+        [
+            (self.scan, "--scan"),
+            (self.clean, "--clean"),
+            (self.list_profiles, "--list-profiles"),
+            (self.count, "--count"),
+            (self.validate_only, "--validate-only"),
+            (self.normalize, "--normalize"),
+            (self.dedupe_write, "--dedupe-write"),
+            (self.normalize_names, "--normalize-names"),
+            (self.list_backups, "--list-backups"),
+            (self.detect, "--detect"),
+            (self.check_mixed_content, "--check-mixed-content"),
+            (self.recheck.is_some(), "--recheck"),
+        ]
+        .into_iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, flag)| flag)
+    }
+
+    /// Rejects option combinations that contradict each other or lack the action
+    /// they modify, with a message naming the command-line flags involved.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut actions = self.selected_actions();
+        if let (Some(first), Some(second)) = (actions.next(), actions.next()) {
+            return Err(format!("{first} cannot be combined with {second}"));
+        }
+
+        if self.suggest_https && !self.check_mixed_content {
+            return Err("--suggest-https requires --check-mixed-content".into());
+        }
+
+        if self.update_report && self.recheck.is_none() {
+            return Err("--update-report requires --recheck".into());
+        }
+
+        if self.diff.is_some() && !self.scan {
+            return Err("--diff requires --scan".into());
+        }
+
+        if self.diff.is_some() && (self.parallel_profiles.is_some() || self.compact) {
+            return Err("--diff cannot be combined with --parallel-profiles or --compact".into());
+        }
+
+        if self.show_version
+            && (self.selected_actions().next().is_some()
+                || self.include_system_profiles
+                || self.max_bookmarks.is_some()
+                || self.sample.is_some()
+                || self.seed.is_some()
+                || self.resume
+                || self.recheck_failures
+                || self.recheck.is_some()
+                || !self.profiles.is_empty()
+                || self.profile_glob.is_some()
+                || self.profile_index.is_some()
+                || self.profile_auto
+                || self.user_data_dir.is_some())
+        {
+            return Err("--version cannot be combined with other options".into());
+        }
+
+        if self.clean_all && !self.clean {
+            return Err("--clean-all requires --clean".into());
+        }
+
+        if self.clean_stdin && !self.clean {
+            return Err("--stdin requires --clean".into());
+        }
+
+        // The URLs on stdin carry no failure kinds for --clean-all to widen.
+        if self.clean_stdin && self.clean_all {
+            return Err("--stdin cannot be combined with --clean-all".into());
+        }
+
+        if self.prune_empty_folders && !self.clean {
+            return Err("--prune-empty-folders requires --clean".into());
+        }
+
+        if self.ignore_file.is_some() && !self.scan && !self.clean {
+            return Err("--ignore-file requires --scan or --clean".into());
+        }
+
+        if self.output_dir.is_some()
+            && !self.scan
+            && !self.clean
+            && !self.normalize
+            && !self.dedupe_write
+            && !self.normalize_names
+            && !self.list_backups
+        {
+            return Err(
+                "--output-dir requires --scan, --clean, --normalize, --dedupe-write, \
+                 --normalize-names, or --list-backups"
+                    .into(),
+            );
+        }
+
+        if (self.timeout_secs.is_some() || self.connect_timeout_secs.is_some())
+            && !self.scan
+            && !self.suggest_https
+        {
+            return Err("--timeout and --connect-timeout require --scan or --suggest-https".into());
+        }
+
+        if self.max_depth.is_some()
+            && !self.scan
+            && !self.check_mixed_content
+            && !self.count
+            && !self.validate_only
+        {
+            return Err(
+                "--depth requires --scan, --count, --validate-only or --check-mixed-content".into(),
+            );
+        }
+
+        if self.input_format.is_some()
+            && !self.scan
+            && !self.check_mixed_content
+            && !self.count
+            && !self.validate_only
+        {
+            return Err(
+                "--input-format requires --scan, --count, --validate-only or --check-mixed-content"
+                    .into(),
+            );
+        }
+
+        if self.bar_only && !self.scan && !self.check_mixed_content {
+            return Err("--bar-only requires --scan or --check-mixed-content".into());
+        }
+
+        if self.use_chrome_backup
+            && !(self.scan || self.count || self.validate_only || self.check_mixed_content)
+        {
+            return Err(
+                "--use-chrome-backup requires --scan, --count, --validate-only, or \
+                 --check-mixed-content"
+                    .into(),
+            );
+        }
+
+        if self.concurrency != Concurrency::Auto && !self.scan {
+            return Err("--concurrency requires --scan".into());
+        }
+
+        if self.auto_tune && !self.scan {
+            return Err("--parallelism-auto-tune requires --scan".into());
+        }
+
+        if self.head_only && !self.scan {
+            return Err("--head-only requires --scan".into());
+        }
+
+        if self.head_first && !self.scan {
+            return Err("--head-first requires --scan".into());
+        }
+
+        if self.head_first && self.head_only {
+            return Err("--head-first cannot be combined with --head-only".into());
+        }
+
+        if self.head_timeout_secs.is_some() && !self.head_only && !self.head_first {
+            return Err("--head-timeout requires --head-only or --head-first".into());
+        }
+
+        if let (Some(head), Some(overall)) = (self.head_timeout_secs, self.timeout_secs)
+            && head > overall
+        {
+            return Err("--head-timeout cannot be longer than --timeout".into());
+        }
+
+        if self.check_parked && !self.scan {
+            return Err("--check-parked requires --scan".into());
+        }
+
+        if self.suggest_updates && !self.scan {
+            return Err("--suggest-updates requires --scan".into());
+        }
+
+        if self.check_ssl_expiry_days.is_some() && !self.scan {
+            return Err("--check-ssl-expiry requires --scan".into());
+        }
+
+        if self.check_parked && self.head_only {
+            return Err(
+                "--check-parked needs response bodies and cannot be combined with --head-only"
+                    .into(),
+            );
+        }
+
+        if self.pool_max_idle_per_host.is_some() && !self.scan {
+            return Err("--pool-max-idle-per-host requires --scan".into());
+        }
+
+        if self.max_response_size.is_some() && !self.scan {
+            return Err("--max-response-size requires --scan".into());
+        }
+
+        if self.if_modified_since.is_some() && !self.scan {
+            return Err("--if-modified-since requires --scan".into());
+        }
+
+        if self.max_response_size.is_some() && self.head_only {
+            return Err("--max-response-size cannot be combined with --head-only".into());
+        }
+
+        if self.max_redirects.is_some() && !self.scan {
+            return Err("--max-redirects requires --scan".into());
+        }
+
+        if self.retries.is_some() && !self.scan {
+            return Err("--retries requires --scan".into());
+        }
+
+        if self.timeout_policy.is_some() && !self.scan {
+            return Err("--timeout-policy requires --scan".into());
+        }
+
+        if !self.headers.is_empty() && !self.scan {
+            return Err("--header and --accept-language require --scan".into());
+        }
+
+        if self.retry_kinds.is_some() && self.retries.is_none() {
+            return Err("--retry-kinds requires --retries".into());
+        }
+
+        if self.check_parked && self.max_redirects == Some(0) {
+            return Err(
+                "--check-parked follows redirects and cannot be combined with --max-redirects 0"
+                    .into(),
+            );
+        }
+
+        if self.trace_redirects && !self.scan {
+            return Err("--trace-redirects requires --scan".into());
+        }
+
+        if self.trace_redirects && self.max_redirects == Some(0) {
+            return Err("--trace-redirects has nothing to record with --max-redirects 0".into());
+        }
+
+        if self.stream.is_some() && !self.scan {
+            return Err("--stream requires --scan".into());
+        }
+
+        if self.format.is_some() && !self.scan {
+            return Err("--format requires --scan".into());
+        }
+
+        if self.min_failures.is_some() && !self.scan && self.recheck.is_none() {
+            return Err("--min-failures requires --scan or --recheck".into());
+        }
+
+        if self.coded_exit && !self.scan && self.recheck.is_none() {
+            return Err("--coded-exit requires --scan or --recheck".into());
+        }
+
+        if self.webhook.is_some() && !cfg!(feature = "webhook") {
+            return Err("--webhook requires building with the `webhook` feature".into());
+        }
+
+        if self.webhook.is_some() && !self.scan {
+            return Err("--webhook requires --scan".into());
+        }
+
+        if self.no_write
+            && let Some(flag) = self.writing_flag()
+        {
+            return Err(format!("--no-write cannot be combined with {flag}"));
+        }
+
+        if self.report_empty && !self.scan {
+            return Err("--report-empty requires --scan".into());
+        }
+
+        if self.append && !self.scan {
+            return Err("--append requires --scan".into());
+        }
+
+        if self.append
+            && self
+                .format
+                .is_some_and(|format| format != ReportFormat::Yaml)
+        {
+            return Err("--append only works with the YAML report format".into());
+        }
+
+        if self
+            .format
+            .is_some_and(|format| format != ReportFormat::Yaml)
+        {
+            // These read the YAML report back, which the other formats never write.
+            let reading = [
+                (self.resume, "--resume"),
+                (self.recheck_failures, "--recheck-failures"),
+                (self.diff.is_some(), "--diff"),
+            ];
+            if let Some((_, flag)) = reading.into_iter().find(|(set, _)| *set) {
+                return Err(format!("{flag} only works with the YAML report format"));
+            }
+        }
+
+        if self.report_max_entries.is_some() && !self.scan {
+            return Err("--report-max-entries requires --scan".into());
+        }
+
+        if self.report_max_entries.is_some()
+            && self
+                .format
+                .is_some_and(|format| format != ReportFormat::Yaml)
+        {
+            return Err("--report-max-entries only works with the YAML report format".into());
+        }
+
+        if self.report_max_entries.is_some() && self.append {
+            return Err("--report-max-entries cannot be combined with --append".into());
+        }
+
+        if self.open_report && !self.scan {
+            return Err("--open-report requires --scan".into());
+        }
+
+        if self.plan && !self.scan {
+            return Err("--plan requires --scan".into());
+        }
+
+        if self.fail_fast && !self.scan {
+            return Err("--fail-fast requires --scan".into());
+        }
+
+        if self.min_interval_secs.is_some() && !self.scan {
+            return Err("--min-interval-between-runs requires --scan".into());
+        }
+
+        if self.force && self.min_interval_secs.is_none() {
+            return Err("--force requires --min-interval-between-runs".into());
+        }
+
+        if self.preflight_url.is_some() && !self.scan {
+            return Err("--preflight-url requires --scan".into());
+        }
+
+        if self.preflight_url.is_some() && !self.preflight {
+            return Err("--preflight-url cannot be combined with --no-preflight".into());
+        }
+
+        if let Some(url) = self.preflight_url.as_deref()
+            && !Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+        {
+            return Err(format!(
+                "--preflight-url must be an http(s) URL, got '{url}'"
+            ));
+        }
+
+        if self.limit_time_secs.is_some() && !self.scan {
+            return Err("--limit-time requires --scan".into());
+        }
+
+        if self.group_by_host && !self.scan {
+            return Err("--group-by-host requires --scan".into());
+        }
+
+        if self.compact && !self.scan {
+            return Err("--compact requires --scan".into());
+        }
+
+        if self.compact && self.group_by_host {
+            return Err("--compact cannot be combined with --group-by-host".into());
+        }
+
+        if self.streams_to_stdout() {
+            // These print their results to stdout, which the JSON lines fill.
+            let conflicting = [
+                (self.compact, "--compact"),
+                (self.group_by_host, "--group-by-host"),
+                (self.diff.is_some(), "--diff"),
+            ];
+            if let Some((_, flag)) = conflicting.into_iter().find(|(set, _)| *set) {
+                return Err(format!("{flag} cannot be combined with --stream -"));
+            }
+        }
+
+        if self.sort.is_some() && !self.scan {
+            return Err("--sort requires --scan".into());
+        }
+
+        if self.resume && self.recheck_failures {
+            return Err("--resume cannot be combined with --recheck-failures".into());
+        }
+
+        if (self.resume || self.recheck_failures) && !self.scan {
+            return Err("--resume and --recheck-failures require --scan".into());
+        }
 
-#[derive(Debug, Clone)]
-pub struct RunConfig {
-    pub max_bookmarks: Option<usize>,
-    pub list_profiles: bool,
-    pub scan: bool,
-    pub profile: Option<String>,
-    pub clean: bool,
-    pub show_version: bool,
+        if self.since_last_run && !self.scan {
+            return Err("--since-last-run requires --scan".into());
+        }
+
+        if self.profiles.len() > 1 && !self.scan {
+            return Err("Multiple --profile flags require --scan".into());
+        }
+
+        if !self.profiles.is_empty() && self.profile_glob.is_some() {
+            return Err("--profile cannot be combined with --profile-glob".into());
+        }
+
+        if self.profile_glob.is_some() && !self.scan {
+            return Err("--profile-glob requires --scan".into());
+        }
+
+        if self.user_data_dir.is_some() && self.recheck.is_some() {
+            return Err("--user-data-dir cannot be combined with --recheck".into());
+        }
+
+        if self.profile_index.is_some()
+            && (!self.profiles.is_empty() || self.profile_glob.is_some())
+        {
+            return Err(
+                "--profile-index cannot be combined with --profile or --profile-glob".into(),
+            );
+        }
+
+        if self.profile_auto
+            && (!self.profiles.is_empty()
+                || self.profile_glob.is_some()
+                || self.profile_index.is_some())
+        {
+            return Err(
+                "--profile-auto cannot be combined with --profile, --profile-glob, or \
+                 --profile-index"
+                    .into(),
+            );
+        }
+
+        if self.parallel_profiles.is_some()
+            && self.profiles.len() < 2
+            && self.profile_glob.is_none()
+        {
+            return Err(
+                "--parallel-profiles requires --profile-glob or several --profile flags".into(),
+            );
+        }
+
+        if self.report_prefix.is_some() && self.profiles.len() < 2 && self.profile_glob.is_none() {
+            return Err(
+                "--report-prefix requires --profile-glob or several --profile flags".into(),
+            );
+        }
+
+        if self
+            .report_prefix
+            .as_deref()
+            .is_some_and(|prefix| prefix.is_empty() || prefix.contains(['/', '\\']))
+        {
+            return Err("--report-prefix must be a file name, not a path".into());
+        }
+
+        if self.profile_report_stdout {
+            if self.profiles.len() < 2 && self.profile_glob.is_none() {
+                return Err(
+                    "--profile-report-stdout requires --profile-glob or several --profile flags"
+                        .into(),
+                );
+            }
+
+            if self
+                .format
+                .is_some_and(|format| format != ReportFormat::Yaml)
+            {
+                return Err(
+                    "--profile-report-stdout only works with the YAML report format".into(),
+                );
+            }
+
+            // Each of these writes a report file, shares stdout with the document or
+            // reports failures elsewhere.
+            let conflicting = [
+                (self.report_prefix.is_some(), "--report-prefix"),
+                (self.append, "--append"),
+                (self.open_report, "--open-report"),
+                (self.no_write, "--no-write"),
+                (self.compact, "--compact"),
+                (self.group_by_host, "--group-by-host"),
+                (self.streams_to_stdout(), "--stream -"),
+                (self.diff.is_some(), "--diff"),
+                (self.webhook.is_some(), "--webhook"),
+            ];
+            if let Some((_, flag)) = conflicting.into_iter().find(|(set, _)| *set) {
+                return Err(format!(
+                    "--profile-report-stdout cannot be combined with {flag}"
+                ));
+            }
+        }
+
+        if self.include_system_profiles
+            && !self.list_profiles
+            && self.profile_glob.is_none()
+            && self.profile_index.is_none()
+            && !self.profile_auto
+        {
+            return Err(
+                "--include-system-profiles requires --list-profiles, --profile-glob, \
+                 --profile-index, or --profile-auto"
+                    .into(),
+            );
+        }
+
+        if self.max_bookmarks.is_some() && !self.scan {
+            return Err("--max-bookmarks requires --scan".into());
+        }
+
+        if self.sample.is_some() && !self.scan {
+            return Err("--sample requires --scan".into());
+        }
+
+        if self.sample.is_some() && self.max_bookmarks.is_some() {
+            return Err("--sample cannot be combined with --max-bookmarks".into());
+        }
+
+        if self.shuffle && !self.scan {
+            return Err("--shuffle requires --scan".into());
+        }
+
+        if self.seed.is_some() && self.sample.is_none() && !self.shuffle {
+            return Err("--seed requires --sample or --shuffle".into());
+        }
+
+        if !self.profiles.is_empty()
+            && !self.scan
+            && !self.clean
+            && !self.count
+            && !self.validate_only
+            && !self.normalize
+            && !self.dedupe_write
+            && !self.normalize_names
+            && !self.list_backups
+            && !self.check_mixed_content
+        {
+            return Err(
+                "--profile requires --scan, --clean, --count, --validate-only, --normalize, \
+                 --dedupe-write, --normalize-names, --list-backups, or --check-mixed-content"
+                    .into(),
+            );
+        }
+
+        if self.selected_actions().next().is_none() && !self.show_version && !self.print_config {
+            return Err(
+                "No action provided. Use --scan, --clean, --normalize, --dedupe-write, \
+                 --normalize-names, --list-backups, --check-mixed-content, --recheck, \
+                 --list-profiles, --detect, --count, --validate-only, or --version."
+                    .into(),
+            );
+        }
+
+        Ok(())
+    }
 }
 
-impl Default for RunConfig {
-    fn default() -> Self {
-        Self {
-            max_bookmarks: None,
-            list_profiles: false,
-            scan: true,
-            profile: None,
-            clean: false,
-            show_version: false,
+impl BookmarkError {
+    /// The underlying I/O error, for branching on its `kind()`.
+    pub fn io_error(&self) -> Option<&io::Error> {
+        match self {
+            BookmarkError::Io(err) | BookmarkError::ReportOpen(_, err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Why a Bookmarks file could not be parsed.
+    pub fn parse_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            BookmarkError::InvalidFormat(err)
+            | BookmarkError::TruncatedBookmarksFile(_, err)
+            | BookmarkError::MalformedBookmarksFile(_, err, _) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// The YAML error behind a report, configuration or state file failure.
+    pub fn yaml_error(&self) -> Option<&serde_yaml::Error> {
+        match self {
+            BookmarkError::ReportWrite(err)
+            | BookmarkError::ReportParse(err)
+            | BookmarkError::ConfigSerialization(err)
+            | BookmarkError::StateFile(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// The HTTP client error behind a client setup or webhook failure.
+    pub fn http_error(&self) -> Option<&reqwest::Error> {
+        match self {
+            BookmarkError::HttpClientBuild(err) | BookmarkError::WebhookDelivery(err) => Some(err),
+            _ => None,
         }
     }
 }
@@ -61,6 +1383,12 @@ impl Display for BookmarkError {
                     "Unsupported operating system for locating Chrome bookmarks"
                 )
             }
+            BookmarkError::HomeDirUnavailable => {
+                write!(
+                    f,
+                    "Could not determine the home directory to locate Chrome bookmarks"
+                )
+            }
             BookmarkError::MissingBookmarksDir(path) => {
                 write!(
                     f,
@@ -71,9 +1399,72 @@ impl Display for BookmarkError {
             BookmarkError::MissingBookmarksFile(path) => {
                 write!(f, "Chrome bookmarks file not found: {}", path.display())
             }
+            BookmarkError::EmptyBookmarks(path) => {
+                write!(
+                    f,
+                    "Chrome bookmarks file contains no bookmarks: {}",
+                    path.display()
+                )
+            }
+            BookmarkError::BlankBookmarksFile(path) => {
+                write!(
+                    f,
+                    "Chrome bookmarks file is blank: {}. Chrome may be writing it; try again \
+                     in a moment, or restore a backup if it stays empty",
+                    path.display()
+                )
+            }
+            BookmarkError::TruncatedBookmarksFile(path, err) => {
+                write!(
+                    f,
+                    "Chrome bookmarks file ends unexpectedly: {} ({err}). Chrome may have \
+                     been interrupted while writing it",
+                    path.display()
+                )
+            }
+            BookmarkError::MalformedBookmarksFile(path, err, snippet) => {
+                write!(
+                    f,
+                    "Failed to parse bookmarks file {}: {err}",
+                    path.display()
+                )?;
+                if !snippet.is_empty() {
+                    write!(f, ", near `{snippet}`")?;
+                }
+                Ok(())
+            }
+            BookmarkError::HtmlBookmarksExport(path) => {
+                write!(
+                    f,
+                    "{} is an HTML bookmark export; only Chrome's JSON Bookmarks file can be \
+                     checked",
+                    path.display()
+                )
+            }
+            BookmarkError::UnrecognizedBookmarksFile(path) => {
+                write!(
+                    f,
+                    "{} is not a Chrome Bookmarks file (expected JSON starting with `{{`)",
+                    path.display()
+                )
+            }
+            BookmarkError::MissingChromeBackup(path) => {
+                write!(
+                    f,
+                    "Chrome's backup bookmarks file not found: {} (--use-chrome-backup)",
+                    path.display()
+                )
+            }
             BookmarkError::ProfileNotFound(name) => {
                 write!(f, "Chrome profile '{name}' not found")
             }
+            BookmarkError::ProfileIndexOutOfRange(index, count) => {
+                write!(
+                    f,
+                    "Profile index {index} is out of range; {count} profile(s) found \
+                     (see --list-profiles)"
+                )
+            }
             BookmarkError::Io(err) => write!(f, "I/O error reading bookmarks: {err}"),
             BookmarkError::InvalidFormat(err) => {
                 write!(f, "Failed to parse bookmarks file: {err}")
@@ -87,9 +1478,38 @@ impl Display for BookmarkError {
             BookmarkError::ReportWrite(err) => {
                 write!(f, "Failed to write YAML report: {err}")
             }
+            BookmarkError::ReportSerialization(err) => {
+                write!(f, "Failed to write JSON report: {err}")
+            }
             BookmarkError::ReportParse(err) => {
                 write!(f, "Failed to parse YAML report: {err}")
             }
+            BookmarkError::ConfigSerialization(err) => {
+                write!(f, "Failed to print configuration: {err}")
+            }
+            BookmarkError::InvalidConfig(message) => f.write_str(message),
+            BookmarkError::StateFile(err) => {
+                write!(f, "Failed to read or write the run state file: {err}")
+            }
+            BookmarkError::WebhookDelivery(err) => {
+                write!(f, "Failed to deliver webhook notification: {err}")
+            }
+            BookmarkError::ReportOpen(path, err) => {
+                write!(f, "Failed to open {}: {err}", path.display())
+            }
+            BookmarkError::ThreadPoolBuild(err) => {
+                write!(f, "Failed to start worker threads: {err}")
+            }
+            BookmarkError::NoNetwork => f.write_str(
+                "No network connectivity detected; not scanning. Use --no-preflight to scan \
+                 anyway.",
+            ),
+            BookmarkError::RunTooSoon(last, interval) => write!(
+                f,
+                "The last scan started at {}, less than {interval} second(s) ago; use --force \
+                 to scan anyway.",
+                last.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
         }
     }
 }
@@ -99,10 +1519,18 @@ impl StdError for BookmarkError {
         match self {
             BookmarkError::Io(err) => Some(err),
             BookmarkError::InvalidFormat(err) => Some(err),
+            BookmarkError::TruncatedBookmarksFile(_, err) => Some(err),
+            BookmarkError::MalformedBookmarksFile(_, err, _) => Some(err),
             BookmarkError::BookmarkSerialization(err) => Some(err),
             BookmarkError::HttpClientBuild(err) => Some(err),
             BookmarkError::ReportWrite(err) => Some(err),
+            BookmarkError::ReportSerialization(err) => Some(err),
             BookmarkError::ReportParse(err) => Some(err),
+            BookmarkError::ConfigSerialization(err) => Some(err),
+            BookmarkError::StateFile(err) => Some(err),
+            BookmarkError::WebhookDelivery(err) => Some(err),
+            BookmarkError::ReportOpen(_, err) => Some(err),
+            BookmarkError::ThreadPoolBuild(err) => Some(err),
             _ => None,
         }
     }
@@ -129,6 +1557,125 @@ impl From<serde_yaml::Error> for BookmarkError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checker::{FailureKind, FailureReason};
+
+    fn bookmark(url: &str) -> Bookmark {
+        Bookmark {
+            name: "Example".into(),
+            url: url.into(),
+            folder_path: Vec::new(),
+            date_added: None,
+        }
+    }
+
+    #[test]
+    fn io_errors_are_reachable_by_type() {
+        let err = BookmarkError::from(io::Error::new(io::ErrorKind::PermissionDenied, "locked"));
+
+        assert_eq!(
+            err.io_error().map(io::Error::kind),
+            Some(io::ErrorKind::PermissionDenied)
+        );
+        assert!(err.parse_error().is_none());
+        assert!(BookmarkError::HomeDirUnavailable.io_error().is_none());
+    }
+
+    #[test]
+    fn parse_errors_are_reachable_by_type() {
+        let err = BookmarkError::InvalidFormat(
+            serde_json::from_str::<serde_json::Value>("{").unwrap_err(),
+        );
+
+        assert!(err.parse_error().is_some_and(serde_json::Error::is_eof));
+        assert!(err.io_error().is_none());
+    }
+
+    #[test]
+    fn yaml_errors_are_reachable_by_type() {
+        let yaml = || serde_yaml::from_str::<Vec<String>>("{").unwrap_err();
+
+        for err in [
+            BookmarkError::ReportWrite(yaml()),
+            BookmarkError::ReportParse(yaml()),
+            BookmarkError::ConfigSerialization(yaml()),
+            BookmarkError::StateFile(yaml()),
+        ] {
+            assert!(err.yaml_error().is_some(), "{err:?}");
+        }
+        assert!(
+            BookmarkError::InvalidConfig("--clean requires a report".into())
+                .yaml_error()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn http_errors_are_reachable_by_type() {
+        let http = || {
+            reqwest::blocking::Client::new()
+                .get("not a url")
+                .build()
+                .unwrap_err()
+        };
+
+        for err in [
+            BookmarkError::HttpClientBuild(http()),
+            BookmarkError::WebhookDelivery(http()),
+        ] {
+            assert!(err.http_error().is_some_and(reqwest::Error::is_builder));
+        }
+        assert!(BookmarkError::UnsupportedPlatform.http_error().is_none());
+    }
+
+    #[test]
+    fn host_is_parsed_from_http_and_https_urls() {
+        assert_eq!(
+            bookmark("http://Example.com/path").host().as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            bookmark("https://docs.rs/reqwest?search=x")
+                .host()
+                .as_deref(),
+            Some("docs.rs")
+        );
+    }
+
+    #[test]
+    fn host_excludes_the_port() {
+        assert_eq!(
+            bookmark("https://localhost:8443/admin").host().as_deref(),
+            Some("localhost")
+        );
+    }
+
+    #[test]
+    fn http_and_https_bookmarks_are_checkable() {
+        assert!(bookmark("https://example.com/page").is_checkable());
+        assert!(bookmark("HTTP://example.com").is_checkable());
+    }
+
+    #[test]
+    fn browser_internal_and_local_urls_are_not_checkable() {
+        for url in [
+            "chrome://settings",
+            "chrome-extension://abcdef/options.html",
+            "javascript:void(0)",
+            "file:///home/user/notes.html",
+            "",
+            "   ",
+            "not a url",
+        ] {
+            assert!(!bookmark(url).is_checkable(), "{url:?}");
+        }
+    }
+
+    #[test]
+    fn host_is_none_for_malformed_urls() {
+        assert_eq!(bookmark("not a url").host(), None);
+        assert_eq!(bookmark("https://").host(), None);
+        assert_eq!(bookmark("mailto:someone@example.com").host(), None);
+    }
 
     #[test]
     fn error_display_mentions_missing_dir() {
@@ -137,6 +1684,372 @@ mod tests {
         assert!(message.contains(path.to_string_lossy().as_ref()));
     }
 
+    #[test]
+    fn home_dir_unavailable_mentions_home_directory() {
+        let message = BookmarkError::HomeDirUnavailable.to_string();
+        assert!(message.contains("home directory"));
+        assert!(!message.contains("Unsupported"));
+    }
+
+    #[test]
+    fn zero_concurrency_means_auto() {
+        assert_eq!("0".parse(), Ok(Concurrency::Auto));
+        assert_eq!("16".parse(), Ok(Concurrency::Fixed(16)));
+        assert_eq!("512".parse(), Ok(Concurrency::Fixed(Concurrency::MAX)));
+    }
+
+    #[test]
+    fn out_of_range_concurrency_is_rejected() {
+        for value in ["513", "100000", "-1", "many"] {
+            let err = value.parse::<Concurrency>().unwrap_err();
+            assert!(err.contains("0 (auto) up to 512"), "{err}");
+        }
+    }
+
+    #[test]
+    fn request_headers_parse_name_and_value() {
+        let header: RequestHeader = "X-Requested-With:  bookmark-checker ".parse().unwrap();
+        assert_eq!(header.name().as_str(), "x-requested-with");
+        assert_eq!(header.value(), "bookmark-checker");
+        assert_eq!(String::from(header), "x-requested-with: bookmark-checker");
+        assert_eq!(
+            RequestHeader::accept_language("de-DE").unwrap(),
+            "Accept-Language: de-DE".parse().unwrap()
+        );
+
+        for invalid in ["no colon", "Bad Name: x", ": empty", "X-Line: a\nb"] {
+            assert!(invalid.parse::<RequestHeader>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn retry_kinds_parse_from_a_list() {
+        assert_eq!(
+            RetryKind::parse_list("connection, SERVER_ERROR,connection"),
+            Ok(vec![RetryKind::Connection, RetryKind::ServerError])
+        );
+        assert_eq!(
+            RetryKind::parse_list("not_found,gone"),
+            Ok(vec![RetryKind::NotFound, RetryKind::Gone])
+        );
+
+        let err = RetryKind::parse_list("connection,teapot").unwrap_err();
+        assert!(err.contains("'teapot'"), "{err}");
+        assert!(RetryKind::parse_list(" , ").is_err());
+    }
+
+    #[test]
+    fn normalized_url_ignores_case_of_scheme_and_host() {
+        assert_eq!(
+            NormalizedUrl::new("HTTPS://Example.COM/Path"),
+            NormalizedUrl::new("https://example.com/Path")
+        );
+        assert_ne!(
+            NormalizedUrl::new("https://example.com/Path"),
+            NormalizedUrl::new("https://example.com/path")
+        );
+    }
+
+    #[test]
+    fn normalized_url_strips_trailing_slashes() {
+        let expected = NormalizedUrl::new("https://example.com");
+        assert_eq!(NormalizedUrl::new("https://example.com/"), expected);
+        assert_eq!(
+            NormalizedUrl::new("https://example.com/docs/"),
+            NormalizedUrl::new("https://example.com/docs")
+        );
+        assert_eq!(
+            NormalizedUrl::new("https://example.com/docs/?q=1"),
+            NormalizedUrl::new("https://example.com/docs?q=1")
+        );
+    }
+
+    #[test]
+    fn normalized_url_keeps_query_and_fragment_distinct() {
+        assert_ne!(
+            NormalizedUrl::new("https://example.com/?q=1"),
+            NormalizedUrl::new("https://example.com/?q=2")
+        );
+        assert_ne!(
+            NormalizedUrl::new("https://example.com/#a"),
+            NormalizedUrl::new("https://example.com/")
+        );
+    }
+
+    #[test]
+    fn bookmark_normalized_url_dedups_in_sets() {
+        let urls = [
+            "https://Example.com/",
+            "https://example.com",
+            "https://other.com",
+        ];
+        let unique: std::collections::HashSet<NormalizedUrl> = urls
+            .iter()
+            .map(|url| {
+                Bookmark {
+                    name: "Example".into(),
+                    url: url.to_string(),
+                    folder_path: Vec::new(),
+                    date_added: None,
+                }
+                .normalized_url()
+            })
+            .collect();
+        assert_eq!(unique.len(), 2);
+    }
+
+    #[test]
+    fn sort_order_parses_known_values() {
+        assert_eq!("name".parse(), Ok(SortOrder::Name));
+        assert_eq!("URL".parse(), Ok(SortOrder::Url));
+        assert_eq!("folder".parse(), Ok(SortOrder::Folder));
+        assert!("size".parse::<SortOrder>().is_err());
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert_eq!(RunConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn contradictory_configs_are_rejected() {
+        let invalid = [
+            (
+                RunConfig {
+                    scan: false,
+                    list_profiles: true,
+                    max_bookmarks: Some(5),
+                    ..RunConfig::default()
+                },
+                "--max-bookmarks requires --scan",
+            ),
+            (
+                RunConfig {
+                    clean: true,
+                    ..RunConfig::default()
+                },
+                "--scan cannot be combined with --clean",
+            ),
+            (
+                RunConfig {
+                    scan: false,
+                    list_backups: true,
+                    detect: true,
+                    ..RunConfig::default()
+                },
+                "--list-backups cannot be combined with --detect",
+            ),
+            (
+                RunConfig {
+                    sample: Some(3),
+                    max_bookmarks: Some(5),
+                    ..RunConfig::default()
+                },
+                "--sample cannot be combined with --max-bookmarks",
+            ),
+            (
+                RunConfig {
+                    no_write: true,
+                    output_dir: Some(PathBuf::from("reports")),
+                    ..RunConfig::default()
+                },
+                "--no-write cannot be combined with --output-dir",
+            ),
+            (
+                RunConfig {
+                    resume: true,
+                    format: Some(ReportFormat::Sarif),
+                    ..RunConfig::default()
+                },
+                "--resume only works with the YAML report format",
+            ),
+            (
+                RunConfig {
+                    recheck_failures: true,
+                    format: Some(ReportFormat::JUnit),
+                    ..RunConfig::default()
+                },
+                "--recheck-failures only works with the YAML report format",
+            ),
+            (
+                RunConfig {
+                    diff: Some(PathBuf::from("previous.yml")),
+                    format: Some(ReportFormat::JUnit),
+                    ..RunConfig::default()
+                },
+                "--diff only works with the YAML report format",
+            ),
+            (
+                RunConfig {
+                    preflight: true,
+                    preflight_url: Some("intranet.local".into()),
+                    ..RunConfig::default()
+                },
+                "--preflight-url must be an http(s) URL",
+            ),
+            (
+                RunConfig {
+                    stream: Some(PathBuf::from("-")),
+                    group_by_host: true,
+                    ..RunConfig::default()
+                },
+                "--group-by-host cannot be combined with --stream -",
+            ),
+            (
+                RunConfig {
+                    scan: false,
+                    ..RunConfig::default()
+                },
+                "No action provided",
+            ),
+        ];
+
+        for (config, expected) in invalid {
+            let err = config.validate().unwrap_err();
+            assert!(err.starts_with(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn timeout_policy_parses_known_values() {
+        assert_eq!("global".parse(), Ok(TimeoutPolicy::Global));
+        assert_eq!("Per-Host".parse(), Ok(TimeoutPolicy::PerHost));
+        assert!("per_host".parse::<TimeoutPolicy>().is_err());
+    }
+
+    #[test]
+    fn color_choice_respects_terminal_and_no_color() {
+        assert_eq!("Always".parse(), Ok(ColorChoice::Always));
+        assert!("yes".parse::<ColorChoice>().is_err());
+        assert!(ColorChoice::Auto.enabled(false, true));
+        assert!(!ColorChoice::Auto.enabled(true, true));
+        assert!(!ColorChoice::Auto.enabled(false, false));
+        assert!(ColorChoice::Always.enabled(true, false));
+        assert!(!ColorChoice::Never.enabled(false, true));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn location_round_trips_through_json_with_string_paths() {
+        let location = BookmarkLocation {
+            directory: PathBuf::from("/home/example/.config/google-chrome/Default"),
+            file: PathBuf::from("/home/example/.config/google-chrome/Default/Bookmarks"),
+        };
+
+        let json = serde_json::to_value(&location).expect("serialize");
+        assert_eq!(
+            json["file"],
+            "/home/example/.config/google-chrome/Default/Bookmarks"
+        );
+
+        let decoded: BookmarkLocation = serde_json::from_value(json).expect("deserialize");
+        assert_eq!(decoded, location);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn bookmark_round_trips_through_json() {
+        let bookmark = Bookmark {
+            name: "Example".into(),
+            url: "https://example.com".into(),
+            folder_path: vec!["Bookmarks bar".into()],
+            date_added: None,
+        };
+
+        let json = serde_json::to_string(&bookmark).expect("serialize");
+        let decoded: Bookmark = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(decoded, bookmark);
+    }
+
+    #[test]
+    fn report_format_parses_known_values() {
+        assert_eq!("yaml".parse(), Ok(ReportFormat::Yaml));
+        assert_eq!("SARIF".parse(), Ok(ReportFormat::Sarif));
+        assert_eq!("yml".parse(), Ok(ReportFormat::Yaml));
+        assert_eq!("JUnit".parse(), Ok(ReportFormat::JUnit));
+    }
+
+    #[test]
+    fn input_format_parses_known_values() {
+        assert_eq!("json".parse(), Ok(InputFormat::Json));
+        assert_eq!("HTML".parse(), Ok(InputFormat::Html));
+        let message = "csv"
+            .parse::<InputFormat>()
+            .expect_err("csv is unsupported");
+        for name in InputFormat::NAMES {
+            assert!(message.contains(name), "{message}");
+        }
+    }
+
+    #[test]
+    fn unknown_report_format_lists_valid_options() {
+        let message = "csv"
+            .parse::<ReportFormat>()
+            .expect_err("csv is unsupported");
+        assert!(message.contains("'csv'"));
+        for name in ReportFormat::NAMES {
+            assert!(message.contains(name), "{message}");
+        }
+    }
+
+    #[test]
+    fn failure_threshold_is_inclusive() {
+        let outcome = ScanOutcome {
+            checked: 10,
+            failures: 3,
+            ..ScanOutcome::default()
+        };
+        assert!(outcome.meets_failure_threshold(1));
+        assert!(outcome.meets_failure_threshold(3));
+        assert!(!outcome.meets_failure_threshold(4));
+        assert!(!ScanOutcome::default().meets_failure_threshold(1));
+    }
+
+    #[test]
+    fn only_an_outcome_without_failures_is_clean() {
+        let outcome = ScanOutcome {
+            checked: 10,
+            failures: 3,
+            ..ScanOutcome::default()
+        };
+        assert!(!outcome.is_clean());
+        assert!(ScanOutcome::default().is_clean());
+    }
+
+    #[test]
+    fn accessors_report_the_checked_and_failed_counts() {
+        let outcome = ScanOutcome {
+            checked: 10,
+            failures: 3,
+            ..ScanOutcome::default()
+        };
+        assert_eq!(outcome.checked(), 10);
+        assert_eq!(outcome.failed(), 3);
+    }
+
+    #[test]
+    fn iterating_an_outcome_yields_its_failed_links() {
+        let failure = |url: &str| LinkFailure {
+            bookmark: bookmark(url),
+            reason: FailureReason::Http(404),
+            kind: FailureKind::NotFound,
+            redirect_chain: Vec::new(),
+        };
+        let outcome = ScanOutcome {
+            checked: 3,
+            failures: 2,
+            failed_links: vec![failure("https://a.example"), failure("https://b.example")],
+            ..ScanOutcome::default()
+        };
+
+        let mut urls = Vec::new();
+        for failure in &outcome {
+            urls.push(failure.bookmark.url.as_str());
+        }
+        assert_eq!(urls, ["https://a.example", "https://b.example"]);
+        assert_eq!((&ScanOutcome::default()).into_iter().count(), 0);
+    }
+
     #[test]
     fn profile_not_found_error_message_includes_name() {
         let message = BookmarkError::ProfileNotFound("Profile 42".into()).to_string();
@@ -0,0 +1,94 @@
+use crate::model::BookmarkError;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Launches `path` in the platform's default application without waiting for it.
+pub(crate) fn open(path: &Path) -> Result<(), BookmarkError> {
+    let mut command = opener_command(path).ok_or(BookmarkError::UnsupportedPlatform)?;
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| BookmarkError::ReportOpen(path.to_path_buf(), err))?;
+    Ok(())
+}
+
+/// The opener for the current platform; `None` where there is none to use.
+fn opener_command(path: &Path) -> Option<Command> {
+    if cfg!(target_os = "macos") {
+        Some(macos_command(path))
+    } else if cfg!(target_os = "windows") {
+        Some(windows_command(path))
+    } else if cfg!(unix) {
+        Some(linux_command(path))
+    } else {
+        None
+    }
+}
+
+fn macos_command(path: &Path) -> Command {
+    let mut command = Command::new("open");
+    command.arg(path);
+    command
+}
+
+/// Other Unix-likes follow the freedesktop.org `xdg-open` convention used on Linux.
+fn linux_command(path: &Path) -> Command {
+    let mut command = Command::new("xdg-open");
+    command.arg(path);
+    command
+}
+
+/// `start` is a `cmd` builtin; its first quoted argument is the window title, so an
+/// empty one keeps paths with spaces from being mistaken for it.
+fn windows_command(path: &Path) -> Command {
+    let mut command = Command::new("cmd");
+    command.args(["/C", "start", ""]).arg(path);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    fn parts(command: &Command) -> (&OsStr, Vec<&OsStr>) {
+        (command.get_program(), command.get_args().collect())
+    }
+
+    #[test]
+    fn macos_uses_open() {
+        let path = Path::new("/tmp/bookmark_failures.yml");
+        assert_eq!(
+            parts(&macos_command(path)),
+            (OsStr::new("open"), vec![path.as_os_str()])
+        );
+    }
+
+    #[test]
+    fn linux_uses_xdg_open() {
+        let path = Path::new("/tmp/bookmark_failures.yml");
+        assert_eq!(
+            parts(&linux_command(path)),
+            (OsStr::new("xdg-open"), vec![path.as_os_str()])
+        );
+    }
+
+    #[test]
+    fn windows_uses_start_with_an_empty_title() {
+        let path = Path::new(r"C:\Reports\bookmark failures.yml");
+        assert_eq!(
+            parts(&windows_command(path)),
+            (
+                OsStr::new("cmd"),
+                vec![
+                    OsStr::new("/C"),
+                    OsStr::new("start"),
+                    OsStr::new(""),
+                    path.as_os_str()
+                ]
+            )
+        );
+    }
+}
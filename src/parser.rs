@@ -1,45 +1,166 @@
-use crate::Bookmark;
+use crate::{Bookmark, InputFormat};
+use chrono::{DateTime, Utc};
 use serde_json::Value;
 
-pub(crate) fn parse_bookmarks(data: &str) -> Result<Vec<Bookmark>, serde_json::Error> {
+/// Chrome stores times as microseconds since 1601-01-01 UTC.
+const WEBKIT_EPOCH_OFFSET_MICROS: i64 = 11_644_473_600_000_000;
+
+/// Folder nesting followed by default: as deep as a folder with bookmarks in it can
+/// be, since serde_json refuses documents nested more than 128 levels and every
+/// folder adds two (the folder and its `children`).
+pub(crate) const DEFAULT_MAX_DEPTH: usize = 61;
+
+/// Bytes of context kept on each side of a parse error; minified files are one long line.
+const SNIPPET_RADIUS: usize = 30;
+
+/// The first line of the Netscape bookmark format browsers use for HTML exports.
+const HTML_EXPORT_DOCTYPE: &str = "<!DOCTYPE NETSCAPE-Bookmark-file";
+
+/// Sniffs the format of `data`; `None` when it looks like neither.
+pub(crate) fn detect_format(data: &str) -> Option<InputFormat> {
+    let start = data.trim_start();
+    if start.starts_with('{') {
+        Some(InputFormat::Json)
+    } else if start
+        .get(..HTML_EXPORT_DOCTYPE.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(HTML_EXPORT_DOCTYPE))
+    {
+        Some(InputFormat::Html)
+    } else {
+        None
+    }
+}
+
+/// Which of Chrome's top-level roots (`bookmark_bar`, `other`, `synced`) to collect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum RootScope {
+    #[default]
+    All,
+    /// Only the bookmarks bar, as with `--bar-only`.
+    BookmarkBar,
+}
+
+/// Bookmarks found in a file, and how many folders were too deep to open.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct ParsedBookmarks {
+    pub(crate) bookmarks: Vec<Bookmark>,
+    pub(crate) skipped_folders: usize,
+}
+
+/// Parses bookmarks nested at most `max_depth` folders deep within the roots in
+/// `scope`; deeper folders are skipped and counted instead of descended into.
+///
+/// serde_json already refuses documents nested more than 128 levels, so the
+/// recursion below is bounded either way.
+pub(crate) fn parse_bookmarks(
+    data: &str,
+    max_depth: usize,
+    scope: RootScope,
+) -> Result<ParsedBookmarks, serde_json::Error> {
     let value: Value = serde_json::from_str(data)?;
-    Ok(extract_bookmarks(&value))
+    let scoped = match scope {
+        RootScope::All => Some(&value),
+        RootScope::BookmarkBar => value.pointer("/roots/bookmark_bar"),
+    };
+    Ok(scoped
+        .map(|value| extract_bookmarks(value, max_depth))
+        .unwrap_or_default())
 }
 
-fn extract_bookmarks(value: &Value) -> Vec<Bookmark> {
-    let mut collected = Vec::new();
-    collect_nodes(value, &mut collected);
-    collected
+/// The text of `data` around the position where `err` stopped parsing.
+pub(crate) fn error_snippet(data: &str, err: &serde_json::Error) -> String {
+    let Some(line) = data.lines().nth(err.line().saturating_sub(1)) else {
+        return String::new();
+    };
+
+    let column = err.column().min(line.len());
+    let mut start = column.saturating_sub(SNIPPET_RADIUS);
+    while !line.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (column + SNIPPET_RADIUS).min(line.len());
+    while !line.is_char_boundary(end) {
+        end += 1;
+    }
+
+    line[start..end].trim().to_string()
 }
 
-fn collect_nodes(node: &Value, collected: &mut Vec<Bookmark>) {
-    if let Some(object) = node.as_object() {
-        if object.get("type").and_then(Value::as_str) == Some("url")
-            && let (Some(name), Some(url)) = (
-                object.get("name").and_then(Value::as_str),
-                object.get("url").and_then(Value::as_str),
-            )
-        {
-            collected.push(Bookmark {
-                name: name.to_string(),
-                url: url.to_string(),
-            });
-        }
+fn extract_bookmarks(value: &Value, max_depth: usize) -> ParsedBookmarks {
+    let mut collector = Collector {
+        max_depth,
+        folder_path: Vec::new(),
+        parsed: ParsedBookmarks::default(),
+    };
+    collector.collect_nodes(value);
+    collector.parsed
+}
 
-        if let Some(children) = object.get("children").and_then(Value::as_array) {
-            for child in children {
-                collect_nodes(child, collected);
+/// Converts a Chrome timestamp; "0" and unparseable values mean "unknown".
+fn webkit_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let micros = value.parse::<i64>().ok().filter(|&micros| micros > 0)?;
+    DateTime::from_timestamp_micros(micros - WEBKIT_EPOCH_OFFSET_MICROS)
+}
+
+struct Collector {
+    max_depth: usize,
+    folder_path: Vec<String>,
+    parsed: ParsedBookmarks,
+}
+
+impl Collector {
+    fn collect_nodes(&mut self, node: &Value) {
+        if let Some(object) = node.as_object() {
+            if object.get("type").and_then(Value::as_str) == Some("url")
+                && let (Some(name), Some(url)) = (
+                    object.get("name").and_then(Value::as_str),
+                    object.get("url").and_then(Value::as_str),
+                )
+            {
+                self.parsed.bookmarks.push(Bookmark {
+                    name: name.to_string(),
+                    url: url.to_string(),
+                    folder_path: self.folder_path.clone(),
+                    date_added: object
+                        .get("date_added")
+                        .and_then(Value::as_str)
+                        .and_then(webkit_timestamp),
+                });
             }
-        }
 
-        for (key, value) in object {
-            if key != "children" {
-                collect_nodes(value, collected);
+            if let Some(children) = object.get("children").and_then(Value::as_array) {
+                let folder_name = object
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .filter(|kind| *kind == "folder")
+                    .and(object.get("name").and_then(Value::as_str));
+
+                if folder_name.is_some() && self.folder_path.len() >= self.max_depth {
+                    self.parsed.skipped_folders += 1;
+                } else {
+                    if let Some(folder_name) = folder_name {
+                        self.folder_path.push(folder_name.to_string());
+                    }
+
+                    for child in children {
+                        self.collect_nodes(child);
+                    }
+
+                    if folder_name.is_some() {
+                        self.folder_path.pop();
+                    }
+                }
+            }
+
+            for (key, value) in object {
+                if key != "children" {
+                    self.collect_nodes(value);
+                }
+            }
+        } else if let Some(array) = node.as_array() {
+            for value in array {
+                self.collect_nodes(value);
             }
-        }
-    } else if let Some(array) = node.as_array() {
-        for value in array {
-            collect_nodes(value, collected);
         }
     }
 }
@@ -48,11 +169,90 @@ fn collect_nodes(node: &Value, collected: &mut Vec<Bookmark>) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn formats_are_detected_from_the_content() {
+        assert_eq!(detect_format("{\"roots\": {}}"), Some(InputFormat::Json));
+        assert_eq!(
+            detect_format("\n  {\n  \"checksum\": \"\"}"),
+            Some(InputFormat::Json)
+        );
+        assert_eq!(
+            detect_format(
+                "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n<TITLE>Bookmarks</TITLE>\n<DL><p></DL>"
+            ),
+            Some(InputFormat::Html)
+        );
+        assert_eq!(
+            detect_format("<!doctype netscape-bookmark-file-1>"),
+            Some(InputFormat::Html)
+        );
+        assert_eq!(detect_format("<html><body></body></html>"), None);
+        assert_eq!(detect_format("[1, 2]"), None);
+        assert_eq!(detect_format("SQLite format 3\0"), None);
+    }
+
+    #[test]
+    fn bar_scope_skips_other_and_synced_roots() {
+        let data = r#"{
+            "roots": {
+                "bookmark_bar": {"type": "folder", "name": "Bookmarks bar", "children": [
+                    {"type": "url", "name": "Triage", "url": "https://triage.example"},
+                    {"type": "folder", "name": "Later", "children": [
+                        {"type": "url", "name": "Nested", "url": "https://nested.example"}
+                    ]}
+                ]},
+                "other": {"type": "folder", "name": "Other bookmarks", "children": [
+                    {"type": "url", "name": "Archive", "url": "https://archive.example"}
+                ]},
+                "synced": {"type": "folder", "name": "Mobile bookmarks", "children": [
+                    {"type": "url", "name": "Phone", "url": "https://phone.example"}
+                ]}
+            }
+        }"#;
+        let urls = |scope| -> Vec<String> {
+            parse_bookmarks(data, DEFAULT_MAX_DEPTH, scope)
+                .expect("should parse")
+                .bookmarks
+                .into_iter()
+                .map(|bookmark| bookmark.url)
+                .collect()
+        };
+
+        assert_eq!(
+            urls(RootScope::BookmarkBar),
+            vec!["https://triage.example", "https://nested.example"]
+        );
+        assert_eq!(urls(RootScope::All).len(), 4);
+        assert!(
+            parse_bookmarks(
+                r#"{"roots": {}}"#,
+                DEFAULT_MAX_DEPTH,
+                RootScope::BookmarkBar
+            )
+            .expect("should parse")
+            .bookmarks
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn error_snippet_is_a_window_around_the_error() {
+        let data = format!(r#"{{"roots": {{"name": "{}", "oops" }}}}"#, "ä".repeat(40));
+        let err = parse_bookmarks(&data, DEFAULT_MAX_DEPTH, RootScope::All).unwrap_err();
+
+        let snippet = error_snippet(&data, &err);
+        assert!(snippet.ends_with(r#""oops" }}"#), "{snippet}");
+        assert!(snippet.len() <= 2 * SNIPPET_RADIUS + 2, "{snippet}");
+        assert!(!snippet.contains("roots"), "{snippet}");
+    }
+
     #[test]
     fn parses_nested_nodes() {
         let data = r#"{
             "roots": {
                 "bookmark_bar": {
+                    "type": "folder",
+                    "name": "Bookmarks bar",
                     "children": [
                         {
                             "type": "url",
@@ -61,6 +261,7 @@ mod tests {
                         },
                         {
                             "type": "folder",
+                            "name": "Reading",
                             "children": [
                                 {
                                     "type": "url",
@@ -74,22 +275,48 @@ mod tests {
             }
         }"#;
 
-        let bookmarks = parse_bookmarks(data).expect("should parse");
+        let bookmarks = parse_bookmarks(data, DEFAULT_MAX_DEPTH, RootScope::All)
+            .expect("should parse")
+            .bookmarks;
         assert_eq!(
             bookmarks,
             vec![
                 Bookmark {
                     name: "Example".into(),
                     url: "https://example.com".into(),
+                    folder_path: vec!["Bookmarks bar".into()],
+                    date_added: None,
                 },
                 Bookmark {
                     name: "Nested".into(),
                     url: "https://nested.example.com".into(),
+                    folder_path: vec!["Bookmarks bar".into(), "Reading".into()],
+                    date_added: None,
                 }
             ]
         );
     }
 
+    #[test]
+    fn reads_chrome_date_added() {
+        let data = r#"[
+            {"type": "url", "name": "Dated", "url": "https://a.example", "date_added": "13300000000000000"},
+            {"type": "url", "name": "Zero", "url": "https://b.example", "date_added": "0"},
+            {"type": "url", "name": "Undated", "url": "https://c.example"}
+        ]"#;
+
+        let dates: Vec<Option<String>> = parse_bookmarks(data, DEFAULT_MAX_DEPTH, RootScope::All)
+            .expect("should parse")
+            .bookmarks
+            .into_iter()
+            .map(|bookmark| bookmark.date_added.map(|date| date.to_rfc3339()))
+            .collect();
+        assert_eq!(
+            dates,
+            vec![Some("2022-06-18T04:26:40+00:00".to_string()), None, None]
+        );
+    }
+
     #[test]
     fn collects_from_arrays() {
         let data = serde_json::json!([{
@@ -98,20 +325,68 @@ mod tests {
             "url": "https://array.example.com"
         }]);
 
-        let mut collected = Vec::new();
-        collect_nodes(&data, &mut collected);
+        let collected = extract_bookmarks(&data, DEFAULT_MAX_DEPTH).bookmarks;
         assert_eq!(
             collected,
             vec![Bookmark {
                 name: "Array Example".into(),
                 url: "https://array.example.com".into(),
+                folder_path: Vec::new(),
+                date_added: None,
             }]
         );
     }
 
+    /// Nests one folder per level, each holding a bookmark named after its depth.
+    fn nested_folders(levels: usize) -> String {
+        let mut node = serde_json::json!({"type": "folder", "name": "leaf", "children": []});
+        for depth in (1..=levels).rev() {
+            node = serde_json::json!({
+                "type": "folder",
+                "name": format!("level {depth}"),
+                "children": [
+                    {"type": "url", "name": depth.to_string(), "url": format!("https://{depth}.example")},
+                    node
+                ]
+            });
+        }
+        serde_json::json!({"roots": {"bookmark_bar": node}}).to_string()
+    }
+
+    #[test]
+    fn deep_nesting_stops_at_the_depth_limit() {
+        let parsed = parse_bookmarks(&nested_folders(40), 5, RootScope::All).expect("should parse");
+
+        let names: Vec<&str> = parsed.bookmarks.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["1", "2", "3", "4", "5"]);
+        assert_eq!(parsed.bookmarks[4].folder_path.len(), 5);
+        assert_eq!(parsed.skipped_folders, 1);
+    }
+
+    #[test]
+    fn the_default_depth_is_the_deepest_a_file_can_nest() {
+        let parsed = parse_bookmarks(
+            &nested_folders(DEFAULT_MAX_DEPTH),
+            DEFAULT_MAX_DEPTH,
+            RootScope::All,
+        )
+        .expect("should parse");
+        assert_eq!(parsed.bookmarks.len(), DEFAULT_MAX_DEPTH);
+        // Only the empty leaf folder lies beyond the limit.
+        assert_eq!(parsed.skipped_folders, 1);
+
+        let err = parse_bookmarks(
+            &nested_folders(DEFAULT_MAX_DEPTH + 1),
+            DEFAULT_MAX_DEPTH,
+            RootScope::All,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("recursion limit"), "{err}");
+    }
+
     #[test]
     fn parsing_invalid_json_returns_error() {
-        let result = parse_bookmarks("not json");
+        let result = parse_bookmarks("not json", DEFAULT_MAX_DEPTH, RootScope::All);
         assert!(result.is_err());
     }
 }
@@ -1,104 +1,289 @@
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use std::sync::Arc;
-use std::time::Duration;
+/// Receives updates while bookmarks are checked; shared by every worker thread.
+pub trait Progress: Sync {
+    fn inc(&self);
+    fn worker_start(&self, idx: usize, message: &str);
+    fn worker_finish(&self, idx: usize);
+    fn finish(&self);
+}
+
+/// Ignores every update; used when progress is hidden or the `progress` feature is
+/// disabled.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoProgress;
 
-pub struct ProgressReporter {
-    multi: MultiProgress,
-    inner: Arc<ProgressInner>,
+impl Progress for NoProgress {
+    fn inc(&self) {}
+    fn worker_start(&self, _idx: usize, _message: &str) {}
+    fn worker_finish(&self, _idx: usize) {}
+    fn finish(&self) {}
 }
 
-#[derive(Clone)]
-pub struct ProgressHandle {
-    inner: Arc<ProgressInner>,
+/// Progress display for `total` checks across `worker_count` threads.
+#[cfg(feature = "progress")]
+pub fn start(total: usize, worker_count: usize, label: &str) -> Box<dyn Progress> {
+    Box::new(bars::ProgressReporter::new(total, worker_count, label))
 }
 
-struct ProgressInner {
-    overall: ProgressBar,
-    workers: Vec<ProgressBar>,
+/// Progress display for `total` checks across `worker_count` threads.
+#[cfg(not(feature = "progress"))]
+pub fn start(_total: usize, _worker_count: usize, _label: &str) -> Box<dyn Progress> {
+    Box::new(NoProgress)
 }
 
-impl ProgressReporter {
-    pub fn new(total: usize, worker_count: usize, label: &str) -> Self {
-        let multi = MultiProgress::new();
-        let overall = create_overall_bar(&multi, total, label);
-        let workers = (0..worker_count)
-            .map(|idx| create_worker_bar(&multi, idx))
-            .collect();
-
-        Self {
-            multi,
-            inner: Arc::new(ProgressInner { overall, workers }),
-        }
-    }
+/// Shared display for checks running side by side, each drawn as one overall bar.
+#[cfg(feature = "progress")]
+pub use bars::ProgressGroup;
 
-    pub fn handle(&self) -> ProgressHandle {
-        ProgressHandle {
-            inner: Arc::clone(&self.inner),
-        }
+/// Shared display for checks running side by side, each drawn as one overall bar.
+#[cfg(not(feature = "progress"))]
+#[derive(Debug, Default, Clone)]
+pub struct ProgressGroup;
+
+#[cfg(not(feature = "progress"))]
+impl ProgressGroup {
+    pub fn new() -> Self {
+        Self
     }
 
-    pub fn finish(self) {
-        self.inner.overall.finish_and_clear();
-        for worker in &self.inner.workers {
-            worker.finish_and_clear();
-        }
-        let _ = self.multi.clear();
+    pub fn start(&self, _total: usize, _label: &str) -> Box<dyn Progress> {
+        Box::new(NoProgress)
     }
 }
 
-impl ProgressHandle {
-    pub fn inc(&self) {
-        self.inner.overall.inc(1);
+#[cfg(feature = "progress")]
+mod bars {
+    use super::Progress;
+    use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+    use std::time::Duration;
+
+    const OVERALL_TEMPLATE: &str = "{prefix} {bar:40.cyan/blue} {pos}/{len} ({per_sec}, {eta})";
+    /// Beyond this many spinners the terminal scrolls; threads past it are not shown,
+    /// since a shared bar would flicker between its threads' bookmarks.
+    const MAX_WORKER_BARS: usize = 8;
+
+    pub struct ProgressReporter {
+        multi: MultiProgress,
+        overall: ProgressBar,
+        workers: Vec<ProgressBar>,
+        /// Other reporters draw to `multi` too, so only this bar is removed on finish.
+        shared: bool,
+    }
+
+    #[derive(Debug, Default, Clone)]
+    pub struct ProgressGroup {
+        multi: MultiProgress,
     }
 
-    pub fn worker_start(&self, idx: usize, message: impl AsRef<str>) {
-        if let Some(bar) = self.inner.workers.get(idx) {
-            bar.set_message(message.as_ref().to_string());
+    impl ProgressGroup {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Adds a bar for `total` checks; per-worker spinners are left out so that
+        /// concurrent checks do not crowd the terminal.
+        pub fn start(&self, total: usize, label: &str) -> Box<dyn Progress> {
+            Box::new(ProgressReporter::in_group(&self.multi, total, label))
         }
     }
 
-    pub fn worker_finish(&self, idx: usize) {
-        if let Some(bar) = self.inner.workers.get(idx) {
-            bar.set_message("idle".to_string());
+    impl ProgressReporter {
+        pub fn new(total: usize, worker_count: usize, label: &str) -> Self {
+            let multi = MultiProgress::new();
+            let overall = create_overall_bar(&multi, total, label);
+            let workers = (0..worker_count.min(MAX_WORKER_BARS))
+                .map(|idx| create_worker_bar(&multi, idx))
+                .collect();
+
+            Self {
+                multi,
+                overall,
+                workers,
+                shared: false,
+            }
+        }
+
+        fn in_group(multi: &MultiProgress, total: usize, label: &str) -> Self {
+            Self {
+                multi: multi.clone(),
+                overall: create_overall_bar(multi, total, label),
+                workers: Vec::new(),
+                shared: true,
+            }
+        }
+
+        fn worker_bar(&self, idx: usize) -> Option<&ProgressBar> {
+            self.workers.get(idx)
         }
     }
-}
 
-fn create_overall_bar(multi: &MultiProgress, total: usize, label: &str) -> ProgressBar {
-    let bar = multi.add(ProgressBar::new(total as u64));
-    bar.set_style(
-        ProgressStyle::with_template("{prefix} {bar:40.cyan/blue} {pos}/{len} ({eta})")
-            .unwrap()
-            .progress_chars("=>-"),
-    );
-    bar.set_prefix(label.to_string());
-    bar.enable_steady_tick(Duration::from_millis(100));
-    bar
-}
+    impl Progress for ProgressReporter {
+        fn inc(&self) {
+            self.overall.inc(1);
+        }
+
+        fn worker_start(&self, idx: usize, message: &str) {
+            if let Some(bar) = self.worker_bar(idx) {
+                bar.set_message(message.to_string());
+            }
+        }
+
+        fn worker_finish(&self, idx: usize) {
+            if let Some(bar) = self.worker_bar(idx) {
+                bar.set_message("idle".to_string());
+            }
+        }
+
+        fn finish(&self) {
+            self.overall.finish_and_clear();
+            if self.shared {
+                self.multi.remove(&self.overall);
+                return;
+            }
+            for worker in &self.workers {
+                worker.finish_and_clear();
+            }
+            let _ = self.multi.clear();
+        }
+    }
 
-fn create_worker_bar(multi: &MultiProgress, idx: usize) -> ProgressBar {
-    let bar = multi.add(ProgressBar::new_spinner());
-    bar.set_style(
-        ProgressStyle::with_template("Thread {prefix}: {msg}")
+    fn create_overall_bar(multi: &MultiProgress, total: usize, label: &str) -> ProgressBar {
+        let bar = multi.add(ProgressBar::new(total as u64));
+        bar.set_style(overall_style());
+        bar.set_prefix(label.to_string());
+        bar.enable_steady_tick(Duration::from_millis(100));
+        bar
+    }
+
+    fn overall_style() -> ProgressStyle {
+        ProgressStyle::with_template(OVERALL_TEMPLATE)
             .unwrap()
-            .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏"),
-    );
-    bar.set_prefix(idx.to_string());
-    bar.set_message("idle".to_string());
-    bar.enable_steady_tick(Duration::from_millis(100));
-    bar
-}
+            .progress_chars("=>-")
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn handle_updates_overall_count() {
-        let reporter = ProgressReporter::new(3, 1, "Testing");
-        let handle = reporter.handle();
-        handle.inc();
-        assert_eq!(handle.inner.overall.position(), 1);
-        reporter.finish();
+    fn create_worker_bar(multi: &MultiProgress, idx: usize) -> ProgressBar {
+        let bar = multi.add(ProgressBar::new_spinner());
+        bar.set_style(
+            ProgressStyle::with_template("Thread {prefix}: {msg}")
+                .unwrap()
+                .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏"),
+        );
+        bar.set_prefix(idx.to_string());
+        bar.set_message("idle".to_string());
+        bar.enable_steady_tick(Duration::from_millis(100));
+        bar
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use indicatif::{ProgressDrawTarget, TermLike};
+        use std::io;
+        use std::sync::{Arc, Mutex};
+
+        #[test]
+        fn reporter_updates_overall_count() {
+            let reporter = ProgressReporter::new(3, 1, "Testing");
+            reporter.inc();
+            assert_eq!(reporter.overall.position(), 1);
+            reporter.finish();
+        }
+
+        #[test]
+        fn worker_bars_are_capped_and_never_shared() {
+            let reporter = ProgressReporter::new(1, 32, "Capped");
+            assert_eq!(reporter.workers.len(), MAX_WORKER_BARS);
+
+            reporter.worker_start(1, "busy");
+            reporter.worker_start(MAX_WORKER_BARS + 1, "hidden");
+            assert_eq!(reporter.workers[1].message(), "busy");
+            assert!(
+                reporter
+                    .workers
+                    .iter()
+                    .all(|worker| worker.message() != "hidden")
+            );
+            reporter.worker_finish(MAX_WORKER_BARS + 1);
+            assert_eq!(reporter.workers[1].message(), "busy");
+            reporter.worker_finish(1);
+            assert_eq!(reporter.workers[1].message(), "idle");
+            reporter.finish();
+        }
+
+        #[test]
+        fn grouped_bars_have_no_worker_spinners() {
+            let multi = MultiProgress::new();
+            let first = ProgressReporter::in_group(&multi, 1, "Default");
+            let second = ProgressReporter::in_group(&multi, 2, "Profile 1");
+            assert!(first.workers.is_empty());
+
+            first.inc();
+            first.finish();
+            assert!(!second.overall.is_finished());
+
+            second.inc();
+            second.finish();
+        }
+
+        /// A terminal that keeps the line last drawn to it.
+        #[derive(Debug, Default, Clone)]
+        struct Screen(Arc<Mutex<String>>);
+
+        impl TermLike for Screen {
+            fn width(&self) -> u16 {
+                120
+            }
+
+            fn move_cursor_up(&self, _n: usize) -> io::Result<()> {
+                Ok(())
+            }
+
+            fn move_cursor_down(&self, _n: usize) -> io::Result<()> {
+                Ok(())
+            }
+
+            fn move_cursor_right(&self, _n: usize) -> io::Result<()> {
+                Ok(())
+            }
+
+            fn move_cursor_left(&self, _n: usize) -> io::Result<()> {
+                Ok(())
+            }
+
+            fn write_line(&self, s: &str) -> io::Result<()> {
+                self.write_str(s)
+            }
+
+            fn write_str(&self, s: &str) -> io::Result<()> {
+                self.0.lock().unwrap().push_str(s);
+                Ok(())
+            }
+
+            fn clear_line(&self) -> io::Result<()> {
+                self.0.lock().unwrap().clear();
+                Ok(())
+            }
+
+            fn flush(&self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        #[test]
+        fn overall_bar_renders_throughput() {
+            let screen = Screen::default();
+            let bar = ProgressBar::with_draw_target(
+                Some(4),
+                ProgressDrawTarget::term_like(Box::new(screen.clone())),
+            );
+            bar.set_style(overall_style());
+            bar.set_prefix("Throughput");
+            bar.inc(2);
+            bar.tick();
+
+            let line = screen.0.lock().unwrap().clone();
+            assert!(line.starts_with("Throughput "), "{line}");
+            assert!(line.contains(" 2/4 ("), "{line}");
+            assert!(line.contains("/s, "), "{line}");
+        }
     }
 }
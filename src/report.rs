@@ -1,32 +1,129 @@
-use crate::checker::{FailureKind, LinkFailure};
-use crate::model::BookmarkError;
-use serde::Serialize;
+use crate::VERSION;
+use crate::atomic::write_atomic;
+use crate::checker::{FailureKind, LinkFailure, LinkWarning, WarningReason};
+use crate::model::{Bookmark, BookmarkError, ReportFormat, SortOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 pub const FAILURE_REPORT_FILE: &str = "bookmark_failures.yml";
 
+/// JUnit suite name used when the reporter is not told which profile was scanned.
+const DEFAULT_SUITE: &str = "bookmarks";
+
+/// Destination for the failures found by a scan, e.g. a file, database, or webhook.
+pub trait Reporter {
+    fn report(&mut self, failures: &[LinkFailure]) -> Result<(), BookmarkError>;
+}
+
 #[derive(Debug)]
 pub struct FailureReporter {
     output_path: PathBuf,
+    sort: SortOrder,
+    format: ReportFormat,
+    suite: String,
+    append: bool,
+    max_entries: Option<usize>,
 }
 
 impl FailureReporter {
     pub fn new<P: Into<PathBuf>>(output_path: P) -> Self {
         Self {
             output_path: output_path.into(),
+            sort: SortOrder::default(),
+            format: ReportFormat::default(),
+            suite: DEFAULT_SUITE.to_string(),
+            append: false,
+            max_entries: None,
         }
     }
 
-    pub fn default() -> Self {
-        Self::new(FAILURE_REPORT_FILE)
+    /// Sets the output format, switching the report's file extension to match.
+    pub fn with_format(mut self, format: ReportFormat) -> Self {
+        self.format = format;
+        self.output_path.set_extension(format.extension());
+        self
+    }
+
+    pub fn with_sort(mut self, sort: SortOrder) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// Merges into an existing YAML report instead of replacing it. A URL reported
+    /// again under the same kind keeps only its newest entry.
+    pub fn with_append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    /// Keeps at most `max_entries` entries per YAML section; `None` keeps them all.
+    pub fn with_max_entries(mut self, max_entries: Option<usize>) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Names the JUnit test suite, normally after the scanned profile.
+    pub fn with_suite<S: Into<String>>(mut self, suite: S) -> Self {
+        self.suite = suite.into();
+        self
+    }
+
+    pub fn in_dir(output_dir: &Path) -> Self {
+        Self::new(output_dir.join(FAILURE_REPORT_FILE))
     }
 
     pub fn write_report(&self, failures: &[LinkFailure]) -> Result<(), BookmarkError> {
-        let report = FailureReport::from_failures(failures);
-        let yaml = serde_yaml::to_string(&report)?;
-        fs::write(&self.output_path, yaml)?;
-        Ok(())
+        self.write_checked_report(&[], failures)
+    }
+
+    /// Like `write_report`, but also knows every bookmark that was checked, so the
+    /// JUnit format can list the passing ones. Without them, only failures appear.
+    pub fn write_checked_report(
+        &self,
+        checked: &[Bookmark],
+        failures: &[LinkFailure],
+    ) -> Result<(), BookmarkError> {
+        self.write_scan_report(checked, failures, &[])
+    }
+
+    /// Like `write_checked_report`, plus the scan's warnings. Only the YAML format
+    /// lists them; SARIF and JUnit report failures alone.
+    pub(crate) fn write_scan_report(
+        &self,
+        checked: &[Bookmark],
+        failures: &[LinkFailure],
+        warnings: &[LinkWarning],
+    ) -> Result<(), BookmarkError> {
+        let mut report = FailureReport::from_failures(failures, warnings, self.sort, None);
+        if self.append && self.format == ReportFormat::Yaml && self.output_path.exists() {
+            report.absorb(FailureReport::load(&self.output_path)?, self.sort);
+        }
+        if let Some(max) = self.max_entries {
+            report.truncate(max);
+        }
+        if let Some(parent) = self
+            .output_path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomic(&self.output_path, |writer| match self.format {
+            ReportFormat::Yaml => {
+                serde_yaml::to_writer(writer, &report)?;
+                Ok(())
+            }
+            ReportFormat::Sarif => serde_json::to_writer_pretty(writer, &report.to_sarif())
+                .map_err(BookmarkError::ReportSerialization),
+            ReportFormat::JUnit => {
+                writer.write_all(to_junit(&self.suite, checked, failures).as_bytes())?;
+                Ok(())
+            }
+        })
     }
 
     pub fn output_path(&self) -> &Path {
@@ -34,41 +131,435 @@ impl FailureReporter {
     }
 }
 
-#[derive(Debug, Serialize)]
-struct FailureReport {
-    not_found: Vec<ReportEntry>,
-    unauthorized: Vec<ReportEntry>,
-    connection_errors: Vec<ReportEntry>,
+impl Default for FailureReporter {
+    fn default() -> Self {
+        Self::new(FAILURE_REPORT_FILE)
+    }
+}
+
+impl Reporter for FailureReporter {
+    fn report(&mut self, failures: &[LinkFailure]) -> Result<(), BookmarkError> {
+        self.write_report(failures)
+    }
+}
+
+/// The YAML report layout, shared by the writer and by everything that reads a
+/// report back (`--clean`, `--resume`, `--recheck`) so the two cannot drift.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct FailureReport {
+    #[serde(default)]
+    pub(crate) not_found: Vec<ReportEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) gone: Vec<ReportEntry>,
+    #[serde(default)]
+    pub(crate) unauthorized: Vec<ReportEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) method_not_allowed: Vec<ReportEntry>,
+    #[serde(default)]
+    pub(crate) connection_errors: Vec<ReportEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) parked: Vec<ReportEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) redirected: Vec<ReportEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) ssl_warnings: Vec<ReportEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) updatable: Vec<ReportEntry>,
+    /// How many more entries each section had beyond `--report-max-entries`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) omitted: BTreeMap<String, usize>,
 }
 
 impl FailureReport {
-    fn from_failures(failures: &[LinkFailure]) -> Self {
+    pub(crate) fn load(path: &Path) -> Result<Self, BookmarkError> {
+        let contents = fs::read_to_string(path)?;
+        serde_yaml::from_str(&contents).map_err(BookmarkError::ReportParse)
+    }
+
+    /// Every failure entry, section by section in report order. Warning sections
+    /// are left out: their bookmarks still work.
+    pub(crate) fn into_entries(self) -> impl Iterator<Item = ReportEntry> {
+        self.not_found
+            .into_iter()
+            .chain(self.gone)
+            .chain(self.unauthorized)
+            .chain(self.method_not_allowed)
+            .chain(self.connection_errors)
+            .chain(self.parked)
+            .chain(self.redirected)
+    }
+
+    /// Sorts each section, then cuts it to `max_entries` when given.
+    fn from_failures(
+        failures: &[LinkFailure],
+        warnings: &[LinkWarning],
+        sort: SortOrder,
+        max_entries: Option<usize>,
+    ) -> Self {
         let mut not_found = Vec::new();
+        let mut gone = Vec::new();
         let mut unauthorized = Vec::new();
+        let mut method_not_allowed = Vec::new();
         let mut connection_errors = Vec::new();
+        let mut parked = Vec::new();
+        let mut redirected = Vec::new();
+        let mut ssl_warnings = Vec::new();
+        let mut updatable = Vec::new();
 
         for failure in failures {
             let entry = ReportEntry::from(failure);
             match failure.kind {
                 FailureKind::NotFound => not_found.push(entry),
+                FailureKind::Gone => gone.push(entry),
                 FailureKind::Unauthorized => unauthorized.push(entry),
+                FailureKind::MethodNotAllowed => method_not_allowed.push(entry),
                 FailureKind::Connection => connection_errors.push(entry),
+                FailureKind::Parked => parked.push(entry),
+                FailureKind::Redirected => redirected.push(entry),
+            }
+        }
+        for warning in warnings {
+            let entry = ReportEntry::from(warning);
+            match warning.reason {
+                WarningReason::CertificateExpiring(_) => ssl_warnings.push(entry),
+                WarningReason::MovedTo(_) => updatable.push(entry),
             }
         }
 
-        Self {
+        for entries in [
+            &mut not_found,
+            &mut gone,
+            &mut unauthorized,
+            &mut method_not_allowed,
+            &mut connection_errors,
+            &mut parked,
+            &mut redirected,
+            &mut ssl_warnings,
+            &mut updatable,
+        ] {
+            sort_entries(entries, sort);
+        }
+
+        let mut report = Self {
             not_found,
+            gone,
             unauthorized,
+            method_not_allowed,
             connection_errors,
+            parked,
+            redirected,
+            ssl_warnings,
+            updatable,
+            omitted: BTreeMap::new(),
+        };
+        if let Some(max) = max_entries {
+            report.truncate(max);
         }
+        report
     }
+
+    /// Keeps the first `max` entries of each section and adds the rest to `omitted`.
+    fn truncate(&mut self, max: usize) {
+        let sections = [
+            ("not_found", &mut self.not_found),
+            ("gone", &mut self.gone),
+            ("unauthorized", &mut self.unauthorized),
+            ("method_not_allowed", &mut self.method_not_allowed),
+            ("connection_errors", &mut self.connection_errors),
+            ("parked", &mut self.parked),
+            ("redirected", &mut self.redirected),
+            ("ssl_warnings", &mut self.ssl_warnings),
+            ("updatable", &mut self.updatable),
+        ];
+        for (section, entries) in sections {
+            if entries.len() > max {
+                *self.omitted.entry(section.to_string()).or_default() += entries.len() - max;
+                entries.truncate(max);
+            }
+        }
+    }
+}
+
+/// Several profiles' failure sections in one YAML document keyed by profile name,
+/// for `--profile-report-stdout`.
+#[derive(Debug, Default, Serialize)]
+#[serde(transparent)]
+pub(crate) struct ConsolidatedReport {
+    profiles: BTreeMap<String, FailureReport>,
 }
 
-#[derive(Debug, Serialize)]
-struct ReportEntry {
-    name: String,
-    url: String,
-    reason: String,
+impl ConsolidatedReport {
+    pub(crate) fn add(
+        &mut self,
+        profile: &str,
+        failures: &[LinkFailure],
+        warnings: &[LinkWarning],
+        sort: SortOrder,
+        max_entries: Option<usize>,
+    ) {
+        self.profiles.insert(
+            profile.to_string(),
+            FailureReport::from_failures(failures, warnings, sort, max_entries),
+        );
+    }
+
+    pub(crate) fn write_to<W: Write>(&self, writer: W) -> Result<(), BookmarkError> {
+        serde_yaml::to_writer(writer, self).map_err(BookmarkError::ReportWrite)
+    }
+}
+
+impl FailureReport {
+    /// Adds the `earlier` entries whose URL this report does not list again under
+    /// the same kind, along with the earlier `omitted` counts.
+    fn absorb(&mut self, mut earlier: FailureReport, sort: SortOrder) {
+        for (section, count) in std::mem::take(&mut earlier.omitted) {
+            *self.omitted.entry(section).or_default() += count;
+        }
+        for (entries, previous) in self.sections_mut().into_iter().zip(earlier.into_sections()) {
+            let fresh: HashSet<String> = entries.iter().map(|entry| entry.url.clone()).collect();
+            entries.extend(
+                previous
+                    .into_iter()
+                    .filter(|entry| !fresh.contains(&entry.url)),
+            );
+            sort_entries(entries, sort);
+        }
+    }
+
+    fn sections_mut(&mut self) -> [&mut Vec<ReportEntry>; 9] {
+        [
+            &mut self.not_found,
+            &mut self.gone,
+            &mut self.unauthorized,
+            &mut self.method_not_allowed,
+            &mut self.connection_errors,
+            &mut self.parked,
+            &mut self.redirected,
+            &mut self.ssl_warnings,
+            &mut self.updatable,
+        ]
+    }
+
+    fn into_sections(self) -> [Vec<ReportEntry>; 9] {
+        [
+            self.not_found,
+            self.gone,
+            self.unauthorized,
+            self.method_not_allowed,
+            self.connection_errors,
+            self.parked,
+            self.redirected,
+            self.ssl_warnings,
+            self.updatable,
+        ]
+    }
+
+    /// The failure sections with their kind; warning sections are not included.
+    fn sections(&self) -> [(FailureKind, &[ReportEntry]); 7] {
+        [
+            (FailureKind::NotFound, &self.not_found),
+            (FailureKind::Gone, &self.gone),
+            (FailureKind::Unauthorized, &self.unauthorized),
+            (FailureKind::MethodNotAllowed, &self.method_not_allowed),
+            (FailureKind::Connection, &self.connection_errors),
+            (FailureKind::Parked, &self.parked),
+            (FailureKind::Redirected, &self.redirected),
+        ]
+    }
+
+    /// Minimal SARIF 2.1.0 log: one rule per failure kind, one result per bookmark.
+    fn to_sarif(&self) -> Value {
+        let sections = self.sections();
+        let rules: Vec<Value> = sections
+            .iter()
+            .map(|(kind, _)| {
+                json!({
+                    "id": kind.label(),
+                    "shortDescription": { "text": sarif_rule_description(*kind) },
+                })
+            })
+            .collect();
+
+        let results: Vec<Value> = sections
+            .iter()
+            .flat_map(|(kind, entries)| entries.iter().map(move |entry| (*kind, entry)))
+            .map(|(kind, entry)| {
+                json!({
+                    "ruleId": kind.label(),
+                    "level": sarif_level(kind),
+                    "message": { "text": format!("{}: {}", entry.name, entry.reason) },
+                    "locations": [{
+                        "physicalLocation": { "artifactLocation": { "uri": entry.url } }
+                    }],
+                })
+            })
+            .collect();
+
+        json!({
+            "version": "2.1.0",
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "bookmark-checker",
+                        "version": VERSION,
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }],
+        })
+    }
+}
+
+fn sarif_rule_description(kind: FailureKind) -> &'static str {
+    match kind {
+        FailureKind::NotFound => "Bookmark returned HTTP 404 Not Found",
+        FailureKind::Gone => "Bookmark returned HTTP 410 Gone",
+        FailureKind::Unauthorized => "Bookmark requires authorization",
+        FailureKind::MethodNotAllowed => "Bookmark rejected the HEAD request",
+        FailureKind::Connection => "Bookmark could not be reached",
+        FailureKind::Parked => "Bookmark redirects to a domain parking page",
+        FailureKind::Redirected => "Bookmark redirects in a loop or too many times",
+    }
+}
+
+fn sarif_level(kind: FailureKind) -> &'static str {
+    match kind {
+        FailureKind::NotFound | FailureKind::Gone => "error",
+        _ => "warning",
+    }
+}
+
+/// JUnit XML with one test case per checked bookmark, each dead link carrying a
+/// `<failure>` with its reason. Failures missing from `checked` are still listed.
+fn to_junit(suite: &str, checked: &[Bookmark], failures: &[LinkFailure]) -> String {
+    let by_url: HashMap<&str, &LinkFailure> = failures
+        .iter()
+        .map(|failure| (failure.bookmark.url.as_str(), failure))
+        .collect();
+    let mut cases: Vec<(&Bookmark, Option<&LinkFailure>)> = checked
+        .iter()
+        .map(|bookmark| (bookmark, by_url.get(bookmark.url.as_str()).copied()))
+        .collect();
+    if checked.is_empty() {
+        cases.extend(
+            failures
+                .iter()
+                .map(|failure| (&failure.bookmark, Some(failure))),
+        );
+    }
+
+    let failed = cases
+        .iter()
+        .filter(|(_, failure)| failure.is_some())
+        .count();
+    let suite = escape_xml(suite);
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"bookmark-checker\" tests=\"{}\" failures=\"{failed}\">\n",
+        cases.len()
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{suite}\" tests=\"{}\" failures=\"{failed}\">\n",
+        cases.len()
+    ));
+    for (bookmark, failure) in cases {
+        let classname = if bookmark.folder_path.is_empty() {
+            suite.clone()
+        } else {
+            escape_xml(&bookmark.folder_path.join("/"))
+        };
+        let name = escape_xml(&bookmark.url);
+        match failure {
+            None => xml.push_str(&format!(
+                "    <testcase classname=\"{classname}\" name=\"{name}\"/>\n"
+            )),
+            Some(failure) => {
+                let reason = escape_xml(&failure.reason.to_string());
+                xml.push_str(&format!(
+                    "    <testcase classname=\"{classname}\" name=\"{name}\">\n"
+                ));
+                xml.push_str(&format!(
+                    "      <failure type=\"{}\" message=\"{reason}\">{}: {reason}</failure>\n",
+                    failure.kind.label(),
+                    escape_xml(&bookmark.name)
+                ));
+                xml.push_str("    </testcase>\n");
+            }
+        }
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn sort_entries(entries: &mut [ReportEntry], sort: SortOrder) {
+    match sort {
+        SortOrder::Name => entries.sort_by(|a, b| a.name.cmp(&b.name).then(a.url.cmp(&b.url))),
+        SortOrder::Url => entries.sort_by(|a, b| a.url.cmp(&b.url).then(a.name.cmp(&b.name))),
+        SortOrder::Folder => entries.sort_by(|a, b| {
+            a.folder
+                .cmp(&b.folder)
+                .then(a.name.cmp(&b.name))
+                .then(a.url.cmp(&b.url))
+        }),
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ReportEntry {
+    #[serde(default)]
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) url: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub(crate) folder: String,
+    #[serde(default)]
+    pub(crate) reason: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) redirect_chain: Vec<String>,
+    /// Where an `updatable` bookmark now leads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) suggested_url: Option<String>,
+}
+
+impl ReportEntry {
+    /// Rebuilds the reported bookmark; `None` for entries without a URL.
+    pub(crate) fn into_bookmark(self) -> Option<Bookmark> {
+        if self.url.is_empty() {
+            return None;
+        }
+
+        Some(Bookmark {
+            name: if self.name.is_empty() {
+                self.url.clone()
+            } else {
+                self.name
+            },
+            folder_path: if self.folder.is_empty() {
+                Vec::new()
+            } else {
+                self.folder.split('/').map(str::to_string).collect()
+            },
+            url: self.url,
+            date_added: None,
+        })
+    }
 }
 
 impl From<&LinkFailure> for ReportEntry {
@@ -76,7 +567,26 @@ impl From<&LinkFailure> for ReportEntry {
         Self {
             name: value.bookmark.name.clone(),
             url: value.bookmark.url.clone(),
-            reason: value.reason.clone(),
+            folder: value.bookmark.folder_path.join("/"),
+            reason: value.reason.to_string(),
+            redirect_chain: value.redirect_chain.clone(),
+            suggested_url: None,
+        }
+    }
+}
+
+impl From<&LinkWarning> for ReportEntry {
+    fn from(value: &LinkWarning) -> Self {
+        Self {
+            name: value.bookmark.name.clone(),
+            url: value.bookmark.url.clone(),
+            folder: value.bookmark.folder_path.join("/"),
+            reason: value.reason.to_string(),
+            redirect_chain: Vec::new(),
+            suggested_url: match &value.reason {
+                WarningReason::MovedTo(url) => Some(url.clone()),
+                WarningReason::CertificateExpiring(_) => None,
+            },
         }
     }
 }
@@ -84,12 +594,16 @@ impl From<&LinkFailure> for ReportEntry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checker::FailureReason;
     use crate::model::Bookmark;
+    use crate::test_support::temp_path;
 
     fn bookmark(name: &str, url: &str) -> Bookmark {
         Bookmark {
             name: name.into(),
             url: url.into(),
+            folder_path: Vec::new(),
+            date_added: None,
         }
     }
 
@@ -98,43 +612,388 @@ mod tests {
         let failures = vec![
             LinkFailure {
                 bookmark: bookmark("Missing", "https://example.com/missing"),
-                reason: "HTTP 404 Not Found".into(),
+                reason: FailureReason::Http(404),
                 kind: FailureKind::NotFound,
+                redirect_chain: Vec::new(),
             },
             LinkFailure {
                 bookmark: bookmark("Private", "https://example.com/private"),
-                reason: "HTTP 403 Forbidden".into(),
+                reason: FailureReason::Http(403),
                 kind: FailureKind::Unauthorized,
+                redirect_chain: Vec::new(),
             },
             LinkFailure {
                 bookmark: bookmark("Timeout", "https://example.com/timeout"),
-                reason: "Request failed: timeout".into(),
+                reason: FailureReason::Other("Request failed: timeout".into()),
                 kind: FailureKind::Connection,
+                redirect_chain: Vec::new(),
             },
         ];
 
-        let report = FailureReport::from_failures(&failures);
+        let report = FailureReport::from_failures(&failures, &[], SortOrder::Url, None);
         assert_eq!(report.not_found.len(), 1);
+        assert!(report.gone.is_empty());
         assert_eq!(report.unauthorized.len(), 1);
         assert_eq!(report.connection_errors.len(), 1);
     }
 
+    #[test]
+    fn sections_beyond_max_entries_are_truncated_and_counted() {
+        let failures: Vec<LinkFailure> = (0..5)
+            .map(|idx| LinkFailure {
+                bookmark: bookmark("Down", &format!("https://example.com/{idx}")),
+                reason: FailureReason::Other("Request failed: timeout".into()),
+                kind: FailureKind::Connection,
+                redirect_chain: Vec::new(),
+            })
+            .chain([LinkFailure {
+                bookmark: bookmark("Missing", "https://example.com/missing"),
+                reason: FailureReason::Http(404),
+                kind: FailureKind::NotFound,
+                redirect_chain: Vec::new(),
+            }])
+            .collect();
+
+        let report = FailureReport::from_failures(&failures, &[], SortOrder::Url, Some(2));
+        assert_eq!(
+            report
+                .connection_errors
+                .iter()
+                .map(|entry| entry.url.as_str())
+                .collect::<Vec<_>>(),
+            ["https://example.com/0", "https://example.com/1"]
+        );
+        assert_eq!(report.not_found.len(), 1);
+        assert_eq!(
+            report.omitted,
+            BTreeMap::from([("connection_errors".to_string(), 3)])
+        );
+
+        let yaml = serde_yaml::to_string(&report).expect("serialize");
+        assert!(yaml.contains("omitted:\n  connection_errors: 3"));
+    }
+
+    #[test]
+    fn warnings_are_reported_but_not_read_back_as_failures() {
+        let failures = vec![LinkFailure {
+            bookmark: bookmark("Missing", "https://example.com/missing"),
+            reason: FailureReason::Http(404),
+            kind: FailureKind::NotFound,
+            redirect_chain: Vec::new(),
+        }];
+        let warnings = vec![LinkWarning {
+            bookmark: bookmark("Expiring", "https://example.com/"),
+            reason: WarningReason::CertificateExpiring(3),
+        }];
+
+        let report = FailureReport::from_failures(&failures, &warnings, SortOrder::Url, None);
+        assert_eq!(report.ssl_warnings.len(), 1);
+        assert_eq!(
+            report.ssl_warnings[0].reason,
+            "TLS certificate expires in 3 day(s)"
+        );
+        let urls: Vec<String> = report.into_entries().map(|entry| entry.url).collect();
+        assert_eq!(urls, ["https://example.com/missing"]);
+    }
+
+    #[test]
+    fn gone_failures_get_their_own_section() {
+        let failures = vec![LinkFailure {
+            bookmark: bookmark("Retired", "https://example.com/retired"),
+            reason: FailureReason::Http(410),
+            kind: FailureKind::Gone,
+            redirect_chain: Vec::new(),
+        }];
+
+        let report = FailureReport::from_failures(&failures, &[], SortOrder::Url, None);
+        assert_eq!(report.gone.len(), 1);
+        assert!(report.not_found.is_empty());
+    }
+
+    #[test]
+    fn consolidated_report_keys_sections_by_profile() {
+        let failure = |url: &str, status: u16, kind: FailureKind| LinkFailure {
+            bookmark: bookmark("Example", url),
+            reason: FailureReason::Http(status),
+            kind,
+            redirect_chain: Vec::new(),
+        };
+        let mut report = ConsolidatedReport::default();
+        report.add(
+            "Work",
+            &[
+                failure("https://work.example/old", 404, FailureKind::NotFound),
+                failure("https://work.example/admin", 401, FailureKind::Unauthorized),
+            ],
+            &[],
+            SortOrder::Url,
+            None,
+        );
+        report.add(
+            "Default",
+            &[failure("https://home.example", 410, FailureKind::Gone)],
+            &[],
+            SortOrder::Url,
+            None,
+        );
+
+        let mut yaml = Vec::new();
+        report.write_to(&mut yaml).expect("serialize");
+        let parsed: BTreeMap<String, FailureReport> =
+            serde_yaml::from_slice(&yaml).expect("keyed by profile");
+
+        assert_eq!(parsed.keys().collect::<Vec<_>>(), ["Default", "Work"]);
+        assert_eq!(parsed["Default"].gone[0].url, "https://home.example");
+        assert!(parsed["Default"].not_found.is_empty());
+        assert_eq!(parsed["Work"].not_found[0].url, "https://work.example/old");
+        assert_eq!(
+            parsed["Work"].unauthorized[0].url,
+            "https://work.example/admin"
+        );
+    }
+
+    #[test]
+    fn sarif_output_lists_each_failure_as_a_result() {
+        let path = temp_path("bookmark-checker-report").with_extension("sarif");
+
+        let failures = vec![
+            LinkFailure {
+                bookmark: bookmark("Missing", "https://example.com/missing"),
+                reason: FailureReason::Http(404),
+                kind: FailureKind::NotFound,
+                redirect_chain: Vec::new(),
+            },
+            LinkFailure {
+                bookmark: bookmark("Timeout", "https://example.com/timeout"),
+                reason: FailureReason::Timeout,
+                kind: FailureKind::Connection,
+                redirect_chain: Vec::new(),
+            },
+        ];
+
+        let reporter = FailureReporter::new(&path).with_format(ReportFormat::Sarif);
+        reporter.write_report(&failures).expect("write");
+
+        let sarif: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let results = sarif["runs"][0]["results"].as_array().expect("results");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "not_found");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "https://example.com/missing"
+        );
+        assert_eq!(results[1]["ruleId"], "connection_errors");
+        assert_eq!(reporter.output_path().extension().unwrap(), "sarif");
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn failure_kind_labels_name_their_report_sections() {
+        let failures: Vec<LinkFailure> = FailureReport::default()
+            .sections()
+            .into_iter()
+            .map(|(kind, _)| LinkFailure {
+                bookmark: bookmark(kind.label(), &format!("https://{}.example", kind.label())),
+                reason: FailureReason::Other("failed".into()),
+                kind,
+                redirect_chain: Vec::new(),
+            })
+            .collect();
+
+        let report = FailureReport::from_failures(&failures, &[], SortOrder::Url, None);
+        let yaml = serde_yaml::to_value(&report).expect("serialize");
+        for failure in &failures {
+            let section = &yaml[failure.kind.label()];
+            assert_eq!(
+                section.as_sequence().map(Vec::len),
+                Some(1),
+                "{}",
+                failure.kind.label()
+            );
+        }
+    }
+
+    #[test]
+    fn junit_output_has_a_test_case_per_checked_bookmark() {
+        let path = temp_path("bookmark-checker-report").with_extension("xml");
+
+        let checked = vec![
+            bookmark("Healthy", "https://example.com/"),
+            bookmark("Missing", "https://example.com/missing"),
+            bookmark("Tom & Jerry", "https://example.com/?a=1&b=<2>"),
+        ];
+        let failures = vec![
+            LinkFailure {
+                bookmark: checked[1].clone(),
+                reason: FailureReason::Http(404),
+                kind: FailureKind::NotFound,
+                redirect_chain: Vec::new(),
+            },
+            LinkFailure {
+                bookmark: checked[2].clone(),
+                reason: FailureReason::Timeout,
+                kind: FailureKind::Connection,
+                redirect_chain: Vec::new(),
+            },
+        ];
+
+        let reporter = FailureReporter::new(&path)
+            .with_format(ReportFormat::JUnit)
+            .with_suite("Profile 1");
+        reporter
+            .write_checked_report(&checked, &failures)
+            .expect("write");
+
+        let xml = fs::read_to_string(reporter.output_path()).unwrap();
+        assert_eq!(xml.matches("<testcase ").count(), 3);
+        assert_eq!(xml.matches("<failure ").count(), 2);
+        assert!(xml.contains(r#"<testsuite name="Profile 1" tests="3" failures="2">"#));
+        assert!(xml.contains(r#"<failure type="not_found""#));
+        assert!(xml.contains("https://example.com/?a=1&amp;b=&lt;2&gt;"));
+        assert!(xml.contains("Tom &amp; Jerry"));
+        assert_eq!(reporter.output_path().extension().unwrap(), "xml");
+
+        let _ = fs::remove_file(reporter.output_path());
+    }
+
+    #[test]
+    fn appended_reports_accumulate_and_dedupe_by_url() {
+        let path = temp_path("bookmark-checker-append").with_extension("yml");
+        let failure = |url: &str, reason, kind| LinkFailure {
+            bookmark: bookmark(url, url),
+            reason,
+            kind,
+            redirect_chain: Vec::new(),
+        };
+        let reporter = FailureReporter::new(&path).with_append(true);
+
+        reporter
+            .write_report(&[
+                failure(
+                    "https://a.example",
+                    FailureReason::Http(404),
+                    FailureKind::NotFound,
+                ),
+                failure(
+                    "https://b.example",
+                    FailureReason::Timeout,
+                    FailureKind::Connection,
+                ),
+            ])
+            .expect("first");
+        reporter
+            .write_report(&[
+                failure(
+                    "https://c.example",
+                    FailureReason::Http(404),
+                    FailureKind::NotFound,
+                ),
+                failure(
+                    "https://b.example",
+                    FailureReason::Dns,
+                    FailureKind::Connection,
+                ),
+            ])
+            .expect("second");
+
+        let report = FailureReport::load(&path).expect("load");
+        let urls = |entries: &[ReportEntry]| -> Vec<String> {
+            entries.iter().map(|entry| entry.url.clone()).collect()
+        };
+        assert_eq!(
+            urls(&report.not_found),
+            ["https://a.example", "https://c.example"]
+        );
+        assert_eq!(urls(&report.connection_errors), ["https://b.example"]);
+        assert_eq!(
+            report.connection_errors[0].reason,
+            FailureReason::Dns.to_string()
+        );
+
+        FailureReporter::new(&path)
+            .write_report(&[failure(
+                "https://d.example",
+                FailureReason::Http(404),
+                FailureKind::NotFound,
+            )])
+            .expect("overwrite");
+        let report = FailureReport::load(&path).expect("load");
+        assert_eq!(urls(&report.not_found), ["https://d.example"]);
+        assert!(report.connection_errors.is_empty());
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn capped_appends_stay_capped_and_add_up_the_omitted_counts() {
+        let path = temp_path("bookmark-checker-capped-append").with_extension("yml");
+        let unreachable = |first: usize| -> Vec<LinkFailure> {
+            (first..first + 3)
+                .map(|idx| LinkFailure {
+                    bookmark: bookmark("Down", &format!("https://example.com/{idx}")),
+                    reason: FailureReason::Timeout,
+                    kind: FailureKind::Connection,
+                    redirect_chain: Vec::new(),
+                })
+                .collect()
+        };
+        let reporter = FailureReporter::new(&path)
+            .with_append(true)
+            .with_max_entries(Some(2));
+
+        reporter.write_report(&unreachable(0)).expect("first");
+        reporter.write_report(&unreachable(3)).expect("second");
+
+        let report = FailureReport::load(&path).expect("load");
+        assert_eq!(report.connection_errors.len(), 2);
+        assert_eq!(
+            report.omitted,
+            BTreeMap::from([("connection_errors".to_string(), 4)])
+        );
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn report_entries_are_sorted_by_url_by_default() {
+        let failures: Vec<LinkFailure> = [
+            "https://c.example",
+            "https://a.example",
+            "https://b.example",
+        ]
+        .iter()
+        .map(|url| LinkFailure {
+            bookmark: bookmark("Missing", url),
+            reason: FailureReason::Http(404),
+            kind: FailureKind::NotFound,
+            redirect_chain: Vec::new(),
+        })
+        .collect();
+
+        let report = FailureReport::from_failures(&failures, &[], SortOrder::default(), None);
+        let urls: Vec<&str> = report.not_found.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://a.example",
+                "https://b.example",
+                "https://c.example"
+            ]
+        );
+    }
+
     #[test]
     fn reporter_writes_yaml_to_disk() {
-        let mut path = std::env::temp_dir();
-        path.push(format!(
-            "bookmark-checker-report-{}.yml",
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_nanos()
-        ));
+        let path = temp_path("bookmark-checker-report").with_extension("yml");
 
         let reporter = FailureReporter::new(&path);
         let failures = vec![LinkFailure {
             bookmark: bookmark("Missing", "https://example.com/missing"),
-            reason: "HTTP 404 Not Found".into(),
+            reason: FailureReason::Http(404),
             kind: FailureKind::NotFound,
+            redirect_chain: Vec::new(),
         }];
 
         reporter.write_report(&failures).expect("write");
@@ -145,4 +1004,24 @@ mod tests {
 
         let _ = fs::remove_file(path);
     }
+
+    #[test]
+    fn reporter_creates_output_dir() {
+        let dir = temp_path("bookmark-checker-output");
+        let nested = dir.join("reports");
+
+        let reporter = FailureReporter::in_dir(&nested);
+        let failures = vec![LinkFailure {
+            bookmark: bookmark("Missing", "https://example.com/missing"),
+            reason: FailureReason::Http(404),
+            kind: FailureKind::NotFound,
+            redirect_chain: Vec::new(),
+        }];
+
+        reporter.write_report(&failures).expect("write");
+        assert_eq!(reporter.output_path(), nested.join(FAILURE_REPORT_FILE));
+        assert!(nested.join(FAILURE_REPORT_FILE).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
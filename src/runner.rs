@@ -1,226 +1,2798 @@
-use crate::checker::check_bookmarks;
-use crate::cleaner;
-use crate::model::{Bookmark, BookmarkError, BookmarkLocation, RunConfig};
-use crate::report::{FAILURE_REPORT_FILE, FailureReporter};
-use crate::{VERSION, locator, parser};
+use crate::checker::{
+    CheckOptions, FailureKind, HttpProbe, LinkFailure, LinkWarning, ProbeOutcome, UrlProbe,
+    check_bookmarks, check_bookmarks_in_group, check_bookmarks_with,
+};
+use crate::cleaner::{self, CleanOptions};
+use crate::diff::{self, PreviousFailures};
+use crate::ignore::{IGNORE_FILE, IgnoreList};
+use crate::model::{
+    Bookmark, BookmarkError, BookmarkLocation, InputFormat, RunConfig, ScanOutcome,
+};
+use crate::parser::RootScope;
+use crate::progress::ProgressGroup;
+use crate::report::{ConsolidatedReport, FAILURE_REPORT_FILE, FailureReporter, Reporter};
+use crate::state::{self, RunState, STATE_FILE};
+use crate::stream::ResultStream;
+use crate::{VERSION, backup, locator, mixed_content, opener, parser, sample, stats};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashSet};
+use std::env;
 use std::fs;
-use std::path::Path;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-pub fn run() -> Result<(), BookmarkError> {
-    run_with_config(RunConfig::default())
+/// Profile used when `--profile` is not given.
+const PROFILE_ENV: &str = "BOOKMARK_CHECKER_PROFILE";
+
+/// Asked before a scan; an answer of any kind from one of them means the network is up.
+const PREFLIGHT_URLS: [&str; 2] = [
+    "https://www.google.com/generate_204",
+    "https://www.cloudflare.com/cdn-cgi/trace",
+];
+
+/// Heading for `--group-by-host` failures whose URL has no host.
+const UNKNOWN_HOST: &str = "(no host)";
+
+/// How long to wait before reading a cut-off Bookmarks file again, giving a browser
+/// that was mid-write time to finish.
+const REREAD_DELAY: Duration = Duration::from_millis(300);
+
+/// Prints a scan status line, unless `--compact` keeps stdout to the summary or
+/// `--profile-report-stdout` keeps it to the report. Under `--stream -` it goes to
+/// stderr, leaving stdout to the JSON lines.
+macro_rules! note {
+    ($config:expr, $($arg:tt)*) => {
+        if !$config.compact && !$config.profile_report_stdout {
+            if $config.streams_to_stdout() {
+                eprintln!($($arg)*);
+            } else {
+                println!($($arg)*);
+            }
+        }
+    };
+}
+
+pub fn run() -> Result<ScanOutcome, BookmarkError> {
+    run_with_config(RunConfig::default(), None)
 }
 
-pub fn run_with_config(config: RunConfig) -> Result<(), BookmarkError> {
+/// Runs the configured action. Actions that check URLs report how many failed.
+///
+/// Scan failures go to `custom_reporter` when given, instead of the report file.
+pub fn run_with_config(
+    mut config: RunConfig,
+    mut custom_reporter: Option<Box<dyn Reporter>>,
+) -> Result<ScanOutcome, BookmarkError> {
+    config.validate().map_err(BookmarkError::InvalidConfig)?;
+
+    let reporter = custom_reporter
+        .as_deref_mut()
+        .map(|reporter| reporter as &mut dyn Reporter);
+
+    if config.profile_glob.is_none() && config.profile_index.is_none() && !config.profile_auto {
+        config.profiles = profiles_or_env_default(config.profiles, env::var(PROFILE_ENV).ok());
+    }
+
+    if config.print_config {
+        print!("{}", render_config(&config)?);
+        return Ok(ScanOutcome::default());
+    }
+
     if config.clean {
-        return clean_from_report(&config);
+        clean_from_report(&config)?;
+        return Ok(ScanOutcome::default());
     }
 
     if config.show_version {
         println!("{VERSION}");
-        return Ok(());
+        return Ok(ScanOutcome::default());
     }
 
-    if config.list_profiles {
-        print_available_profiles()?;
-        return Ok(());
+    if config.validate_only {
+        validate_bookmarks(&config)?;
+        return Ok(ScanOutcome::default());
     }
 
-    let (location, mut bookmarks) = gather_bookmarks_for_profile(config.profile.as_deref())?;
+    if config.normalize {
+        strip_tracking_params(&config)?;
+        return Ok(ScanOutcome::default());
+    }
 
-    if bookmarks.is_empty() {
-        println!("No bookmarks found in {}", location.file.display());
-        return Ok(());
+    if config.dedupe_write {
+        remove_duplicates(&config)?;
+        return Ok(ScanOutcome::default());
     }
 
-    let total_found = apply_limit(&mut bookmarks, config.max_bookmarks);
-    let processing = bookmarks.len();
+    if config.normalize_names {
+        normalize_bookmark_names(&config)?;
+        return Ok(ScanOutcome::default());
+    }
 
-    if processing == 0 {
-        println!("Bookmark limit of 0 prevents checking any entries ({total_found} total found).");
-        return Ok(());
+    if config.list_backups {
+        print_backups(&config)?;
+        return Ok(ScanOutcome::default());
+    }
+
+    if config.list_profiles {
+        print_available_profiles(&config)?;
+        return Ok(ScanOutcome::default());
     }
 
-    announce_workload(total_found, processing, &location);
+    if config.detect {
+        print_detected_browsers();
+        return Ok(ScanOutcome::default());
+    }
 
-    let failures = check_bookmarks(&bookmarks)?;
+    if config.count {
+        print_folder_counts(&config)?;
+        return Ok(ScanOutcome::default());
+    }
 
-    if failures.is_empty() {
-        println!("All bookmarks responded successfully.");
+    if config.check_mixed_content {
+        print_insecure_bookmarks(&config)?;
+        return Ok(ScanOutcome::default());
+    }
+
+    if let Some(report) = config.recheck.as_deref() {
+        return recheck_report(report, &config);
+    }
+
+    if config.plan {
+        print_scan_plan(&config)?;
+        return Ok(ScanOutcome::default());
+    }
+
+    if config.min_interval_secs.is_some() {
+        let state = load_state(&config)?;
+        ensure_interval_elapsed(&config, &state, Utc::now())?;
+    }
+    let options = check_options(&config);
+    ensure_network(&config, &options)?;
+    let outcome = if let Some(pattern) = config.profile_glob.as_deref() {
+        scan_matching_profiles(pattern, &config, &options, reporter)?
+    } else if config.profiles.len() > 1 {
+        let profiles = locator::locate_profiles(&config.profiles, user_data_dir(&config))?;
+        scan_profiles(profiles, &config, &options, reporter)?
     } else {
-        let reporter = FailureReporter::default();
-        reporter.write_report(&failures)?;
-        println!(
-            "Logged {} unreachable bookmarks to {}",
-            failures.len(),
-            reporter.output_path().display()
-        );
+        scan_single_profile(&config, &options, reporter)?
+    };
+
+    if config.compact {
+        println!("{}", compact_summary(&outcome));
     }
 
-    Ok(())
+    Ok(outcome)
 }
 
-fn clean_from_report(config: &RunConfig) -> Result<(), BookmarkError> {
-    let location = locator::locate_profile(config.profile.as_deref())?;
-    let report_path = Path::new(FAILURE_REPORT_FILE);
+/// Refuses to scan again within `--min-interval-between-runs` of the latest recorded
+/// scan, unless `--force` is given.
+fn ensure_interval_elapsed(
+    config: &RunConfig,
+    state: &RunState,
+    now: DateTime<Utc>,
+) -> Result<(), BookmarkError> {
+    let Some(interval) = config.min_interval_secs.filter(|_| !config.force) else {
+        return Ok(());
+    };
+
+    match state.latest_run() {
+        Some(last) if now.signed_duration_since(last).num_seconds() < interval as i64 => {
+            Err(BookmarkError::RunTooSoon(last, interval))
+        }
+        _ => Ok(()),
+    }
+}
 
-    if !report_path.exists() {
-        println!("No {} file found; nothing to clean.", report_path.display());
+/// Refuses to scan while offline, when every bookmark would fail with a connection
+/// error. Only runs when `preflight` is set, probing `--preflight-url` or else
+/// `PREFLIGHT_URLS` with the scan's own timeouts and headers.
+fn ensure_network(config: &RunConfig, options: &CheckOptions) -> Result<(), BookmarkError> {
+    if !config.preflight {
         return Ok(());
     }
 
-    let result = cleaner::clean_failures(&location, report_path)?;
-    let backup = result.backup_path.as_ref();
+    let probe = HttpProbe::new(&CheckOptions {
+        head_only: true,
+        ..options.clone()
+    })?;
+    let reachable = match config.preflight_url.as_deref() {
+        Some(url) => network_reachable(&probe, &[url]),
+        None => network_reachable(&probe, &PREFLIGHT_URLS),
+    };
+    if reachable {
+        Ok(())
+    } else {
+        Err(BookmarkError::NoNetwork)
+    }
+}
 
-    if result.removed > 0 {
-        if let Some(path) = backup {
-            println!(
-                "Backed up {} to {} and removed {} bookmark(s) listed in {}.",
-                location.file.display(),
-                path.display(),
-                result.removed,
-                report_path.display()
-            );
-        } else {
-            println!(
-                "Removed {} bookmark(s) listed in {}.",
-                result.removed,
-                report_path.display()
-            );
-        }
-    } else if let Some(path) = backup {
-        println!(
-            "No bookmarks in {} matched entries from {}. Backup saved to {}.",
-            location.file.display(),
-            report_path.display(),
-            path.display()
-        );
+fn network_reachable<P: UrlProbe>(probe: &P, urls: &[&str]) -> bool {
+    urls.iter()
+        .any(|url| matches!(probe.probe(url), ProbeOutcome::Response { .. }))
+}
+
+/// One line for status bars, e.g. "bookmarks: 1180 ok, 20 dead".
+fn compact_summary(outcome: &ScanOutcome) -> String {
+    format!(
+        "bookmarks: {} ok, {} dead",
+        outcome.checked.saturating_sub(outcome.failures),
+        outcome.failures
+    )
+}
+
+fn scan_single_profile(
+    config: &RunConfig,
+    options: &CheckOptions,
+    reporter: Option<&mut dyn Reporter>,
+) -> Result<ScanOutcome, BookmarkError> {
+    let (location, bookmarks) = load_profile(locate_single_profile(config)?, config)?;
+    let previous = previous_failures(config)?;
+    let stream = open_stream(config)?;
+    let mut state = load_state(config)?;
+    let started = Utc::now();
+    let mut outcome = ScanOutcome::default();
+
+    let report = report_path(config);
+    let results = scan_profile(
+        &location,
+        bookmarks,
+        config,
+        options,
+        stream.as_ref(),
+        &report,
+        None,
+        since_last_run(&state, &location, config),
+    )?;
+    let complete = is_complete(results.as_ref());
+    if let Some(results) = results {
+        write_failures(&results, config, reporter)?;
+        print_report_diff(previous.as_ref(), &results);
+        record_outcome(&mut outcome, results.checked.len(), &results.failures);
+    }
+
+    if complete {
+        record_runs(&mut state, [&location], started, config)?;
+    }
+    Ok(outcome)
+}
+
+/// The effective configuration as YAML, after defaults and environment overrides.
+fn render_config(config: &RunConfig) -> Result<String, BookmarkError> {
+    serde_yaml::to_string(config).map_err(BookmarkError::ConfigSerialization)
+}
+
+fn profiles_or_env_default(flags: Vec<String>, env_value: Option<String>) -> Vec<String> {
+    if !flags.is_empty() {
+        return flags;
+    }
+
+    env_value
+        .filter(|value| !value.trim().is_empty())
+        .into_iter()
+        .collect()
+}
+
+/// The profile for actions that work on one profile; the CLI rejects several.
+fn single_profile(config: &RunConfig) -> Option<&str> {
+    config.profiles.first().map(String::as_str)
+}
+
+/// The profile picked by `--profile-index` or `--profile-auto`, or else by name.
+/// A picked profile is used as found rather than looked up again by its
+/// directory name, which might read as the "default" alias.
+fn locate_single_profile(config: &RunConfig) -> Result<BookmarkLocation, BookmarkError> {
+    if let Some(index) = config.profile_index {
+        locator::locate_profile_at(index, config.include_system_profiles, user_data_dir(config))
+    } else if config.profile_auto {
+        locator::locate_most_recent_profile(config.include_system_profiles, user_data_dir(config))
     } else {
-        println!(
-            "{} contained no bookmark entries to clean; nothing removed.",
-            report_path.display()
-        );
+        locator::locate_profile(single_profile(config), user_data_dir(config))
     }
+}
 
-    Ok(())
+/// The `--user-data-dir` override, replacing the platform's Chrome directory.
+fn user_data_dir(config: &RunConfig) -> Option<&Path> {
+    config.user_data_dir.as_deref()
 }
 
-pub fn gather_bookmarks() -> Result<(BookmarkLocation, Vec<Bookmark>), BookmarkError> {
-    gather_bookmarks_for_profile(None)
+fn scan_matching_profiles(
+    pattern: &str,
+    config: &RunConfig,
+    options: &CheckOptions,
+    reporter: Option<&mut dyn Reporter>,
+) -> Result<ScanOutcome, BookmarkError> {
+    let profiles = locator::list_profiles_matching(
+        pattern,
+        config.include_system_profiles,
+        user_data_dir(config),
+    )?;
+
+    if profiles.is_empty() {
+        note!(config, "No Chrome profiles match '{pattern}'.");
+        return Ok(ScanOutcome::default());
+    }
+
+    scan_profiles(profiles, config, options, reporter)
 }
 
-pub fn gather_bookmarks_for_profile(
-    profile: Option<&str>,
-) -> Result<(BookmarkLocation, Vec<Bookmark>), BookmarkError> {
-    let location = locator::locate_profile(profile)?;
+/// Adds a scan that checked `checked` bookmarks, `failures` of which failed.
+fn record_outcome(outcome: &mut ScanOutcome, checked: usize, failures: &[LinkFailure]) {
+    outcome.checked += checked;
+    outcome.failures += failures.len();
+    for failure in failures {
+        outcome.failure_bits |= failure.kind.exit_bit();
+    }
+    outcome.failed_links.extend_from_slice(failures);
+}
 
-    ensure_location_exists(&location)?;
+/// Checks each profile in turn, writing a report per profile and one combined
+/// report, unless `--parallel-profiles` asks for concurrent scans with only a report
+/// each.
+fn scan_profiles(
+    profiles: Vec<BookmarkLocation>,
+    config: &RunConfig,
+    options: &CheckOptions,
+    reporter: Option<&mut dyn Reporter>,
+) -> Result<ScanOutcome, BookmarkError> {
+    if let Some(limit) = config.parallel_profiles {
+        return scan_profiles_in_parallel(profiles, limit, config, options, reporter);
+    }
 
-    let bookmarks = load_bookmarks_from(&location.file)?;
-    Ok((location, bookmarks))
+    let previous = previous_failures(config)?;
+    let stream = open_stream(config)?;
+    let mut state = load_state(config)?;
+    let started = Utc::now();
+    let mut combined = ScanResults {
+        suite: profiles
+            .iter()
+            .map(profile_name)
+            .collect::<Vec<_>>()
+            .join(", "),
+        ..ScanResults::default()
+    };
+    let mut checked_any = false;
+    let mut completed = Vec::new();
+    let consolidate = config.profile_report_stdout && reporter.is_none();
+    let mut consolidated = ConsolidatedReport::default();
+
+    let report = report_path(config);
+
+    for location in &profiles {
+        if config.fail_fast && !combined.failures.is_empty() {
+            break;
+        }
+
+        let (source, bookmarks) = load_profile(location.clone(), config)?;
+
+        let results = scan_profile(
+            &source,
+            bookmarks,
+            config,
+            options,
+            stream.as_ref(),
+            &report,
+            None,
+            since_last_run(&state, location, config),
+        )?;
+        if is_complete(results.as_ref()) {
+            completed.push(location);
+        }
+        if let Some(results) = results {
+            checked_any = true;
+            if consolidate {
+                add_to_consolidated(&mut consolidated, &results, config);
+            } else if reporter.is_none() {
+                write_profile_report(&results, config, location)?;
+            }
+            combined.checked.extend(results.checked);
+            combined.failures.extend(results.failures);
+            combined.warnings.extend(results.warnings);
+        }
+    }
+
+    if consolidate {
+        consolidated.write_to(io::stdout().lock())?;
+    } else if checked_any {
+        write_failures(&combined, config, reporter)?;
+        print_report_diff(previous.as_ref(), &combined);
+    }
+
+    record_runs(&mut state, completed, started, config)?;
+
+    let mut outcome = ScanOutcome::default();
+    record_outcome(&mut outcome, combined.checked.len(), &combined.failures);
+    Ok(outcome)
 }
 
-fn ensure_location_exists(location: &BookmarkLocation) -> Result<(), BookmarkError> {
-    if !location.directory.exists() {
-        return Err(BookmarkError::MissingBookmarksDir(
-            location.directory.clone(),
-        ));
+/// Checks up to `limit` profiles at once, writing a separate report for each. A
+/// custom reporter and the webhook get every profile's failures at once, after the
+/// last profile finishes.
+fn scan_profiles_in_parallel(
+    profiles: Vec<BookmarkLocation>,
+    limit: usize,
+    config: &RunConfig,
+    options: &CheckOptions,
+    reporter: Option<&mut dyn Reporter>,
+) -> Result<ScanOutcome, BookmarkError> {
+    let stream = open_stream(config)?;
+    let mut state = load_state(config)?;
+    let started = Utc::now();
+    let group = ProgressGroup::new();
+
+    let results = scan_concurrently(&profiles, limit, |location| {
+        let (source, bookmarks) = load_profile(location.clone(), config)?;
+        let report = profile_report_path(config, location);
+        scan_profile(
+            &source,
+            bookmarks,
+            config,
+            options,
+            stream.as_ref(),
+            &report,
+            Some(&group),
+            since_last_run(&state, location, config),
+        )
+    });
+
+    let consolidate = config.profile_report_stdout && reporter.is_none();
+    let mut consolidated = ConsolidatedReport::default();
+    let mut combined = ScanResults::default();
+    let mut checked_any = false;
+    let mut completed = Vec::new();
+    for (location, result) in profiles.iter().zip(results) {
+        let result = result?;
+        if is_complete(result.as_ref()) {
+            completed.push(location);
+        }
+        if let Some(results) = result {
+            checked_any = true;
+            if consolidate {
+                add_to_consolidated(&mut consolidated, &results, config);
+            } else if reporter.is_none() {
+                note!(config, "Profile {}:", results.suite);
+                let report = profile_report_path(config, location);
+                write_failures_to(&results, config, None, &report)?;
+            }
+            combined.checked.extend(results.checked);
+            combined.failures.extend(results.failures);
+            combined.warnings.extend(results.warnings);
+        }
     }
 
-    if !location.file.exists() {
-        return Err(BookmarkError::MissingBookmarksFile(location.file.clone()));
+    if consolidate {
+        consolidated.write_to(io::stdout().lock())?;
+    } else if checked_any {
+        #[cfg(feature = "webhook")]
+        notify_webhook(&combined.failures, config);
+
+        if let Some(reporter) = reporter {
+            write_failures_to(&combined, config, Some(reporter), &report_path(config))?;
+        }
     }
 
-    Ok(())
+    record_runs(&mut state, completed, started, config)?;
+    let mut outcome = ScanOutcome::default();
+    record_outcome(&mut outcome, combined.checked.len(), &combined.failures);
+    Ok(outcome)
 }
 
-fn load_bookmarks_from(path: &Path) -> Result<Vec<Bookmark>, BookmarkError> {
-    let contents = fs::read_to_string(path)?;
-    parser::parse_bookmarks(&contents).map_err(BookmarkError::from)
+/// Adds one profile's results to the `--profile-report-stdout` document, which
+/// replaces the report files unless a custom reporter takes the failures instead.
+fn add_to_consolidated(
+    consolidated: &mut ConsolidatedReport,
+    results: &ScanResults,
+    config: &RunConfig,
+) {
+    consolidated.add(
+        &results.suite,
+        &results.failures,
+        &results.warnings,
+        config.sort.unwrap_or_default(),
+        config.report_max_entries,
+    );
 }
 
-fn apply_limit(bookmarks: &mut Vec<Bookmark>, limit: Option<usize>) -> usize {
-    let total = bookmarks.len();
+/// Runs `scan` on up to `limit` threads, returning the results in input order.
+fn scan_concurrently<T, R, F>(items: &[T], limit: usize, scan: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
 
-    if let Some(max) = limit.filter(|&value| value < bookmarks.len()) {
-        bookmarks.truncate(max);
+    thread::scope(|scope| {
+        for _ in 0..limit.min(items.len()) {
+            scope.spawn(|| {
+                loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(idx) else {
+                        break;
+                    };
+                    let result = scan(item);
+                    results
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .push((idx, result));
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap_or_else(PoisonError::into_inner);
+    results.sort_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// What a scan checked and which of those bookmarks failed, ready to be reported.
+#[derive(Debug, Default)]
+struct ScanResults {
+    /// The scanned profile (or profiles), naming the JUnit test suite.
+    suite: String,
+    checked: Vec<Bookmark>,
+    failures: Vec<LinkFailure>,
+    /// Bookmarks that work but are worth a look; never counted as failures.
+    warnings: Vec<LinkWarning>,
+    /// Every bookmark left by the filters was checked: neither `--sample` nor
+    /// `--max-bookmarks` cut the list, and neither `--fail-fast` nor `--limit-time`
+    /// stopped the checks. Only such scans are recorded for `--since-last-run`.
+    complete: bool,
+}
+
+/// The bookmarks a scan will check once every filter and limit has been applied.
+#[derive(Debug)]
+struct ScanPlan {
+    bookmarks: Vec<Bookmark>,
+    /// How many were left before `--max-bookmarks` or `--sample` cut the list down.
+    total_found: usize,
+}
+
+/// Filters and checks one profile's bookmarks. Returns `None` when nothing was checked.
+///
+/// `report` is the earlier report read by `--resume`; with a `group`, progress is drawn
+/// as one bar in that shared display. With `since`, only bookmarks added after it are
+/// checked.
+#[allow(clippy::too_many_arguments)]
+fn scan_profile(
+    location: &BookmarkLocation,
+    bookmarks: Vec<Bookmark>,
+    config: &RunConfig,
+    options: &CheckOptions,
+    stream: Option<&ResultStream>,
+    report: &Path,
+    group: Option<&ProgressGroup>,
+    since: Option<DateTime<Utc>>,
+) -> Result<Option<ScanResults>, BookmarkError> {
+    let Some(ScanPlan {
+        bookmarks,
+        total_found,
+    }) = plan_scan(location, bookmarks, config, report, since)?
+    else {
+        return Ok(None);
+    };
+
+    announce_workload(config, total_found, bookmarks.len(), location);
+
+    let summary = match group {
+        Some(group) => {
+            check_bookmarks_in_group(&bookmarks, options, stream, group, &profile_name(location))?
+        }
+        None => check_bookmarks(&bookmarks, options, stream)?,
+    };
+    note!(
+        config,
+        "Downloaded {}",
+        format_bytes(summary.bytes_downloaded)
+    );
+    if summary.out_of_time {
+        note!(
+            config,
+            "Time limit reached (--limit-time); checked {} of {} bookmark(s).",
+            bookmarks.len() - summary.skipped.len(),
+            bookmarks.len()
+        );
+    } else if !summary.skipped.is_empty() {
+        note!(
+            config,
+            "Stopped at the first failure (--fail-fast); {} bookmark(s) were not checked.",
+            summary.skipped.len()
+        );
     }
 
-    total
+    Ok(Some(ScanResults {
+        suite: profile_name(location),
+        complete: bookmarks.len() == total_found && summary.skipped.is_empty(),
+        checked: without_skipped(bookmarks, &summary.skipped),
+        failures: summary.failures,
+        warnings: summary.warnings,
+    }))
 }
 
-fn announce_workload(total_found: usize, processing: usize, location: &BookmarkLocation) {
-    if processing == total_found {
-        println!(
-            "Checking {} bookmarks from {}",
-            processing,
+/// Drops the bookmarks at the `skipped` positions, which `--fail-fast` never checked.
+fn without_skipped(bookmarks: Vec<Bookmark>, skipped: &[usize]) -> Vec<Bookmark> {
+    let skipped: HashSet<usize> = skipped.iter().copied().collect();
+    bookmarks
+        .into_iter()
+        .enumerate()
+        .filter(|(position, _)| !skipped.contains(position))
+        .map(|(_, bookmark)| bookmark)
+        .collect()
+}
+
+/// Applies every `--scan` filter and limit to one profile's bookmarks without
+/// checking them. Returns `None`, after saying why, when nothing is left.
+fn plan_scan(
+    location: &BookmarkLocation,
+    mut bookmarks: Vec<Bookmark>,
+    config: &RunConfig,
+    report: &Path,
+    since: Option<DateTime<Utc>>,
+) -> Result<Option<ScanPlan>, BookmarkError> {
+    if bookmarks.is_empty() {
+        note!(config, "No bookmarks found in {}", location.file.display());
+        return Ok(None);
+    }
+
+    if let Some(since) = since {
+        bookmarks.retain(|bookmark| state::added_since(bookmark, since));
+        if bookmarks.is_empty() {
+            note!(
+                config,
+                "No bookmarks added to {} since the last run ({}).",
+                location.file.display(),
+                since.format("%Y-%m-%d %H:%M UTC")
+            );
+            return Ok(None);
+        }
+    }
+
+    let uncheckable = skip_uncheckable(&mut bookmarks);
+    if uncheckable > 0 {
+        note!(
+            config,
+            "Skipping {uncheckable} bookmark(s) that are not http(s) URLs (chrome://, javascript:, file://, ...)."
+        );
+    }
+
+    if bookmarks.is_empty() {
+        note!(
+            config,
+            "No http(s) bookmarks to check in {}.",
             location.file.display()
         );
-    } else {
-        println!(
-            "Checking {} of {} bookmarks from {}",
-            processing,
-            total_found,
+        return Ok(None);
+    }
+
+    let ignore = load_ignore_list(config)?;
+    let ignored = apply_ignore(&mut bookmarks, &ignore);
+    if ignored > 0 {
+        note!(
+            config,
+            "Skipping {ignored} bookmark(s) matched by the ignore file."
+        );
+    }
+
+    if bookmarks.is_empty() {
+        note!(
+            config,
+            "All bookmarks in {} are ignored.",
             location.file.display()
         );
+        return Ok(None);
+    }
+
+    if config.resume || config.recheck_failures {
+        if report.exists() {
+            let recorded = cleaner::load_report_targets(report)?;
+            filter_recorded(&mut bookmarks, &recorded, config.recheck_failures);
+        } else if config.recheck_failures {
+            note!(
+                config,
+                "No {} file found; nothing to recheck.",
+                report.display()
+            );
+            return Ok(None);
+        }
+
+        if bookmarks.is_empty() {
+            note!(
+                config,
+                "No bookmarks left to check after applying {}.",
+                report.display()
+            );
+            return Ok(None);
+        }
+    }
+
+    let seed = config.seed.unwrap_or_else(time_seed);
+    let total_found = match config.sample {
+        Some(count) => {
+            note!(config, "Sampling with seed {seed}");
+            sample::sample_bookmarks(&mut bookmarks, count, seed)
+        }
+        None => apply_limit(&mut bookmarks, config.max_bookmarks),
+    };
+
+    if config.shuffle {
+        if config.sample.is_none() {
+            note!(config, "Shuffling with seed {seed}");
+        }
+        sample::shuffle_bookmarks(&mut bookmarks, seed);
+    }
+
+    if bookmarks.is_empty() {
+        note!(
+            config,
+            "Bookmark limit of 0 prevents checking any entries ({total_found} total found)."
+        );
+        return Ok(None);
     }
+
+    Ok(Some(ScanPlan {
+        bookmarks,
+        total_found,
+    }))
 }
 
-fn print_available_profiles() -> Result<(), BookmarkError> {
-    let profiles = locator::list_profiles()?;
+/// The check settings for a run. Build them once per run: the `--limit-time`
+/// deadline starts counting here.
+fn check_options(config: &RunConfig) -> CheckOptions {
+    let defaults = CheckOptions::default();
 
-    if profiles.is_empty() {
-        println!("No Chrome profiles with bookmarks found.");
-    } else {
-        println!("Available Chrome profiles:");
-        for location in profiles {
-            let name = location
-                .directory
-                .file_name()
-                .map(|value| value.to_string_lossy().into_owned())
-                .unwrap_or_else(|| location.directory.display().to_string());
+    CheckOptions {
+        head_only: config.head_only,
+        head_first: config.head_first,
+        timeout: config
+            .timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(defaults.timeout),
+        connect_timeout: config.connect_timeout_secs.map(Duration::from_secs),
+        head_timeout: config.head_timeout_secs.map(Duration::from_secs),
+        concurrency: config.concurrency,
+        auto_tune: config.auto_tune,
+        check_parked: config.check_parked,
+        max_redirects: config.max_redirects.unwrap_or(defaults.max_redirects),
+        retries: config.retries.unwrap_or(defaults.retries),
+        retry_kinds: config.retry_kinds.clone().unwrap_or(defaults.retry_kinds),
+        retry_delay: defaults.retry_delay,
+        retry_jitter_seed: time_seed(),
+        headers: config.headers.clone(),
+        timeout_policy: config.timeout_policy.unwrap_or_default(),
+        pool_max_idle_per_host: config.pool_max_idle_per_host,
+        ssl_expiry_days: config.check_ssl_expiry_days,
+        suggest_updates: config.suggest_updates,
+        fail_fast: config.fail_fast,
+        deadline: config
+            .limit_time_secs
+            .map(|secs| Instant::now() + Duration::from_secs(secs)),
+        max_response_size: config.max_response_size,
+        if_modified_since: config.if_modified_since,
+        trace_redirects: config.trace_redirects,
+        show_progress: !config.compact,
+    }
+}
 
-            println!("- {name} ({})", location.file.display());
+fn open_stream(config: &RunConfig) -> Result<Option<ResultStream>, BookmarkError> {
+    config
+        .stream
+        .as_deref()
+        .map(ResultStream::create)
+        .transpose()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1000.0;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+fn describe_failure(failure: &LinkFailure, color: bool) -> String {
+    paint(
+        failure.kind,
+        format!(
+            "- {} ({}): {}",
+            failure.bookmark.name, failure.bookmark.url, failure.reason
+        ),
+        color,
+    )
+}
+
+fn print_failures_by_host(failures: &[LinkFailure], color: bool) {
+    for (host, entries) in group_by_host(failures) {
+        println!("{host} ({})", entries.len());
+        for failure in entries {
+            let line = format!("    {} ({})", failure.bookmark.url, failure.reason);
+            println!("{}", paint(failure.kind, line, color));
         }
     }
+}
 
-    Ok(())
+/// Whether failure listings on stdout should be colored.
+fn use_color(config: &RunConfig) -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    config
+        .color
+        .unwrap_or_default()
+        .enabled(no_color, io::stdout().is_terminal())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// ANSI foreground color for a failure kind, if it has one.
+fn kind_color(kind: FailureKind) -> Option<&'static str> {
+    match kind {
+        FailureKind::NotFound | FailureKind::Gone => Some("\x1b[31m"),
+        FailureKind::Unauthorized => Some("\x1b[33m"),
+        FailureKind::Connection => Some("\x1b[35m"),
+        _ => None,
+    }
+}
 
-    #[test]
-    fn limit_reduces_bookmarks_when_needed() {
-        let mut bookmarks = vec![
-            Bookmark {
-                name: "One".into(),
-                url: "https://one".into(),
-            },
-            Bookmark {
-                name: "Two".into(),
-                url: "https://two".into(),
-            },
-            Bookmark {
-                name: "Three".into(),
-                url: "https://three".into(),
-            },
-        ];
+fn paint(kind: FailureKind, line: String, color: bool) -> String {
+    match kind_color(kind) {
+        Some(code) if color => format!("{code}{line}\x1b[0m"),
+        _ => line,
+    }
+}
 
-        let total = apply_limit(&mut bookmarks, Some(2));
-        assert_eq!(total, 3);
-        assert_eq!(bookmarks.len(), 2);
+/// Groups failures by URL host, hosts with the most failures first (ties by name).
+/// URLs without a host are collected under `UNKNOWN_HOST`.
+fn group_by_host(failures: &[LinkFailure]) -> Vec<(String, Vec<&LinkFailure>)> {
+    let mut groups: BTreeMap<String, Vec<&LinkFailure>> = BTreeMap::new();
+    for failure in failures {
+        let host = failure
+            .bookmark
+            .host()
+            .unwrap_or_else(|| UNKNOWN_HOST.to_string());
+        groups.entry(host).or_default().push(failure);
     }
 
-    #[test]
-    fn limit_is_noop_when_higher_than_total() {
-        let mut bookmarks = vec![Bookmark {
-            name: "Only".into(),
-            url: "https://only".into(),
-        }];
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by_key(|(_, entries)| std::cmp::Reverse(entries.len()));
+    groups
+}
 
-        let total = apply_limit(&mut bookmarks, Some(10));
-        assert_eq!(total, 1);
+/// Hands a scan's failures to the webhook and the reporter, or else to the report.
+fn write_failures(
+    results: &ScanResults,
+    config: &RunConfig,
+    custom: Option<&mut dyn Reporter>,
+) -> Result<(), BookmarkError> {
+    #[cfg(feature = "webhook")]
+    notify_webhook(&results.failures, config);
+
+    write_failures_to(results, config, custom, &report_path(config))
+}
+
+fn write_failures_to(
+    results: &ScanResults,
+    config: &RunConfig,
+    custom: Option<&mut dyn Reporter>,
+    report: &Path,
+) -> Result<(), BookmarkError> {
+    let failures = results.failures.as_slice();
+    let warnings = results.warnings.as_slice();
+    if failures.is_empty() {
+        note!(config, "All bookmarks responded successfully.");
+        if warnings.is_empty() && !config.report_empty {
+            return Ok(());
+        }
+    }
+
+    if config.group_by_host {
+        print_failures_by_host(failures, use_color(config));
+    }
+
+    if let Some(reporter) = custom {
+        return reporter.report(failures);
+    }
+
+    if config.no_write {
+        let mut out: Box<dyn Write> = if config.streams_to_stdout() {
+            Box::new(io::stderr())
+        } else {
+            Box::new(io::stdout())
+        };
+        if !failures.is_empty() && !config.compact {
+            writeln!(out, "Unreachable bookmarks ({}):", failures.len())?;
+            let color = use_color(config);
+            for failure in failures {
+                writeln!(out, "{}", describe_failure(failure, color))?;
+            }
+        }
+        if !warnings.is_empty() && !config.compact {
+            writeln!(out, "Warnings ({}):", warnings.len())?;
+            for warning in warnings {
+                writeln!(
+                    out,
+                    "- {} ({}): {}",
+                    warning.bookmark.name, warning.bookmark.url, warning.reason
+                )?;
+            }
+        }
+        return Ok(());
+    }
+
+    let reporter = failure_reporter(config, &results.suite, report);
+    reporter.write_scan_report(&results.checked, failures, warnings)?;
+    if config.open_report {
+        open_report(reporter.output_path());
+    }
+
+    if failures.is_empty() && warnings.is_empty() {
+        note!(
+            config,
+            "Wrote an empty report to {}",
+            reporter.output_path().display()
+        );
+    } else if failures.is_empty() {
+        note!(
+            config,
+            "Logged {} warning(s) to {}",
+            warnings.len(),
+            reporter.output_path().display()
+        );
+    } else {
+        note!(
+            config,
+            "Logged {} unreachable bookmarks to {}",
+            failures.len(),
+            reporter.output_path().display()
+        );
+    }
+
+    Ok(())
+}
+
+/// The report file writer for one scan, set up from the run's report flags.
+fn failure_reporter(config: &RunConfig, suite: &str, report: &Path) -> FailureReporter {
+    FailureReporter::new(report)
+        .with_sort(config.sort.unwrap_or_default())
+        .with_format(config.format.unwrap_or_default())
+        .with_suite(suite)
+        .with_append(config.append || config.resume)
+        .with_max_entries(config.report_max_entries)
+}
+
+/// Writes one profile's own report during a sequential multi-profile scan, next to
+/// the combined report. Nothing is written under `--no-write`, or for a clean
+/// profile unless `--report-empty` asks for it.
+fn write_profile_report(
+    results: &ScanResults,
+    config: &RunConfig,
+    location: &BookmarkLocation,
+) -> Result<(), BookmarkError> {
+    let clean = results.failures.is_empty() && results.warnings.is_empty();
+    if config.no_write || (clean && !config.report_empty) {
+        return Ok(());
+    }
+
+    failure_reporter(
+        config,
+        &results.suite,
+        &profile_report_path(config, location),
+    )
+    .write_scan_report(&results.checked, &results.failures, &results.warnings)
+}
+
+/// Reads the `--diff` report before the scan can overwrite it.
+fn previous_failures(config: &RunConfig) -> Result<Option<PreviousFailures>, BookmarkError> {
+    config
+        .diff
+        .as_deref()
+        .map(diff::load_previous_failures)
+        .transpose()
+}
+
+fn print_report_diff(previous: Option<&PreviousFailures>, results: &ScanResults) {
+    let Some(previous) = previous else {
+        return;
+    };
+    let diff = diff::diff_failures(previous, &results.checked, &results.failures);
+
+    for (heading, urls) in [
+        ("Newly broken", &diff.newly_broken),
+        ("Fixed", &diff.fixed),
+        ("Still broken", &diff.still_broken),
+    ] {
+        println!("{heading} ({}):", urls.len());
+        for url in urls {
+            println!("- {url}");
+        }
+    }
+}
+
+/// Outcome of re-checking the entries of an earlier report.
+#[derive(Debug, Default)]
+struct Recheck {
+    healthy: Vec<Bookmark>,
+    still_broken: Vec<LinkFailure>,
+}
+
+fn recheck_report(report_path: &Path, config: &RunConfig) -> Result<ScanOutcome, BookmarkError> {
+    let bookmarks = cleaner::load_report_bookmarks(report_path)?;
+
+    if bookmarks.is_empty() {
+        println!("{} lists no bookmarks to recheck.", report_path.display());
+        return Ok(ScanOutcome::default());
+    }
+
+    println!(
+        "Rechecking {} bookmark(s) from {}",
+        bookmarks.len(),
+        report_path.display()
+    );
+
+    let options = check_options(config);
+    let probe = HttpProbe::new(&options)?;
+    let recheck = recheck_bookmarks(bookmarks, &probe, &options);
+
+    if !recheck.healthy.is_empty() {
+        println!("Now healthy ({}):", recheck.healthy.len());
+        for bookmark in &recheck.healthy {
+            println!("- {} ({})", bookmark.name, bookmark.url);
+        }
+    }
+
+    if !recheck.still_broken.is_empty() {
+        println!("Still broken ({}):", recheck.still_broken.len());
+        let color = use_color(config);
+        for failure in &recheck.still_broken {
+            println!("{}", describe_failure(failure, color));
+        }
+    }
+
+    if config.update_report {
+        FailureReporter::new(report_path)
+            .with_sort(config.sort.unwrap_or_default())
+            .write_report(&recheck.still_broken)?;
+        println!("Updated {}", report_path.display());
+    }
+
+    let mut outcome = ScanOutcome::default();
+    record_outcome(
+        &mut outcome,
+        recheck.healthy.len() + recheck.still_broken.len(),
+        &recheck.still_broken,
+    );
+    Ok(outcome)
+}
+
+fn recheck_bookmarks<P: UrlProbe + Sync>(
+    bookmarks: Vec<Bookmark>,
+    probe: &P,
+    options: &CheckOptions,
+) -> Recheck {
+    let summary = check_bookmarks_with(&bookmarks, probe, options, None);
+    let broken: HashSet<&str> = summary
+        .failures
+        .iter()
+        .map(|failure| failure.bookmark.url.as_str())
+        .collect();
+    let healthy = bookmarks
+        .iter()
+        .filter(|bookmark| !broken.contains(bookmark.url.as_str()))
+        .cloned()
+        .collect();
+
+    Recheck {
+        healthy,
+        still_broken: summary.failures,
+    }
+}
+
+/// A failed notification must not cost the user the report, so it only warns.
+#[cfg(feature = "webhook")]
+fn notify_webhook(failures: &[LinkFailure], config: &RunConfig) {
+    let Some(url) = config.webhook.as_deref() else {
+        return;
+    };
+
+    if failures.is_empty() {
+        return;
+    }
+
+    let delivered =
+        crate::webhook::WebhookReporter::new(url).and_then(|mut webhook| webhook.report(failures));
+    if let Err(err) = delivered {
+        eprintln!("Warning: {err}");
+    }
+}
+
+/// Opens a written report for `--open-report`, only for someone watching a terminal.
+/// A failed launch is a warning; the report is already on disk.
+fn open_report(path: &Path) {
+    if !io::stdout().is_terminal() {
+        return;
+    }
+
+    if let Err(err) = opener::open(path) {
+        eprintln!("Warning: {err}");
+    }
+}
+
+fn clean_from_report(config: &RunConfig) -> Result<(), BookmarkError> {
+    let location = locate_single_profile(config)?;
+    let report_path = report_path(config);
+    let source = if config.clean_stdin {
+        "stdin".to_string()
+    } else {
+        report_path.display().to_string()
+    };
+
+    if !config.clean_stdin && !report_path.exists() {
+        println!("No {source} file found; nothing to clean.");
+        return Ok(());
+    }
+
+    warn_if_browser_running(&location);
+
+    let options = CleanOptions {
+        ignore: load_ignore_list(config)?,
+        backup_dir: config.output_dir.clone(),
+        include_transient: config.clean_all,
+        prune_empty_folders: config.prune_empty_folders,
+    };
+    let result = if config.clean_stdin {
+        let urls = cleaner::read_url_list(io::stdin().lock())?;
+        cleaner::remove_urls(&location, urls, &options)?
+    } else {
+        cleaner::clean_failures(&location, &report_path, &options)?
+    };
+    let backup = result.backup_path.as_ref();
+
+    if result.removed > 0 {
+        if let Some(path) = backup {
+            println!(
+                "Backed up {} to {} and removed {} bookmark(s) listed in {source}.",
+                location.file.display(),
+                path.display(),
+                result.removed,
+            );
+        } else {
+            println!("Removed {} bookmark(s) listed in {source}.", result.removed,);
+        }
+        if result.pruned_folders > 0 {
+            println!("Removed {} folder(s) left empty.", result.pruned_folders);
+        }
+    } else {
+        println!(
+            "No bookmarks in {} matched entries from {source}; nothing removed and no backup needed.",
+            location.file.display(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Warns that a running browser may overwrite the Bookmarks file about to change.
+fn warn_if_browser_running(location: &BookmarkLocation) {
+    if locator::chrome_appears_running(location) {
+        eprintln!(
+            "Warning: Chrome appears to be running. Close it first, or it may overwrite {} \
+             with its in-memory bookmarks.",
+            location.file.display()
+        );
+    }
+}
+
+fn strip_tracking_params(config: &RunConfig) -> Result<(), BookmarkError> {
+    let location = locate_single_profile(config)?;
+
+    warn_if_browser_running(&location);
+
+    let result = cleaner::strip_tracking(&location, config.output_dir.as_deref())?;
+    match result.backup_path {
+        Some(backup) => println!(
+            "Backed up {} to {} and stripped tracking parameters from {} bookmark(s).",
+            location.file.display(),
+            backup.display(),
+            result.rewritten
+        ),
+        None => println!(
+            "No bookmarks in {} carry tracking parameters; nothing changed.",
+            location.file.display()
+        ),
+    }
+
+    Ok(())
+}
+
+fn normalize_bookmark_names(config: &RunConfig) -> Result<(), BookmarkError> {
+    let location = locate_single_profile(config)?;
+
+    warn_if_browser_running(&location);
+
+    let result = cleaner::normalize_names(&location, config.output_dir.as_deref())?;
+    match result.backup_path {
+        Some(backup) => println!(
+            "Backed up {} to {} and normalized {} bookmark name(s).",
+            location.file.display(),
+            backup.display(),
+            result.rewritten
+        ),
+        None => println!(
+            "Every bookmark name in {} is already trimmed; nothing changed.",
+            location.file.display()
+        ),
+    }
+
+    Ok(())
+}
+
+fn remove_duplicates(config: &RunConfig) -> Result<(), BookmarkError> {
+    let location = locate_single_profile(config)?;
+
+    warn_if_browser_running(&location);
+
+    let result = cleaner::remove_duplicates(&location, config.output_dir.as_deref())?;
+    match result.backup_path {
+        Some(backup) => println!(
+            "Backed up {} to {} and removed {} duplicate bookmark(s).",
+            location.file.display(),
+            backup.display(),
+            result.removed
+        ),
+        None => println!(
+            "No duplicate bookmarks in {}; nothing changed.",
+            location.file.display()
+        ),
+    }
+
+    Ok(())
+}
+
+pub fn gather_bookmarks() -> Result<(BookmarkLocation, Vec<Bookmark>), BookmarkError> {
+    gather_bookmarks_for_profile(None)
+}
+
+pub fn gather_bookmarks_for_profile(
+    profile: Option<&str>,
+) -> Result<(BookmarkLocation, Vec<Bookmark>), BookmarkError> {
+    gather_profile_bookmarks(profile, None, parser::DEFAULT_MAX_DEPTH, RootScope::All)
+}
+
+fn gather_profile_bookmarks(
+    profile: Option<&str>,
+    user_data_dir: Option<&Path>,
+    max_depth: usize,
+    scope: RootScope,
+) -> Result<(BookmarkLocation, Vec<Bookmark>), BookmarkError> {
+    let location = locator::locate_profile(profile, user_data_dir)?;
+
+    ensure_location_exists(&location)?;
+
+    let bookmarks = load_bookmarks_from(&location.file, max_depth, scope, None)?;
+    Ok((location, bookmarks))
+}
+
+/// Loads a profile's bookmarks with the run's depth and scope, from Chrome's
+/// backup file under `--use-chrome-backup`. Returns the location actually read.
+fn load_profile(
+    location: BookmarkLocation,
+    config: &RunConfig,
+) -> Result<(BookmarkLocation, Vec<Bookmark>), BookmarkError> {
+    let location = source_location(location, config)?;
+    ensure_location_exists(&location)?;
+
+    let bookmarks = load_bookmarks_from(
+        &location.file,
+        max_depth(config),
+        root_scope(config),
+        config.input_format,
+    )?;
+    Ok((location, bookmarks))
+}
+
+/// The file to read for `location`: Chrome's `Bookmarks.bak` under
+/// `--use-chrome-backup`, which must then exist, or the live file otherwise.
+fn source_location(
+    location: BookmarkLocation,
+    config: &RunConfig,
+) -> Result<BookmarkLocation, BookmarkError> {
+    if !config.use_chrome_backup {
+        return Ok(location);
+    }
+
+    let backup = locator::chrome_backup(&location);
+    if location.directory.exists() && !backup.file.exists() {
+        return Err(BookmarkError::MissingChromeBackup(backup.file));
+    }
+    Ok(backup)
+}
+
+fn max_depth(config: &RunConfig) -> usize {
+    config.max_depth.unwrap_or(parser::DEFAULT_MAX_DEPTH)
+}
+
+fn root_scope(config: &RunConfig) -> RootScope {
+    if config.bar_only {
+        RootScope::BookmarkBar
+    } else {
+        RootScope::All
+    }
+}
+
+fn ensure_location_exists(location: &BookmarkLocation) -> Result<(), BookmarkError> {
+    if !location.directory.exists() {
+        return Err(BookmarkError::MissingBookmarksDir(
+            location.directory.clone(),
+        ));
+    }
+
+    if !location.file.exists() {
+        return Err(BookmarkError::MissingBookmarksFile(location.file.clone()));
+    }
+
+    Ok(())
+}
+
+/// Reads `path` as `format`, or as whatever its content looks like when `None`.
+///
+/// A running browser may be rewriting the file, so a read that finds it empty or cut
+/// off is tried once more after `REREAD_DELAY`.
+fn load_bookmarks_from(
+    path: &Path,
+    max_depth: usize,
+    scope: RootScope,
+    format: Option<InputFormat>,
+) -> Result<Vec<Bookmark>, BookmarkError> {
+    match read_bookmarks_file(path, max_depth, scope, format) {
+        Err(BookmarkError::TruncatedBookmarksFile(..) | BookmarkError::BlankBookmarksFile(_)) => {
+            thread::sleep(REREAD_DELAY);
+            read_bookmarks_file(path, max_depth, scope, format)
+        }
+        result => result,
+    }
+}
+
+fn read_bookmarks_file(
+    path: &Path,
+    max_depth: usize,
+    scope: RootScope,
+    format: Option<InputFormat>,
+) -> Result<Vec<Bookmark>, BookmarkError> {
+    let contents = fs::read_to_string(path)?;
+    // Exports edited on Windows may start with a UTF-8 byte order mark, which JSON forbids.
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(&contents);
+    if contents.trim().is_empty() {
+        return Err(BookmarkError::BlankBookmarksFile(path.to_path_buf()));
+    }
+    match format.or_else(|| parser::detect_format(contents)) {
+        Some(InputFormat::Json) => {}
+        Some(InputFormat::Html) => {
+            return Err(BookmarkError::HtmlBookmarksExport(path.to_path_buf()));
+        }
+        None => return Err(BookmarkError::UnrecognizedBookmarksFile(path.to_path_buf())),
+    }
+
+    let parsed = parser::parse_bookmarks(contents, max_depth, scope).map_err(|err| {
+        if err.is_eof() {
+            BookmarkError::TruncatedBookmarksFile(path.to_path_buf(), err)
+        } else {
+            let snippet = parser::error_snippet(contents, &err);
+            BookmarkError::MalformedBookmarksFile(path.to_path_buf(), err, snippet)
+        }
+    })?;
+
+    if parsed.skipped_folders > 0 {
+        eprintln!(
+            "Warning: skipped {} folder(s) in {} nested more than {max_depth} levels deep.",
+            parsed.skipped_folders,
+            path.display()
+        );
+    }
+
+    Ok(parsed.bookmarks)
+}
+
+fn report_path(config: &RunConfig) -> PathBuf {
+    match config.output_dir.as_deref() {
+        Some(dir) => dir.join(FAILURE_REPORT_FILE),
+        None => PathBuf::from(FAILURE_REPORT_FILE),
+    }
+}
+
+fn state_path(config: &RunConfig) -> PathBuf {
+    match config.output_dir.as_deref() {
+        Some(dir) => dir.join(STATE_FILE),
+        None => PathBuf::from(STATE_FILE),
+    }
+}
+
+/// When `location` was last scanned, if `--since-last-run` asks to start from there.
+fn since_last_run(
+    state: &RunState,
+    location: &BookmarkLocation,
+    config: &RunConfig,
+) -> Option<DateTime<Utc>> {
+    if !config.since_last_run {
+        return None;
+    }
+
+    let since = state.last_run(&location.directory);
+    if since.is_none() {
+        note!(
+            config,
+            "No earlier run recorded for {}; checking every bookmark.",
+            location.directory.display()
+        );
+    }
+    since
+}
+
+/// Whether a profile's scan left nothing unchecked; a profile with nothing to check
+/// counts as complete.
+fn is_complete(results: Option<&ScanResults>) -> bool {
+    results.is_none_or(|results| results.complete)
+}
+
+/// Reads the run state. A malformed state file only loses the remembered scan
+/// times, so it is a warning and the run carries on without them.
+fn load_state(config: &RunConfig) -> Result<RunState, BookmarkError> {
+    match RunState::load(&state_path(config)) {
+        Err(err @ BookmarkError::StateFile(_)) => {
+            eprintln!("Warning: {err}");
+            Ok(RunState::default())
+        }
+        loaded => loaded,
+    }
+}
+
+/// Remembers that `locations` were scanned from `started`, unless `--no-write` is set.
+fn record_runs<'a>(
+    state: &mut RunState,
+    locations: impl IntoIterator<Item = &'a BookmarkLocation>,
+    started: DateTime<Utc>,
+    config: &RunConfig,
+) -> Result<(), BookmarkError> {
+    if config.no_write {
+        return Ok(());
+    }
+
+    for location in locations {
+        state.record(&location.directory, started);
+    }
+    state.save(&state_path(config))
+}
+
+/// `<prefix>-<profile>.<ext>` next to the combined report. The prefix defaults to the
+/// combined report's name.
+fn profile_report_path(config: &RunConfig, location: &BookmarkLocation) -> PathBuf {
+    let combined = report_path(config);
+    let stem = combined.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = combined.extension().unwrap_or_default().to_string_lossy();
+    let prefix = config.report_prefix.as_deref().unwrap_or(&stem);
+    combined.with_file_name(format!(
+        "{prefix}-{}.{extension}",
+        file_name_token(&profile_name(location))
+    ))
+}
+
+/// Replaces spaces, path separators and other characters that are awkward in file
+/// names with `_`.
+fn file_name_token(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn profile_name(location: &BookmarkLocation) -> String {
+    location
+        .directory
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| location.directory.display().to_string())
+}
+
+fn load_ignore_list(config: &RunConfig) -> Result<IgnoreList, BookmarkError> {
+    match config.ignore_file.as_deref() {
+        Some(path) => IgnoreList::load(path),
+        None => {
+            let default_path = Path::new(IGNORE_FILE);
+            if default_path.exists() {
+                IgnoreList::load(default_path)
+            } else {
+                Ok(IgnoreList::default())
+            }
+        }
+    }
+}
+
+fn apply_ignore(bookmarks: &mut Vec<Bookmark>, ignore: &IgnoreList) -> usize {
+    if ignore.is_empty() {
+        return 0;
+    }
+
+    let before = bookmarks.len();
+    bookmarks.retain(|bookmark| !ignore.is_ignored(&bookmark.url));
+    before - bookmarks.len()
+}
+
+fn skip_uncheckable(bookmarks: &mut Vec<Bookmark>) -> usize {
+    let before = bookmarks.len();
+    bookmarks.retain(Bookmark::is_checkable);
+    before - bookmarks.len()
+}
+
+fn filter_recorded(bookmarks: &mut Vec<Bookmark>, recorded: &HashSet<String>, keep_recorded: bool) {
+    bookmarks.retain(|bookmark| recorded.contains(&bookmark.url) == keep_recorded);
+}
+
+fn apply_limit(bookmarks: &mut Vec<Bookmark>, limit: Option<usize>) -> usize {
+    let total = bookmarks.len();
+
+    if let Some(max) = limit.filter(|&value| value < bookmarks.len()) {
+        bookmarks.truncate(max);
+    }
+
+    total
+}
+
+fn time_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+fn announce_workload(
+    config: &RunConfig,
+    total_found: usize,
+    processing: usize,
+    location: &BookmarkLocation,
+) {
+    if processing == total_found {
+        note!(
+            config,
+            "Checking {} bookmarks from {}",
+            processing,
+            location.file.display()
+        );
+    } else {
+        note!(
+            config,
+            "Checking {} of {} bookmarks from {}",
+            processing,
+            total_found,
+            location.file.display()
+        );
+    }
+}
+
+/// Prints the bookmarks `--scan` would check in each profile, without checking them.
+fn print_scan_plan(config: &RunConfig) -> Result<(), BookmarkError> {
+    let profiles = match config.profile_glob.as_deref() {
+        Some(pattern) => locator::list_profiles_matching(
+            pattern,
+            config.include_system_profiles,
+            user_data_dir(config),
+        )?,
+        None if config.profiles.len() > 1 => {
+            locator::locate_profiles(&config.profiles, user_data_dir(config))?
+        }
+        None => vec![locate_single_profile(config)?],
+    };
+    let state = load_state(config)?;
+
+    let mut planned = 0;
+    for location in &profiles {
+        let (source, bookmarks) = load_profile(location.clone(), config)?;
+        let report = match config.parallel_profiles {
+            Some(_) => profile_report_path(config, location),
+            None => report_path(config),
+        };
+        let Some(plan) = plan_scan(
+            &source,
+            bookmarks,
+            config,
+            &report,
+            since_last_run(&state, location, config),
+        )?
+        else {
+            continue;
+        };
+
+        planned += plan.bookmarks.len();
+        if config.compact {
+            continue;
+        }
+        println!(
+            "Would check {} of {} bookmarks from {}:",
+            plan.bookmarks.len(),
+            plan.total_found,
+            location.file.display()
+        );
+        for bookmark in &plan.bookmarks {
+            println!("- {} ({})", bookmark.name, bookmark.url);
+        }
+    }
+
+    if config.compact {
+        println!("bookmarks: {planned} planned");
+    }
+
+    Ok(())
+}
+
+/// Parses the profile's bookmarks without any network access.
+fn validate_bookmarks(config: &RunConfig) -> Result<(), BookmarkError> {
+    let location = source_location(locate_single_profile(config)?, config)?;
+    ensure_location_exists(&location)?;
+
+    let count = count_valid_bookmarks(&location.file, max_depth(config), config.input_format)?;
+    println!("{} is valid: {count} bookmark(s).", location.file.display());
+
+    Ok(())
+}
+
+fn count_valid_bookmarks(
+    path: &Path,
+    max_depth: usize,
+    format: Option<InputFormat>,
+) -> Result<usize, BookmarkError> {
+    let bookmarks = load_bookmarks_from(path, max_depth, RootScope::All, format)?;
+
+    if bookmarks.is_empty() {
+        return Err(BookmarkError::EmptyBookmarks(path.to_path_buf()));
+    }
+
+    Ok(bookmarks.len())
+}
+
+fn print_folder_counts(config: &RunConfig) -> Result<(), BookmarkError> {
+    let (location, bookmarks) = load_profile(locate_single_profile(config)?, config)?;
+    let counts = stats::count_by_top_level_folder(&bookmarks);
+
+    if counts.total == 0 {
+        println!("No bookmarks found in {}", location.file.display());
+        return Ok(());
+    }
+
+    println!("Bookmarks per folder in {}:", location.file.display());
+    for (folder, count) in &counts.folders {
+        println!("- {folder}: {count}");
+    }
+    println!("Total: {}", counts.total);
+
+    Ok(())
+}
+
+fn print_backups(config: &RunConfig) -> Result<(), BookmarkError> {
+    let location = locate_single_profile(config)?;
+    let dir = config
+        .output_dir
+        .as_deref()
+        .unwrap_or(location.directory.as_path());
+    let backups = backup::list_backups(&location.file, dir)?;
+
+    if backups.is_empty() {
+        println!(
+            "No backups of {} found in {}.",
+            location.file.display(),
+            dir.display()
+        );
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    println!("Backups of {}, newest first:", location.file.display());
+    for entry in backups {
+        println!(
+            "- {} ({}, {})",
+            entry.path.display(),
+            entry.created.format("%Y-%m-%d %H:%M:%S UTC"),
+            backup::format_age(entry.created, now)
+        );
+    }
+
+    Ok(())
+}
+
+fn print_insecure_bookmarks(config: &RunConfig) -> Result<(), BookmarkError> {
+    let (location, bookmarks) = load_profile(locate_single_profile(config)?, config)?;
+    let mut insecure = mixed_content::find_insecure(&bookmarks);
+
+    if insecure.is_empty() {
+        println!(
+            "Every bookmark in {} uses https:// or another scheme.",
+            location.file.display()
+        );
+        return Ok(());
+    }
+
+    if config.suggest_https {
+        let probe = HttpProbe::new(&check_options(config))?;
+        mixed_content::probe_https(&mut insecure, &probe);
+    }
+
+    println!(
+        "{} bookmark(s) in {} use plain http://:",
+        insecure.len(),
+        location.file.display()
+    );
+    for entry in &insecure {
+        let suggestion = match entry.https_available {
+            Some(true) => " - https:// available, safe to upgrade",
+            Some(false) => " - no https:// response",
+            None => "",
+        };
+        println!(
+            "- {} ({}){suggestion}",
+            entry.bookmark.name, entry.bookmark.url
+        );
+    }
+
+    Ok(())
+}
+
+fn print_detected_browsers() {
+    let browsers = locator::detect_browsers();
+
+    if browsers.is_empty() {
+        println!("No supported browsers found (looked for Google Chrome and Microsoft Edge).");
+    } else {
+        println!("Supported browsers found:");
+        for browser in browsers {
+            println!("- {browser}");
+        }
+    }
+}
+
+fn print_available_profiles(config: &RunConfig) -> Result<(), BookmarkError> {
+    let profiles = locator::list_profiles(config.include_system_profiles, user_data_dir(config))?;
+
+    if profiles.is_empty() {
+        println!("No Chrome profiles with bookmarks found.");
+    } else {
+        println!("Available Chrome profiles:");
+        for location in profiles {
+            let name = location
+                .directory
+                .file_name()
+                .map(|value| value.to_string_lossy().into_owned())
+                .unwrap_or_else(|| location.directory.display().to_string());
+
+            println!("- {name} ({})", location.file.display());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::{FailureKind, FailureReason, ProbeOutcome};
+    use crate::test_support::temp_path;
+    use reqwest::StatusCode;
+
+    /// A `Reporter` that keeps the URLs of each batch of failures it is handed.
+    #[derive(Default)]
+    struct InMemory(Vec<Vec<String>>);
+
+    impl Reporter for InMemory {
+        fn report(&mut self, failures: &[LinkFailure]) -> Result<(), BookmarkError> {
+            self.0.push(
+                failures
+                    .iter()
+                    .map(|failure| failure.bookmark.url.clone())
+                    .collect(),
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn limit_reduces_bookmarks_when_needed() {
+        let mut bookmarks = vec![
+            Bookmark {
+                name: "One".into(),
+                url: "https://one".into(),
+                folder_path: Vec::new(),
+                date_added: None,
+            },
+            Bookmark {
+                name: "Two".into(),
+                url: "https://two".into(),
+                folder_path: Vec::new(),
+                date_added: None,
+            },
+            Bookmark {
+                name: "Three".into(),
+                url: "https://three".into(),
+                folder_path: Vec::new(),
+                date_added: None,
+            },
+        ];
+
+        let total = apply_limit(&mut bookmarks, Some(2));
+        assert_eq!(total, 3);
+        assert_eq!(bookmarks.len(), 2);
+    }
+
+    #[test]
+    fn limit_is_noop_when_higher_than_total() {
+        let mut bookmarks = vec![Bookmark {
+            name: "Only".into(),
+            url: "https://only".into(),
+            folder_path: Vec::new(),
+            date_added: None,
+        }];
+
+        let total = apply_limit(&mut bookmarks, Some(10));
+        assert_eq!(total, 1);
         assert_eq!(bookmarks.len(), 1);
     }
+
+    #[test]
+    fn profile_env_var_is_a_fallback_for_the_flag() {
+        assert_eq!(
+            profiles_or_env_default(Vec::new(), Some("Work".into())),
+            vec!["Work".to_string()]
+        );
+        assert_eq!(
+            profiles_or_env_default(vec!["Personal".into()], Some("Work".into())),
+            vec!["Personal".to_string()]
+        );
+        assert!(profiles_or_env_default(Vec::new(), Some(" ".into())).is_empty());
+        assert!(profiles_or_env_default(Vec::new(), None).is_empty());
+    }
+
+    #[test]
+    fn printed_config_reflects_merged_overrides() {
+        let config = RunConfig {
+            scan: true,
+            timeout_secs: Some(30),
+            profiles: profiles_or_env_default(Vec::new(), Some("Work".into())),
+            ..RunConfig::default()
+        };
+
+        let yaml = render_config(&config).expect("render");
+        let printed: serde_yaml::Value = serde_yaml::from_str(&yaml).expect("yaml");
+
+        assert_eq!(printed["scan"], serde_yaml::Value::Bool(true));
+        assert_eq!(printed["timeout_secs"], serde_yaml::Value::from(30));
+        assert_eq!(printed["profiles"][0], serde_yaml::Value::from("Work"));
+        assert_eq!(printed["concurrency"], serde_yaml::Value::from(0));
+        assert!(printed.get("print_config").is_none());
+    }
+
+    #[test]
+    fn compact_summary_is_a_single_line() {
+        let outcome = ScanOutcome {
+            checked: 1200,
+            failures: 20,
+            ..ScanOutcome::default()
+        };
+        assert_eq!(compact_summary(&outcome), "bookmarks: 1180 ok, 20 dead");
+        assert_eq!(
+            compact_summary(&ScanOutcome::default()),
+            "bookmarks: 0 ok, 0 dead"
+        );
+    }
+
+    #[test]
+    fn coded_exit_status_sets_a_bit_per_failure_category() {
+        let failures = |kinds: &[FailureKind]| {
+            let failures: Vec<LinkFailure> = kinds
+                .iter()
+                .map(|&kind| LinkFailure {
+                    bookmark: sample_bookmarks().remove(0),
+                    reason: FailureReason::Other("failed".into()),
+                    kind,
+                    redirect_chain: Vec::new(),
+                })
+                .collect();
+            let mut outcome = ScanOutcome::default();
+            record_outcome(&mut outcome, failures.len(), &failures);
+            outcome.coded_exit_status()
+        };
+
+        assert_eq!(failures(&[]), 0);
+        assert_eq!(failures(&[FailureKind::NotFound, FailureKind::Gone]), 4);
+        assert_eq!(failures(&[FailureKind::Unauthorized]), 8);
+        assert_eq!(
+            failures(&[
+                FailureKind::NotFound,
+                FailureKind::Connection,
+                FailureKind::Connection
+            ]),
+            20
+        );
+        assert_eq!(
+            failures(&[
+                FailureKind::Gone,
+                FailureKind::Unauthorized,
+                FailureKind::Connection,
+                FailureKind::Parked
+            ]),
+            60
+        );
+    }
+
+    #[test]
+    fn concurrent_scans_keep_input_order() {
+        let delays = [30, 0, 10, 0];
+        let results = scan_concurrently(&delays, 2, |&delay| {
+            thread::sleep(Duration::from_millis(delay));
+            delay
+        });
+        assert_eq!(results, delays);
+    }
+
+    #[test]
+    fn parallel_profiles_get_a_report_each() {
+        let dir = temp_path("bookmark-checker-parallel");
+        let profiles = unreachable_profiles(&dir);
+        let config = RunConfig {
+            scan: true,
+            output_dir: Some(dir.join("reports")),
+            parallel_profiles: Some(2),
+            ..RunConfig::default()
+        };
+
+        let outcome =
+            scan_profiles(profiles.clone(), &config, &check_options(&config), None).expect("scan");
+
+        assert_eq!(outcome.failures, 2);
+        for location in &profiles {
+            let report = fs::read_to_string(profile_report_path(&config, location)).unwrap();
+            assert!(report.contains(&profile_name(location)));
+        }
+        assert!(!dir.join("reports").join(FAILURE_REPORT_FILE).exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn parallel_profiles_report_to_a_custom_reporter_once() {
+        let dir = temp_path("bookmark-checker-batches");
+        let config = RunConfig {
+            scan: true,
+            compact: true,
+            output_dir: Some(dir.join("reports")),
+            parallel_profiles: Some(2),
+            ..RunConfig::default()
+        };
+
+        let mut reporter = InMemory::default();
+        scan_profiles(
+            unreachable_profiles(&dir),
+            &config,
+            &check_options(&config),
+            Some(&mut reporter),
+        )
+        .expect("scan");
+
+        assert_eq!(reporter.0.len(), 1);
+        assert_eq!(reporter.0[0].len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn sequential_profiles_get_a_report_each_besides_the_combined_one() {
+        let dir = temp_path("bookmark-checker-sequential");
+        let profiles = unreachable_profiles(&dir);
+        let config = RunConfig {
+            scan: true,
+            compact: true,
+            output_dir: Some(dir.join("reports")),
+            ..RunConfig::default()
+        };
+
+        let outcome =
+            scan_profiles(profiles.clone(), &config, &check_options(&config), None).expect("scan");
+
+        assert_eq!(outcome.failures, 2);
+        assert_eq!(outcome.failed_links.len(), 2);
+        for location in &profiles {
+            let report = fs::read_to_string(profile_report_path(&config, location)).unwrap();
+            assert!(report.contains(&format!("name: {}", profile_name(location))));
+            assert_eq!(report.matches("- name:").count(), 1);
+        }
+        let combined = fs::read_to_string(dir.join("reports").join(FAILURE_REPORT_FILE)).unwrap();
+        assert_eq!(combined.matches("- name:").count(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn profile_report_stdout_leaves_failures_to_a_custom_reporter() {
+        let dir = temp_path("bookmark-checker-stdout");
+        let profiles = unreachable_profiles(&dir);
+        let config = RunConfig {
+            scan: true,
+            output_dir: Some(dir.join("reports")),
+            profile_report_stdout: true,
+            ..RunConfig::default()
+        };
+        let options = check_options(&config);
+
+        let mut reporter = InMemory::default();
+        let outcome =
+            scan_profiles(profiles.clone(), &config, &options, Some(&mut reporter)).expect("scan");
+        assert_eq!(outcome.failures, 2);
+        assert_eq!(reporter.0.concat().len(), 2);
+
+        let outcome = scan_profiles(profiles, &config, &options, None).expect("scan");
+        assert_eq!(outcome.failures, 2);
+        let written: Vec<_> = fs::read_dir(dir.join("reports"))
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name() != STATE_FILE)
+            .collect();
+        assert!(written.is_empty(), "{written:?}");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Two profiles under `dir`, each with one bookmark on a closed local port.
+    fn unreachable_profiles(dir: &Path) -> Vec<BookmarkLocation> {
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}/gone", closed.local_addr().unwrap());
+        drop(closed);
+
+        ["Default", "Profile 1"]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                let directory = dir.join(name);
+                fs::create_dir_all(&directory).unwrap();
+                let file = directory.join("Bookmarks");
+                fs::write(
+                    &file,
+                    format!(
+                        r#"{{"roots":{{"bookmark_bar":{{"type":"folder","name":"Bar","children":[
+                        {{"type":"url","name":"{name}","url":"{url}/{idx}"}}]}}}}}}"#
+                    ),
+                )
+                .unwrap();
+                BookmarkLocation { directory, file }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_complete_scans_are_recorded_despite_a_malformed_state_file() {
+        let dir = temp_path("bookmark-checker-state");
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}/gone", closed.local_addr().unwrap());
+        drop(closed);
+        let directory = dir.join("Default");
+        fs::create_dir_all(&directory).unwrap();
+        let file = directory.join("Bookmarks");
+        fs::write(
+            &file,
+            format!(
+                r#"{{"roots":{{"bookmark_bar":{{"type":"folder","name":"Bar","children":[
+                {{"type":"url","name":"a","url":"{url}/a"}},
+                {{"type":"url","name":"b","url":"{url}/b"}}]}}}}}}"#
+            ),
+        )
+        .unwrap();
+        let location = BookmarkLocation { directory, file };
+        let mut config = RunConfig {
+            scan: true,
+            compact: true,
+            output_dir: Some(dir.join("reports")),
+            max_bookmarks: Some(1),
+            ..RunConfig::default()
+        };
+        fs::create_dir_all(dir.join("reports")).unwrap();
+        fs::write(state_path(&config), "last_run: [").unwrap();
+
+        let options = check_options(&config);
+        scan_profiles(vec![location.clone()], &config, &options, None).expect("partial scan");
+        let state = RunState::load(&state_path(&config)).expect("state rewritten");
+        assert_eq!(state.last_run(&location.directory), None);
+
+        config.max_bookmarks = None;
+        scan_profiles(vec![location.clone()], &config, &options, None).expect("full scan");
+        let state = RunState::load(&state_path(&config)).expect("state");
+        assert!(state.last_run(&location.directory).is_some());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn report_prefix_names_profile_reports_safely() {
+        let location = BookmarkLocation {
+            directory: PathBuf::from("/chrome/Profile 1"),
+            file: PathBuf::from("/chrome/Profile 1/Bookmarks"),
+        };
+        let config = RunConfig {
+            output_dir: Some(PathBuf::from("/reports")),
+            ..RunConfig::default()
+        };
+        assert_eq!(
+            profile_report_path(&config, &location),
+            Path::new("/reports/bookmark_failures-Profile_1.yml")
+        );
+
+        let config = RunConfig {
+            report_prefix: Some("nightly".into()),
+            ..config
+        };
+        assert_eq!(
+            profile_report_path(&config, &location),
+            Path::new("/reports/nightly-Profile_1.yml")
+        );
+        assert_eq!(file_name_token("Work/Old: 2"), "Work_Old__2");
+    }
+
+    #[test]
+    fn a_profile_picked_by_index_is_not_looked_up_again_by_name() {
+        let root = temp_path("bookmark-checker-index");
+        // Sorted, "default" comes second; by name it would resolve to "Default".
+        for name in ["Default", "default"] {
+            fs::create_dir_all(root.join(name)).unwrap();
+            fs::write(root.join(name).join("Bookmarks"), "{}").unwrap();
+        }
+        let config = RunConfig {
+            user_data_dir: Some(root.clone()),
+            profile_index: Some(1),
+            ..RunConfig::default()
+        };
+
+        let location = locate_single_profile(&config).expect("profile");
+        assert_eq!(location.directory, root.join("default"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn timeouts_reach_the_check_options() {
+        let config = RunConfig {
+            scan: true,
+            timeout_secs: Some(20),
+            connect_timeout_secs: Some(3),
+            ..RunConfig::default()
+        };
+        let options = check_options(&config);
+        assert_eq!(options.timeout, Duration::from_secs(20));
+        assert_eq!(options.connect_timeout, Some(Duration::from_secs(3)));
+
+        let options = check_options(&RunConfig::default());
+        assert_eq!(options.timeout, CheckOptions::default().timeout);
+        assert_eq!(options.connect_timeout, None);
+    }
+
+    #[test]
+    fn a_file_cut_off_mid_write_is_read_again() {
+        let path = temp_path("bookmark-checker-reread");
+        let complete = r#"{"roots":{"bookmark_bar":{"type":"folder","name":"Bar","children":[
+            {"type":"url","name":"One","url":"https://one.example"}]}}}"#;
+        fs::write(&path, &complete[..complete.len() / 2]).unwrap();
+        let writer = {
+            let path = path.clone();
+            thread::spawn(move || {
+                thread::sleep(REREAD_DELAY / 4);
+                fs::write(path, complete).unwrap();
+            })
+        };
+
+        let bookmarks = load_bookmarks_from(&path, parser::DEFAULT_MAX_DEPTH, RootScope::All, None);
+        writer.join().unwrap();
+
+        assert_eq!(bookmarks.expect("complete on the second read").len(), 1);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn validation_counts_bookmarks_and_rejects_bad_files() {
+        let path = temp_path("bookmark-checker-validate");
+
+        fs::write(
+            &path,
+            r#"{"roots":{"bookmark_bar":{"type":"folder","name":"Bar","children":[
+            {"type":"url","name":"One","url":"https://one.example"},
+            {"type":"url","name":"Two","url":"https://two.example"},
+            {"type":"folder","name":"Sub","children":[
+            {"type":"url","name":"Three","url":"https://three.example"}]}]}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            count_valid_bookmarks(&path, parser::DEFAULT_MAX_DEPTH, None).expect("valid"),
+            3
+        );
+        assert_eq!(count_valid_bookmarks(&path, 1, None).expect("valid"), 2);
+
+        fs::write(&path, r#"{"roots": {"#).unwrap();
+        assert!(matches!(
+            count_valid_bookmarks(&path, parser::DEFAULT_MAX_DEPTH, None),
+            Err(BookmarkError::TruncatedBookmarksFile(_, _))
+        ));
+
+        fs::write(&path, r#"{"roots": }"#).unwrap();
+        assert!(matches!(
+            count_valid_bookmarks(&path, parser::DEFAULT_MAX_DEPTH, None),
+            Err(BookmarkError::MalformedBookmarksFile(_, _, _))
+        ));
+
+        fs::write(&path, r#"{"roots":{}}"#).unwrap();
+        assert!(matches!(
+            count_valid_bookmarks(&path, parser::DEFAULT_MAX_DEPTH, None),
+            Err(BookmarkError::EmptyBookmarks(_))
+        ));
+
+        fs::write(&path, "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n<DL><p></DL>").unwrap();
+        assert!(matches!(
+            count_valid_bookmarks(&path, parser::DEFAULT_MAX_DEPTH, None),
+            Err(BookmarkError::HtmlBookmarksExport(_))
+        ));
+
+        fs::write(&path, "roots: {}").unwrap();
+        assert!(matches!(
+            count_valid_bookmarks(&path, parser::DEFAULT_MAX_DEPTH, None),
+            Err(BookmarkError::UnrecognizedBookmarksFile(_))
+        ));
+        // A forced format skips the sniffing.
+        assert!(matches!(
+            count_valid_bookmarks(&path, parser::DEFAULT_MAX_DEPTH, Some(InputFormat::Json)),
+            Err(BookmarkError::MalformedBookmarksFile(_, _, _))
+        ));
+        fs::write(&path, r#"{"roots":{}}"#).unwrap();
+        assert!(matches!(
+            count_valid_bookmarks(&path, parser::DEFAULT_MAX_DEPTH, Some(InputFormat::Html)),
+            Err(BookmarkError::HtmlBookmarksExport(_))
+        ));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn no_write_prints_failures_without_creating_files() {
+        let dir = temp_path("bookmark-checker-no-write");
+        let config = RunConfig {
+            output_dir: Some(dir.clone()),
+            report_empty: true,
+            no_write: true,
+            ..RunConfig::default()
+        };
+        let failure = LinkFailure {
+            bookmark: sample_bookmarks().remove(0),
+            reason: FailureReason::Http(404),
+            kind: FailureKind::NotFound,
+            redirect_chain: Vec::new(),
+        };
+
+        let results = ScanResults {
+            failures: vec![failure.clone()],
+            ..ScanResults::default()
+        };
+        write_failures(&results, &config, None).expect("print");
+        write_failures(&ScanResults::default(), &config, None).expect("print");
+
+        assert!(!dir.exists());
+        assert_eq!(
+            describe_failure(&failure, false),
+            "- missing (https://missing.example): HTTP 404 Not Found"
+        );
+    }
+
+    #[test]
+    fn failures_are_colored_by_kind_only_when_enabled() {
+        let failure = |kind| LinkFailure {
+            bookmark: sample_bookmarks().remove(0),
+            reason: FailureReason::Http(404),
+            kind,
+            redirect_chain: Vec::new(),
+        };
+
+        let plain = describe_failure(&failure(FailureKind::NotFound), false);
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(
+            describe_failure(&failure(FailureKind::NotFound), true),
+            format!("\x1b[31m{plain}\x1b[0m")
+        );
+        assert!(
+            describe_failure(&failure(FailureKind::Unauthorized), true).starts_with("\x1b[33m")
+        );
+        assert!(describe_failure(&failure(FailureKind::Connection), true).starts_with("\x1b[35m"));
+        assert!(!describe_failure(&failure(FailureKind::Parked), true).contains('\x1b'));
+    }
+
+    #[test]
+    fn no_write_refuses_actions_that_modify_files() {
+        let config = RunConfig {
+            scan: false,
+            clean: true,
+            no_write: true,
+            ..RunConfig::default()
+        };
+
+        let err = run_with_config(config, None).unwrap_err();
+        assert!(matches!(err, BookmarkError::InvalidConfig(_)));
+        assert_eq!(
+            err.to_string(),
+            "--no-write cannot be combined with --clean"
+        );
+    }
+
+    #[test]
+    fn invalid_configs_are_rejected_before_running() {
+        let config = RunConfig {
+            scan: false,
+            list_profiles: true,
+            max_bookmarks: Some(5),
+            ..RunConfig::default()
+        };
+
+        let err = run_with_config(config, None).unwrap_err();
+        assert!(matches!(err, BookmarkError::InvalidConfig(_)));
+        assert_eq!(err.to_string(), "--max-bookmarks requires --scan");
+    }
+
+    #[test]
+    fn report_empty_writes_a_report_without_failures() {
+        let dir = temp_path("bookmark-checker-empty");
+        let mut config = RunConfig {
+            output_dir: Some(dir.clone()),
+            ..RunConfig::default()
+        };
+
+        write_failures(&ScanResults::default(), &config, None).expect("write");
+        assert!(!dir.join(FAILURE_REPORT_FILE).exists());
+
+        config.report_empty = true;
+        write_failures(&ScanResults::default(), &config, None).expect("write");
+        let report = fs::read_to_string(dir.join(FAILURE_REPORT_FILE)).unwrap();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&report).expect("valid YAML");
+        for section in ["not_found", "unauthorized", "connection_errors"] {
+            assert_eq!(
+                parsed[section].as_sequence().map(Vec::len),
+                Some(0),
+                "{section}"
+            );
+        }
+        assert!(
+            cleaner::load_report_targets(&dir.join(FAILURE_REPORT_FILE))
+                .expect("load")
+                .is_empty()
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn empty_bookmarks_file_gets_its_own_error() {
+        let path = temp_path("bookmark-checker-empty-file");
+
+        for contents in ["", "\u{feff}", " \n"] {
+            fs::write(&path, contents).unwrap();
+            let err = load_bookmarks_from(&path, parser::DEFAULT_MAX_DEPTH, RootScope::All, None)
+                .unwrap_err();
+            assert!(
+                matches!(&err, BookmarkError::BlankBookmarksFile(empty) if *empty == path),
+                "{contents:?}: {err}"
+            );
+            let message = err.to_string();
+            assert!(message.contains(&path.display().to_string()), "{message}");
+            assert!(message.contains("Chrome may be writing it"), "{message}");
+        }
+
+        fs::write(&path, r#"{"roots": {"bookmark_bar": {"#).unwrap();
+        let message = load_bookmarks_from(&path, parser::DEFAULT_MAX_DEPTH, RootScope::All, None)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains(&path.display().to_string()), "{message}");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn chrome_backup_is_read_when_the_live_file_is_missing_or_corrupt() {
+        let root = temp_path("bookmark-checker-chrome-bak");
+        let profile = root.join("Default");
+        fs::create_dir_all(&profile).unwrap();
+        let config = RunConfig {
+            use_chrome_backup: true,
+            user_data_dir: Some(root.clone()),
+            ..RunConfig::default()
+        };
+
+        let err = load_profile(locate_single_profile(&config).unwrap(), &config).unwrap_err();
+        assert!(
+            matches!(&err, BookmarkError::MissingChromeBackup(path) if *path == profile.join("Bookmarks.bak")),
+            "{err}"
+        );
+
+        fs::write(
+            profile.join("Bookmarks.bak"),
+            r#"{"roots":{"bookmark_bar":{"type":"folder","name":"Bar","children":[
+                {"type":"url","name":"Saved","url":"https://example.com"}]}}}"#,
+        )
+        .unwrap();
+        for live in [None, Some(r#"{"roots": {"bookmark_bar": {"#)] {
+            if let Some(contents) = live {
+                fs::write(profile.join("Bookmarks"), contents).unwrap();
+            }
+            let (location, bookmarks) =
+                load_profile(locate_single_profile(&config).unwrap(), &config).unwrap();
+            assert_eq!(location.file, profile.join("Bookmarks.bak"));
+            assert_eq!(location.directory, profile);
+            assert_eq!(bookmarks.len(), 1);
+            assert_eq!(bookmarks[0].name, "Saved");
+        }
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn malformed_bookmarks_report_line_column_and_snippet() {
+        let path = temp_path("bookmark-checker-malformed");
+        fs::write(
+            &path,
+            "{\n  \"roots\": {\n    \"bookmark_bar\": {\"children\": [,]}\n  }\n}\n",
+        )
+        .unwrap();
+
+        let message = load_bookmarks_from(&path, parser::DEFAULT_MAX_DEPTH, RootScope::All, None)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("line 3"), "{message}");
+        assert!(message.contains("column"), "{message}");
+        assert!(message.contains(r#"{"children": [,]}`"#), "{message}");
+        assert!(message.contains(&path.display().to_string()), "{message}");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn byte_order_mark_is_ignored_when_loading() {
+        let path = temp_path("bookmark-checker-bom");
+        let data = r#"{"roots":{"bookmark_bar":{"type":"folder","name":"Bar","children":[
+            {"type":"url","name":"Example","url":"https://example.com"}]}}}"#;
+        fs::write(&path, format!("\u{feff}{data}")).unwrap();
+
+        let bookmarks = load_bookmarks_from(&path, parser::DEFAULT_MAX_DEPTH, RootScope::All, None)
+            .expect("BOM-prefixed file should load");
+        assert_eq!(
+            bookmarks,
+            parser::parse_bookmarks(data, parser::DEFAULT_MAX_DEPTH, RootScope::All)
+                .expect("parse")
+                .bookmarks
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn custom_reporter_receives_failures_instead_of_the_file() {
+        let dir = temp_path("bookmark-checker-custom");
+        let config = RunConfig {
+            output_dir: Some(dir.clone()),
+            ..RunConfig::default()
+        };
+        let failures = sample_bookmarks()
+            .into_iter()
+            .take(2)
+            .map(|bookmark| LinkFailure {
+                bookmark,
+                reason: FailureReason::Http(404),
+                kind: FailureKind::NotFound,
+                redirect_chain: Vec::new(),
+            })
+            .collect();
+        let results = ScanResults {
+            failures,
+            ..ScanResults::default()
+        };
+
+        let mut reporter = InMemory::default();
+        write_failures(&results, &config, Some(&mut reporter)).expect("report");
+
+        assert_eq!(
+            reporter.0,
+            [vec!["https://missing.example", "https://fine.example"]]
+        );
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn group_by_host_orders_hosts_by_failure_count() {
+        let failures: Vec<LinkFailure> = [
+            "https://b.example/1",
+            "https://a.example/1",
+            "https://c.example:8443/1",
+            "https://c.example/2",
+            "not a url",
+        ]
+        .iter()
+        .map(|url| LinkFailure {
+            bookmark: Bookmark {
+                name: url.to_string(),
+                url: url.to_string(),
+                folder_path: Vec::new(),
+                date_added: None,
+            },
+            reason: FailureReason::Timeout,
+            kind: FailureKind::Connection,
+            redirect_chain: Vec::new(),
+        })
+        .collect();
+
+        let groups: Vec<(String, Vec<&str>)> = group_by_host(&failures)
+            .into_iter()
+            .map(|(host, entries)| {
+                let urls = entries
+                    .iter()
+                    .map(|failure| failure.bookmark.url.as_str())
+                    .collect();
+                (host, urls)
+            })
+            .collect();
+
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    "c.example".to_string(),
+                    vec!["https://c.example:8443/1", "https://c.example/2"]
+                ),
+                (UNKNOWN_HOST.to_string(), vec!["not a url"]),
+                ("a.example".to_string(), vec!["https://a.example/1"]),
+                ("b.example".to_string(), vec!["https://b.example/1"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn format_bytes_uses_decimal_units() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1_500), "1.5 KB");
+        assert_eq!(format_bytes(14_200_000), "14.2 MB");
+        assert_eq!(format_bytes(3_000_000_000), "3.0 GB");
+    }
+
+    #[test]
+    fn resume_skips_recorded_failures() {
+        let (report_path, recorded) = load_sample_report();
+        let mut bookmarks = sample_bookmarks();
+
+        filter_recorded(&mut bookmarks, &recorded, false);
+        let urls: Vec<&str> = bookmarks.iter().map(|b| b.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://fine.example"]);
+
+        fs::remove_file(report_path).unwrap();
+    }
+
+    #[test]
+    fn resume_keeps_the_failures_of_earlier_runs() {
+        let dir = temp_path("bookmark-checker-resume");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(FAILURE_REPORT_FILE),
+            "not_found:\n  - name: missing\n    url: https://missing.example\n",
+        )
+        .unwrap();
+        let config = RunConfig {
+            output_dir: Some(dir.clone()),
+            resume: true,
+            compact: true,
+            ..RunConfig::default()
+        };
+        let results = ScanResults {
+            failures: vec![LinkFailure {
+                bookmark: sample_bookmarks().remove(2),
+                reason: FailureReason::Http(401),
+                kind: FailureKind::Unauthorized,
+                redirect_chain: Vec::new(),
+            }],
+            ..ScanResults::default()
+        };
+
+        write_failures(&results, &config, None).expect("write");
+
+        let recorded = cleaner::load_report_targets(&dir.join(FAILURE_REPORT_FILE)).expect("load");
+        let expected: HashSet<String> = ["https://missing.example", "https://private.example"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(recorded, expected);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn recheck_keeps_only_recorded_failures() {
+        let (report_path, recorded) = load_sample_report();
+        let mut bookmarks = sample_bookmarks();
+
+        filter_recorded(&mut bookmarks, &recorded, true);
+        let urls: Vec<&str> = bookmarks.iter().map(|b| b.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec!["https://missing.example", "https://private.example"]
+        );
+
+        fs::remove_file(report_path).unwrap();
+    }
+
+    #[test]
+    fn ignore_list_filters_bookmarks_before_scanning() {
+        let mut bookmarks = sample_bookmarks();
+        let ignore = IgnoreList::parse("# noisy hosts\nhttps://private.*\n");
+
+        let ignored = apply_ignore(&mut bookmarks, &ignore);
+        assert_eq!(ignored, 1);
+        let urls: Vec<&str> = bookmarks.iter().map(|b| b.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec!["https://missing.example", "https://fine.example"]
+        );
+    }
+
+    #[test]
+    fn plan_reflects_skip_patterns_and_limits() {
+        let dir = temp_path("bookmark-checker-plan");
+        fs::create_dir_all(&dir).unwrap();
+        let ignore_file = dir.join(".bookmarkignore");
+        fs::write(&ignore_file, "https://missing.*\n").unwrap();
+        let location = BookmarkLocation {
+            directory: dir.clone(),
+            file: dir.join("Bookmarks"),
+        };
+
+        let mut bookmarks = sample_bookmarks();
+        bookmarks.push(Bookmark {
+            name: "settings".into(),
+            url: "chrome://settings".into(),
+            folder_path: Vec::new(),
+            date_added: None,
+        });
+        let config = RunConfig {
+            scan: true,
+            plan: true,
+            ignore_file: Some(ignore_file),
+            max_bookmarks: Some(1),
+            compact: true,
+            ..RunConfig::default()
+        };
+
+        let plan = plan_scan(&location, bookmarks, &config, &dir.join("none.yml"), None)
+            .unwrap()
+            .expect("a bookmark should be left");
+        let urls: Vec<&str> = plan.bookmarks.iter().map(|b| b.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://fine.example"]);
+        assert_eq!(plan.total_found, 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn recent_runs_block_a_scan_unless_forced() {
+        let now = Utc::now();
+        let mut state = RunState::default();
+        state.record(
+            Path::new("/profiles/Default"),
+            now - chrono::Duration::seconds(30),
+        );
+        let config = RunConfig {
+            scan: true,
+            min_interval_secs: Some(60),
+            ..RunConfig::default()
+        };
+
+        let err = ensure_interval_elapsed(&config, &state, now).unwrap_err();
+        assert!(matches!(err, BookmarkError::RunTooSoon(_, 60)));
+
+        let forced = RunConfig {
+            force: true,
+            ..config.clone()
+        };
+        assert!(ensure_interval_elapsed(&forced, &state, now).is_ok());
+        let later = now + chrono::Duration::seconds(30);
+        assert!(ensure_interval_elapsed(&config, &state, later).is_ok());
+        assert!(ensure_interval_elapsed(&config, &RunState::default(), now).is_ok());
+    }
+
+    #[test]
+    fn preflight_passes_when_any_endpoint_answers() {
+        struct Reachable(&'static str);
+
+        impl UrlProbe for Reachable {
+            fn probe(&self, url: &str) -> ProbeOutcome {
+                if url == self.0 {
+                    ProbeOutcome::Response {
+                        status: StatusCode::NO_CONTENT,
+                        bytes: 0,
+                        content_type: None,
+                        landing: None,
+                        certificate_expiry: None,
+                        final_url: None,
+                    }
+                } else {
+                    ProbeOutcome::Failed(FailureReason::Dns)
+                }
+            }
+        }
+
+        let urls = ["https://first.example", "https://second.example"];
+        assert!(network_reachable(
+            &Reachable("https://second.example"),
+            &urls
+        ));
+        assert!(!network_reachable(
+            &Reachable("https://elsewhere.example"),
+            &urls
+        ));
+    }
+
+    #[test]
+    fn recheck_separates_fixed_links_from_broken_ones() {
+        struct StillMissing;
+
+        impl UrlProbe for StillMissing {
+            fn probe(&self, url: &str) -> ProbeOutcome {
+                let status = if url == "https://missing.example" {
+                    StatusCode::NOT_FOUND
+                } else {
+                    StatusCode::OK
+                };
+                ProbeOutcome::Response {
+                    status,
+                    bytes: 0,
+                    content_type: None,
+                    landing: None,
+                    certificate_expiry: None,
+                    final_url: None,
+                }
+            }
+        }
+
+        let (report_path, _) = load_sample_report();
+        let bookmarks = cleaner::load_report_bookmarks(&report_path).expect("load");
+
+        let recheck = recheck_bookmarks(bookmarks, &StillMissing, &CheckOptions::default());
+        let healthy: Vec<&str> = recheck.healthy.iter().map(|b| b.url.as_str()).collect();
+        assert_eq!(healthy, vec!["https://private.example"]);
+        assert_eq!(recheck.still_broken.len(), 1);
+        assert_eq!(
+            recheck.still_broken[0].bookmark.url,
+            "https://missing.example"
+        );
+
+        fs::remove_file(report_path).unwrap();
+    }
+
+    fn sample_bookmarks() -> Vec<Bookmark> {
+        ["missing", "fine", "private"]
+            .iter()
+            .map(|name| Bookmark {
+                name: name.to_string(),
+                url: format!("https://{name}.example"),
+                folder_path: Vec::new(),
+                date_added: None,
+            })
+            .collect()
+    }
+
+    fn load_sample_report() -> (std::path::PathBuf, HashSet<String>) {
+        let path = temp_path("bookmark-checker-prior").with_extension("yml");
+        fs::write(
+            &path,
+            "not_found:\n  - name: missing\n    url: https://missing.example\n\
+             unauthorized:\n  - name: private\n    url: https://private.example\n",
+        )
+        .unwrap();
+
+        let recorded = cleaner::load_report_targets(&path).expect("report should load");
+        (path, recorded)
+    }
 }
@@ -0,0 +1,121 @@
+use crate::model::Bookmark;
+
+/// SplitMix64: a tiny, seedable generator that is plenty for spot-check sampling
+/// and shuffling.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+/// Keeps `count` bookmarks chosen uniformly without replacement, preserving their
+/// original order. Returns the total number of bookmarks before sampling.
+pub(crate) fn sample_bookmarks(bookmarks: &mut Vec<Bookmark>, count: usize, seed: u64) -> usize {
+    let total = bookmarks.len();
+    if count >= total {
+        return total;
+    }
+
+    let mut rng = SplitMix64(seed);
+    let mut indices: Vec<usize> = (0..total).collect();
+    for position in 0..count {
+        let pick = position + rng.below(total - position);
+        indices.swap(position, pick);
+    }
+
+    let mut keep = vec![false; total];
+    for &index in &indices[..count] {
+        keep[index] = true;
+    }
+
+    let mut flags = keep.into_iter();
+    bookmarks.retain(|_| flags.next().unwrap_or(false));
+    total
+}
+
+/// Puts the bookmarks in a random order (Fisher-Yates), the same one for the same
+/// seed, so links from one site stop arriving back to back.
+pub(crate) fn shuffle_bookmarks(bookmarks: &mut [Bookmark], seed: u64) {
+    let mut rng = SplitMix64(seed);
+    for position in (1..bookmarks.len()).rev() {
+        bookmarks.swap(position, rng.below(position + 1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmarks(count: usize) -> Vec<Bookmark> {
+        (0..count)
+            .map(|idx| Bookmark {
+                name: format!("Bookmark {idx}"),
+                url: format!("https://example.com/{idx}"),
+                folder_path: Vec::new(),
+                date_added: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_yields_same_selection() {
+        let mut first = bookmarks(50);
+        let mut second = bookmarks(50);
+
+        assert_eq!(sample_bookmarks(&mut first, 5, 42), 50);
+        sample_bookmarks(&mut second, 5, 42);
+
+        assert_eq!(first.len(), 5);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn different_seeds_usually_differ() {
+        let mut first = bookmarks(50);
+        let mut second = bookmarks(50);
+
+        sample_bookmarks(&mut first, 5, 1);
+        sample_bookmarks(&mut second, 5, 2);
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn seeded_shuffle_is_deterministic() {
+        let mut first = bookmarks(50);
+        let mut second = bookmarks(50);
+
+        shuffle_bookmarks(&mut first, 42);
+        shuffle_bookmarks(&mut second, 42);
+
+        assert_eq!(first, second);
+        assert_ne!(first, bookmarks(50));
+
+        let mut sorted = first.clone();
+        sorted.sort_by_key(|bookmark| bookmark.url.clone());
+        let mut expected = bookmarks(50);
+        expected.sort_by_key(|bookmark| bookmark.url.clone());
+        assert_eq!(sorted, expected);
+
+        let mut other = bookmarks(50);
+        shuffle_bookmarks(&mut other, 43);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn sample_larger_than_input_keeps_everything() {
+        let mut all = bookmarks(3);
+        assert_eq!(sample_bookmarks(&mut all, 10, 7), 3);
+        assert_eq!(all, bookmarks(3));
+    }
+}
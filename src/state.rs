@@ -0,0 +1,122 @@
+use crate::atomic::write_atomic;
+use crate::model::{Bookmark, BookmarkError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Remembers when each profile was last scanned, for `--since-last-run`.
+pub(crate) const STATE_FILE: &str = ".bookmark_checker_state.yml";
+
+/// Last scan time per profile, keyed by the profile directory.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RunState {
+    #[serde(default)]
+    last_run: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl RunState {
+    /// Reads the state file; a missing file means no profile was scanned yet.
+    pub(crate) fn load(path: &Path) -> Result<Self, BookmarkError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_yaml::from_str(&contents).map_err(BookmarkError::StateFile),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> Result<(), BookmarkError> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomic(path, |writer| {
+            serde_yaml::to_writer(writer, self).map_err(BookmarkError::StateFile)
+        })
+    }
+
+    pub(crate) fn last_run(&self, profile: &Path) -> Option<DateTime<Utc>> {
+        self.last_run.get(&key(profile)).copied()
+    }
+
+    /// The most recent scan of any profile.
+    pub(crate) fn latest_run(&self) -> Option<DateTime<Utc>> {
+        self.last_run.values().max().copied()
+    }
+
+    pub(crate) fn record(&mut self, profile: &Path, at: DateTime<Utc>) {
+        self.last_run.insert(key(profile), at);
+    }
+}
+
+/// Whether `bookmark` should be checked on a run limited to bookmarks added after
+/// `since`. Bookmarks without a recorded date are always checked.
+pub(crate) fn added_since(bookmark: &Bookmark, since: DateTime<Utc>) -> bool {
+    bookmark.date_added.is_none_or(|added| added > since)
+}
+
+fn key(profile: &Path) -> String {
+    profile.display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+    use chrono::TimeZone;
+
+    fn bookmark(date_added: Option<DateTime<Utc>>) -> Bookmark {
+        Bookmark {
+            name: "Example".into(),
+            url: "https://example.com".into(),
+            folder_path: Vec::new(),
+            date_added,
+        }
+    }
+
+    #[test]
+    fn state_round_trips_through_the_file() {
+        let dir = temp_dir("bookmark-checker-state");
+        let path = dir.join(STATE_FILE);
+        let profile = Path::new("/home/user/.config/google-chrome/Default");
+        let at = Utc.with_ymd_and_hms(2024, 5, 1, 8, 30, 0).unwrap();
+
+        assert_eq!(RunState::load(&path).expect("missing"), RunState::default());
+
+        let mut state = RunState::default();
+        state.record(profile, at);
+        state.save(&path).expect("save");
+
+        let loaded = RunState::load(&path).expect("load");
+        assert_eq!(loaded.last_run(profile), Some(at));
+        assert_eq!(loaded.last_run(Path::new("/elsewhere")), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn malformed_state_is_reported() {
+        let dir = temp_dir("bookmark-checker-state");
+        let path = dir.join(STATE_FILE);
+        fs::write(&path, "last_run: [").unwrap();
+
+        assert!(matches!(
+            RunState::load(&path),
+            Err(BookmarkError::StateFile(_))
+        ));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn only_newer_or_undated_bookmarks_are_kept() {
+        let last_run = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+        let day = chrono::Duration::days(1);
+
+        assert!(added_since(&bookmark(Some(last_run + day)), last_run));
+        assert!(!added_since(&bookmark(Some(last_run - day)), last_run));
+        assert!(!added_since(&bookmark(Some(last_run)), last_run));
+        assert!(added_since(&bookmark(None), last_run));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::model::Bookmark;
+use std::collections::BTreeMap;
+
+pub(crate) const UNFILED_FOLDER: &str = "(no folder)";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct FolderCounts {
+    pub(crate) folders: BTreeMap<String, usize>,
+    pub(crate) total: usize,
+}
+
+pub(crate) fn count_by_top_level_folder(bookmarks: &[Bookmark]) -> FolderCounts {
+    let mut counts = FolderCounts::default();
+
+    for bookmark in bookmarks {
+        let folder = bookmark
+            .folder_path
+            .first()
+            .map(String::as_str)
+            .unwrap_or(UNFILED_FOLDER);
+        *counts.folders.entry(folder.to_string()).or_default() += 1;
+        counts.total += 1;
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(url: &str, folders: &[&str]) -> Bookmark {
+        Bookmark {
+            name: url.into(),
+            url: url.into(),
+            folder_path: folders.iter().map(|folder| folder.to_string()).collect(),
+            date_added: None,
+        }
+    }
+
+    #[test]
+    fn counts_nested_bookmarks_under_top_level_folder() {
+        let bookmarks = vec![
+            bookmark("https://a", &["Bookmarks bar"]),
+            bookmark("https://b", &["Bookmarks bar", "Reading"]),
+            bookmark("https://c", &["Bookmarks bar", "Reading", "Later"]),
+            bookmark("https://d", &["Other bookmarks"]),
+            bookmark("https://e", &[]),
+        ];
+
+        let counts = count_by_top_level_folder(&bookmarks);
+        assert_eq!(counts.total, 5);
+        assert_eq!(counts.folders["Bookmarks bar"], 3);
+        assert_eq!(counts.folders["Other bookmarks"], 1);
+        assert_eq!(counts.folders[UNFILED_FOLDER], 1);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::checker::FailureKind;
+use crate::model::{Bookmark, BookmarkError};
+use reqwest::StatusCode;
+use serde_json::json;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Streams one JSON object per checked bookmark as results complete.
+pub(crate) struct ResultStream {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl ResultStream {
+    /// Opens `target` for streaming; `-` writes to stdout.
+    pub(crate) fn create(target: &Path) -> Result<Self, BookmarkError> {
+        let writer: Box<dyn Write + Send> = if target == Path::new("-") {
+            Box::new(io::stdout())
+        } else {
+            Box::new(BufWriter::new(File::create(target)?))
+        };
+
+        Ok(Self {
+            writer: Mutex::new(writer),
+        })
+    }
+
+    pub(crate) fn record(
+        &self,
+        bookmark: &Bookmark,
+        status: Option<StatusCode>,
+        content_type: Option<&str>,
+        kind: Option<FailureKind>,
+    ) {
+        let line = json!({
+            "url": bookmark.url,
+            "status": status.map(|status| status.as_u16()),
+            "content_type": content_type,
+            "kind": kind.map_or("ok", FailureKind::label),
+        });
+
+        if let Ok(mut writer) = self.writer.lock() {
+            // Streaming is best-effort; a broken pipe must not abort the scan.
+            let _ = writeln!(writer, "{line}").and_then(|()| writer.flush());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_path;
+    use std::fs;
+
+    #[test]
+    fn records_one_json_line_per_result() {
+        let path = temp_path("bookmark-checker-stream").with_extension("jsonl");
+
+        let stream = ResultStream::create(&path).expect("create");
+        let bookmark = Bookmark {
+            name: "Missing".into(),
+            url: "https://example.com/missing".into(),
+            folder_path: Vec::new(),
+            date_added: None,
+        };
+        stream.record(
+            &bookmark,
+            Some(StatusCode::NOT_FOUND),
+            Some("text/html"),
+            Some(FailureKind::NotFound),
+        );
+        stream.record(&bookmark, None, None, Some(FailureKind::Connection));
+        drop(stream);
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("valid json"))
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["status"], 404);
+        assert_eq!(lines[0]["content_type"], "text/html");
+        assert_eq!(lines[0]["kind"], "not_found");
+        assert_eq!(lines[1]["status"], serde_json::Value::Null);
+        assert_eq!(lines[1]["kind"], "connection_errors");
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,64 @@
+use reqwest::Url;
+
+/// Query parameters that only identify where a click came from.
+const TRACKING_PARAMS: [&str; 2] = ["fbclid", "gclid"];
+const TRACKING_PREFIX: &str = "utm_";
+
+/// Returns `url` without tracking query parameters, or `None` when it has none (or
+/// does not parse). Remaining parameters keep their order and original encoding.
+pub(crate) fn strip_tracking_params(url: &str) -> Option<String> {
+    let mut parsed = Url::parse(url).ok()?;
+    let query = parsed.query()?;
+
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| !is_tracking_param(pair))
+        .collect();
+    if kept.len() == query.split('&').count() {
+        return None;
+    }
+
+    let kept = kept.join("&");
+    parsed.set_query((!kept.is_empty()).then_some(kept.as_str()));
+    Some(parsed.into())
+}
+
+fn is_tracking_param(pair: &str) -> bool {
+    let key = pair
+        .split('=')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    key.starts_with(TRACKING_PREFIX) || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_utm_params_and_keeps_meaningful_ones() {
+        assert_eq!(
+            strip_tracking_params(
+                "https://example.com/article?id=42&utm_source=news&q=a%20b&UTM_Medium=mail#top"
+            )
+            .as_deref(),
+            Some("https://example.com/article?id=42&q=a%20b#top")
+        );
+    }
+
+    #[test]
+    fn drops_the_query_when_only_trackers_remain() {
+        assert_eq!(
+            strip_tracking_params("https://example.com/?fbclid=abc&gclid=def").as_deref(),
+            Some("https://example.com/")
+        );
+    }
+
+    #[test]
+    fn leaves_clean_and_unparseable_urls_alone() {
+        assert_eq!(strip_tracking_params("https://example.com/?page=2"), None);
+        assert_eq!(strip_tracking_params("https://example.com/"), None);
+        assert_eq!(strip_tracking_params("not a url?utm_source=x"), None);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::checker::LinkFailure;
+use crate::model::BookmarkError;
+use crate::report::Reporter;
+use reqwest::blocking::Client;
+use reqwest::header::CONTENT_TYPE;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// How many failures are listed before the message is cut short.
+const TOP_ENTRIES: usize = 10;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Posts a short failure summary to a Slack or Discord incoming webhook.
+pub struct WebhookReporter {
+    client: Client,
+    url: String,
+}
+
+impl WebhookReporter {
+    pub fn new(url: impl Into<String>) -> Result<Self, BookmarkError> {
+        Ok(Self {
+            client: Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .map_err(BookmarkError::HttpClientBuild)?,
+            url: url.into(),
+        })
+    }
+}
+
+impl Reporter for WebhookReporter {
+    fn report(&mut self, failures: &[LinkFailure]) -> Result<(), BookmarkError> {
+        self.client
+            .post(&self.url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(payload(failures).to_string())
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(BookmarkError::WebhookDelivery)
+    }
+}
+
+/// Slack reads `text` and Discord reads `content`; each ignores the other's field.
+fn payload(failures: &[LinkFailure]) -> Value {
+    let mut lines = vec![format!("Found {} dead bookmarks", failures.len())];
+    lines.extend(
+        failures
+            .iter()
+            .take(TOP_ENTRIES)
+            .map(|failure| format!("• {} ({})", failure.bookmark.url, failure.reason)),
+    );
+    if failures.len() > TOP_ENTRIES {
+        lines.push(format!("…and {} more", failures.len() - TOP_ENTRIES));
+    }
+
+    let message = lines.join("\n");
+    json!({ "text": message, "content": message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::{FailureKind, FailureReason};
+    use crate::model::Bookmark;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    fn failure(url: &str) -> LinkFailure {
+        LinkFailure {
+            bookmark: Bookmark {
+                name: url.into(),
+                url: url.into(),
+                folder_path: Vec::new(),
+                date_added: None,
+            },
+            reason: FailureReason::Http(404),
+            kind: FailureKind::NotFound,
+            redirect_chain: Vec::new(),
+        }
+    }
+
+    /// Accepts one request, answers `status`, and hands back the request body.
+    fn mock_webhook(status: &'static str) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("accept");
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).expect("read header");
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    content_length = value.trim().parse().expect("length");
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).expect("read body");
+            let _ = write!(
+                reader.get_mut(),
+                "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            );
+            let _ = sender.send(String::from_utf8(body).expect("utf-8"));
+        });
+
+        (url, receiver)
+    }
+
+    #[test]
+    fn posts_failure_count_and_top_entries() {
+        let (url, received) = mock_webhook("200 OK");
+        let failures = [
+            failure("https://one.example"),
+            failure("https://two.example"),
+        ];
+
+        WebhookReporter::new(url)
+            .expect("client")
+            .report(&failures)
+            .expect("delivered");
+
+        let body: Value = serde_json::from_str(&received.recv().unwrap()).expect("json");
+        let text = body["text"].as_str().expect("text");
+        assert!(text.starts_with("Found 2 dead bookmarks"));
+        assert!(text.contains("https://two.example (HTTP 404 Not Found)"));
+        assert_eq!(body["content"], body["text"]);
+    }
+
+    #[test]
+    fn long_lists_are_truncated() {
+        let failures: Vec<LinkFailure> = (0..TOP_ENTRIES + 3)
+            .map(|idx| failure(&format!("https://{idx}.example")))
+            .collect();
+
+        let text = payload(&failures)["text"].as_str().unwrap().to_string();
+        assert_eq!(text.lines().count(), TOP_ENTRIES + 2);
+        assert!(text.ends_with("…and 3 more"));
+    }
+
+    #[test]
+    fn rejected_delivery_is_an_error() {
+        let (url, _received) = mock_webhook("500 Internal Server Error");
+
+        let err = WebhookReporter::new(url)
+            .expect("client")
+            .report(&[failure("https://one.example")])
+            .expect_err("500 should fail");
+        assert!(matches!(err, BookmarkError::WebhookDelivery(_)));
+    }
+}
@@ -0,0 +1,46 @@
+use std::fs;
+use std::net::TcpListener;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[test]
+fn stream_to_stdout_prints_only_json_lines() {
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    let dir = std::env::temp_dir().join(format!("bookmark-checker-cli-{seed}"));
+    let profile = dir.join("Default");
+    fs::create_dir_all(&profile).unwrap();
+    let closed = TcpListener::bind("127.0.0.1:0").expect("bind");
+    let url = format!("http://{}/gone", closed.local_addr().unwrap());
+    drop(closed);
+    fs::write(
+        profile.join("Bookmarks"),
+        format!(
+            r#"{{"roots":{{"bookmark_bar":{{"type":"folder","name":"Bar","children":[
+            {{"type":"url","name":"a","url":"{url}/a"}},
+            {{"type":"url","name":"b","url":"{url}/b"}}]}}}}}}"#
+        ),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bookmark-checker"))
+        .arg("--user-data-dir")
+        .arg(&dir)
+        .args(["--scan", "--no-preflight", "--no-write", "--stream", "-"])
+        .env("HOME", &dir)
+        .output()
+        .expect("run bookmark-checker");
+    fs::remove_dir_all(&dir).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "stdout: {stdout}");
+    for line in lines {
+        assert!(
+            serde_json::from_str::<serde_json::Value>(line).is_ok(),
+            "not a JSON line: {line}"
+        );
+    }
+}